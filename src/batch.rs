@@ -0,0 +1,316 @@
+//! Solves one day's puzzle for every input file in a directory, in parallel.
+//!
+//! This grades many inputs, such as those of several participants, in a single run. The answers
+//! can be checked against a manifest in the format of `answers/dayNN.toml` (see
+//! [`regression`](crate::regression)), with each path relative to the directory.
+
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use color_eyre::{eyre::WrapErr, Result};
+use rayon::prelude::*;
+
+use crate::{
+    bench::Short,
+    params::{self, Overrides},
+    parsing,
+    regression::{indent, Manifest, Outcome},
+    Answers, Runner,
+};
+
+/// The outcome of solving one input file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileReport {
+    /// The input file, relative to the directory.
+    pub path: PathBuf,
+    /// The answers and the time taken to solve both parts, or why the input couldn't be solved.
+    pub solved: Result<(Answers, Duration), String>,
+    /// Whether the manifest lists answers for the file.
+    pub listed: bool,
+    pub part1: Outcome,
+    pub part2: Outcome,
+}
+
+/// The outcomes of solving every input file in a directory.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Report {
+    pub day: u8,
+    /// The parameters overridden for the run.
+    pub params: Overrides,
+    pub files: Vec<FileReport>,
+    /// Whether the answers were checked against a manifest.
+    pub checked: bool,
+}
+
+impl Report {
+    /// Whether every input was solved, and every answer checked matched.
+    pub fn passed(&self) -> bool {
+        self.failures() == 0
+    }
+
+    /// The number of inputs which couldn't be solved or had an answer which didn't match.
+    pub fn failures(&self) -> usize {
+        self.files
+            .iter()
+            .filter(|f| f.part1.is_failure() || f.part2.is_failure())
+            .count()
+    }
+}
+
+/// Solves every file in `dir`, ignoring subdirectories and hidden files, spread across threads.
+///
+/// If a manifest is given, the answers are compared against it, and any input it lists which
+/// isn't in the directory counts as a failure.
+pub fn run(solution: &dyn Runner, dir: &Path, manifest: Option<&Manifest>) -> Result<Report> {
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(dir).wrap_err_with(|| format!("reading {}", dir.display()))? {
+        let entry = entry?;
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        if entry.file_type()?.is_file() && !hidden {
+            paths.push(PathBuf::from(entry.file_name()));
+        }
+    }
+    paths.sort_unstable();
+
+    let mut files = paths
+        .into_par_iter()
+        .map(|path| solve_file(solution, dir, path, manifest))
+        .collect::<Vec<_>>();
+
+    if let Some(manifest) = manifest {
+        for case in &manifest.inputs {
+            if !files.iter().any(|f| f.path == case.path) {
+                let failed = Outcome::Failed("listed in the manifest but not found".to_owned());
+                files.push(FileReport {
+                    path: case.path.clone(),
+                    solved: Err("not found".to_owned()),
+                    listed: true,
+                    part1: failed.clone(),
+                    part2: failed,
+                });
+            }
+        }
+    }
+
+    Ok(Report {
+        day: solution.day(),
+        params: solution.overrides(),
+        files,
+        checked: manifest.is_some(),
+    })
+}
+
+fn solve_file(
+    solution: &dyn Runner,
+    dir: &Path,
+    path: PathBuf,
+    manifest: Option<&Manifest>,
+) -> FileReport {
+    let solved = fs::read_to_string(dir.join(&path))
+        .wrap_err("reading the input")
+        .and_then(|input| solution.run_timed(&input))
+        .map(|(answers, timings)| (answers, timings.total()))
+        .map_err(|e| format!("{:#}", parsing::name_source(e, path.display())));
+
+    let case = manifest.and_then(|m| m.inputs.iter().find(|case| case.path == path));
+    let (part1, part2) = match &solved {
+        Ok((answers, _)) => (
+            Outcome::check(case.and_then(|c| c.part1.as_ref()), &answers.part1),
+            Outcome::check(case.and_then(|c| c.part2.as_ref()), &answers.part2),
+        ),
+        Err(e) => (Outcome::Failed(e.clone()), Outcome::Failed(e.clone())),
+    };
+    FileReport {
+        path,
+        solved,
+        listed: case.is_some(),
+        part1,
+        part2,
+    }
+}
+
+impl FileReport {
+    /// The cells of the file's row of the table: its path, answers, time and result.
+    fn row(&self, checked: bool) -> [String; 5] {
+        let (part1, part2, time) = match &self.solved {
+            Ok((answers, time)) => (
+                first_line(&answers.part1),
+                first_line(&answers.part2),
+                Short(*time).to_string(),
+            ),
+            Err(_) => ("-".to_owned(), "-".to_owned(), "-".to_owned()),
+        };
+        let result = match (&self.solved, &self.part1, &self.part2) {
+            (Err(e), _, _) => first_line(e),
+            (Ok(_), Outcome::Mismatch { .. }, _) | (Ok(_), _, Outcome::Mismatch { .. }) => {
+                "MISMATCH".to_owned()
+            }
+            (Ok(_), _, _) if checked && !self.listed => "-".to_owned(),
+            (Ok(_), _, _) => "ok".to_owned(),
+        };
+        [self.path.display().to_string(), part1, part2, time, result]
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rows = self
+            .files
+            .iter()
+            .map(|file| file.row(self.checked))
+            .collect::<Vec<_>>();
+
+        let header = ["input", "part 1", "part 2", "time", "result"];
+        let width = |column: usize| {
+            rows.iter()
+                .map(|row| row[column].chars().count())
+                .chain([header[column].len()])
+                .max()
+                .unwrap_or(0)
+        };
+        let [path_width, part1_width, part2_width, time_width] = [0, 1, 2, 3].map(width);
+        for [path, part1, part2, time, result] in [header.map(str::to_owned)].iter().chain(&rows) {
+            writeln!(
+                f,
+                "{path:path_width$}  {part1:part1_width$}  {part2:part2_width$}  {time:>time_width$}  {result}"
+            )?;
+        }
+
+        for file in &self.files {
+            for (part, outcome) in [(1, &file.part1), (2, &file.part2)] {
+                match outcome {
+                    Outcome::Mismatch { expected, actual } => {
+                        writeln!(f, "\n{} part {part}:", file.path.display())?;
+                        writeln!(f, "  expected: {}", indent(expected))?;
+                        writeln!(f, "  actual:   {}", indent(actual))?;
+                    }
+                    // Both parts fail together, so only report the error once.
+                    Outcome::Failed(error) if part == 1 => {
+                        writeln!(f, "\n{}:", file.path.display())?;
+                        writeln!(f, "  {}", error.trim_end().replace('\n', "\n  "))?;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let solved = self.files.iter().filter(|f| f.solved.is_ok()).count();
+        write!(f, "\nday {}", self.day)?;
+        if !self.params.is_empty() {
+            write!(f, " ({})", params::describe(&self.params))?;
+        }
+        write!(f, ": {solved} of {} inputs solved", self.files.len())?;
+        if self.checked {
+            let outcomes = self
+                .files
+                .iter()
+                .filter(|f| f.listed)
+                .flat_map(|f| [&f.part1, &f.part2]);
+            let (matched, total) =
+                outcomes.fold((0, 0), |(matched, total), outcome| match outcome {
+                    Outcome::Pass => (matched + 1, total + 1),
+                    Outcome::Unchecked => (matched, total),
+                    _ => (matched, total + 1),
+                });
+            write!(f, ", {matched} of {total} answers matched")?;
+        }
+        Ok(())
+    }
+}
+
+/// The first line of a multi-line answer or error, marked as cut short.
+fn first_line(s: &str) -> String {
+    let mut lines = s.trim_end().lines();
+    let first = lines.next().unwrap_or_default();
+    if lines.next().is_some() {
+        format!("{first}…")
+    } else {
+        first.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use super::*;
+    use crate::regression::{Answer, Case};
+
+    /// A fresh directory holding the given files.
+    fn directory(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc-batch-{}-{name}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        for (path, contents) in files {
+            fs::write(dir.join(path), contents).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn solves_every_file() {
+        let dir = directory(
+            "solves",
+            &[
+                ("alice", "1000\n2000\n\n3000\n"),
+                ("bob", "1000\nlots\n"),
+                (".hidden", "not an input"),
+            ],
+        );
+        let report = run(&crate::day01::Day01, &dir, None).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let paths = report
+            .files
+            .iter()
+            .map(|f| f.path.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(paths, [PathBuf::from("alice"), PathBuf::from("bob")]);
+        let answers = report.files[0].solved.as_ref().unwrap().0.clone();
+        assert_eq!(
+            (answers.part1.as_str(), answers.part2.as_str()),
+            ("3000", "6000")
+        );
+        assert!(report.files[1]
+            .solved
+            .as_ref()
+            .unwrap_err()
+            .contains("--> bob:2:1"));
+        assert_eq!(report.failures(), 1);
+        assert!(report.to_string().ends_with("day 1: 1 of 2 inputs solved"));
+    }
+
+    #[test]
+    fn checks_answers_against_a_manifest() {
+        let dir = directory("checks", &[("alice", "1000\n"), ("bob", "2000\n")]);
+        let case = |path: &str, part1| Case {
+            path: path.into(),
+            part1: Some(Answer::Number(part1)),
+            part2: None,
+            slow: false,
+        };
+        let manifest = Manifest {
+            inputs: vec![case("alice", 1000), case("bob", 1), case("carol", 3000)],
+        };
+        let report = run(&crate::day01::Day01, &dir, Some(&manifest)).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let outcomes = report
+            .files
+            .iter()
+            .map(|f| (f.path.display().to_string(), f.part1.label()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            outcomes,
+            [("alice", "ok"), ("bob", "MISMATCH"), ("carol", "FAILED")]
+                .map(|(path, label)| (path.to_owned(), label))
+        );
+        assert_eq!(report.failures(), 2);
+        assert!(report
+            .to_string()
+            .ends_with("day 1: 2 of 3 inputs solved, 1 of 4 answers matched"));
+    }
+}
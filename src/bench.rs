@@ -0,0 +1,398 @@
+//! Times each phase of the solutions over repeated runs.
+//!
+//! Reports can be saved as JSON and compared against a later run, to see whether a change made a
+//! day faster or slower.
+
+use std::{fmt, path::PathBuf, time::Duration};
+
+use color_eyre::{eyre::ensure, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    params::{self, Overrides},
+    Runner, Timings,
+};
+
+/// How many times to run each day.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Options {
+    /// Untimed runs made before measuring, to warm up caches and the allocator.
+    pub warmup: usize,
+    /// Timed runs made after warming up.
+    pub runs: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            warmup: 2,
+            runs: 20,
+        }
+    }
+}
+
+/// A summary of the times taken by one phase over several runs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stats {
+    #[serde(with = "nanos")]
+    pub min: Duration,
+    #[serde(with = "nanos")]
+    pub median: Duration,
+    #[serde(with = "nanos")]
+    pub p90: Duration,
+    #[serde(with = "nanos")]
+    pub p99: Duration,
+    #[serde(with = "nanos")]
+    pub max: Duration,
+}
+
+impl Stats {
+    /// Summarises the samples, or returns `None` if there are none.
+    pub fn from_samples(samples: &mut [Duration]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        samples.sort_unstable();
+        Some(Self {
+            min: samples[0],
+            median: percentile(samples, 50),
+            p90: percentile(samples, 90),
+            p99: percentile(samples, 99),
+            max: samples[samples.len() - 1],
+        })
+    }
+}
+
+/// The nearest-rank percentile of sorted, non-empty samples.
+fn percentile(sorted: &[Duration], p: usize) -> Duration {
+    let rank = (p * sorted.len()).div_ceil(100);
+    sorted[rank.saturating_sub(1)]
+}
+
+/// The timings for one day's input.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DayReport {
+    pub day: u8,
+    pub input: PathBuf,
+    /// The parameters overridden for the run.
+    #[serde(default, skip_serializing_if = "Overrides::is_empty")]
+    pub params: Overrides,
+    pub parse: Stats,
+    pub part1: Stats,
+    pub part2: Stats,
+    pub total: Stats,
+}
+
+impl DayReport {
+    fn phases(&self) -> [(&'static str, &Stats); 4] {
+        [
+            ("parse", &self.parse),
+            ("part 1", &self.part1),
+            ("part 2", &self.part2),
+            ("total", &self.total),
+        ]
+    }
+}
+
+/// The timings for every benchmarked day.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Report {
+    /// Whether the solutions were built with optimisations.
+    pub optimised: bool,
+    pub warmup: usize,
+    pub runs: usize,
+    pub days: Vec<DayReport>,
+}
+
+impl Report {
+    pub fn new(options: Options) -> Self {
+        Self {
+            optimised: !cfg!(debug_assertions),
+            warmup: options.warmup,
+            runs: options.runs,
+            days: Vec::new(),
+        }
+    }
+
+    /// Times the solution on the given input and adds it to the report.
+    pub fn bench(&mut self, solution: &dyn Runner, input: &str, path: PathBuf) -> Result<()> {
+        let options = Options {
+            warmup: self.warmup,
+            runs: self.runs,
+        };
+        self.days.push(bench(solution, input, path, options)?);
+        Ok(())
+    }
+
+    /// Parses a report previously saved with [`Report::to_json`].
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Compares this report's median times against an earlier one.
+    pub fn compare<'r>(&'r self, baseline: &'r Report) -> Comparison<'r> {
+        Comparison {
+            baseline,
+            current: self,
+        }
+    }
+}
+
+/// Times each phase of the solution over repeated runs on the same input.
+pub fn bench(
+    solution: &dyn Runner,
+    input: &str,
+    path: PathBuf,
+    options: Options,
+) -> Result<DayReport> {
+    ensure!(options.runs > 0, "at least one run is needed");
+
+    for _ in 0..options.warmup {
+        solution.run_timed(input)?;
+    }
+    let timings = (0..options.runs)
+        .map(|_| solution.run_timed(input).map(|(_, timings)| timings))
+        .collect::<Result<Vec<_>>>()?;
+
+    let stats = |phase: fn(&Timings) -> Duration| {
+        let mut samples = timings.iter().map(phase).collect::<Vec<_>>();
+        Stats::from_samples(&mut samples).expect("there is at least one run")
+    };
+    Ok(DayReport {
+        day: solution.day(),
+        input: path,
+        params: solution.overrides(),
+        parse: stats(|t| t.parse),
+        part1: stats(|t| t.part1),
+        part2: stats(|t| t.part2),
+        total: stats(Timings::total),
+    })
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "day  phase   {:>9}  {:>9}  {:>9}  {:>9}  {:>9}",
+            "min", "median", "p90", "p99", "max"
+        )?;
+        for day in &self.days {
+            for (idx, (phase, stats)) in day.phases().into_iter().enumerate() {
+                let label = if idx == 0 {
+                    day.day.to_string()
+                } else {
+                    String::new()
+                };
+                writeln!(
+                    f,
+                    "{label:>3}  {phase:6}  {:>9}  {:>9}  {:>9}  {:>9}  {:>9}",
+                    Short(stats.min),
+                    Short(stats.median),
+                    Short(stats.p90),
+                    Short(stats.p99),
+                    Short(stats.max),
+                )?;
+            }
+        }
+        for day in self.days.iter().filter(|day| !day.params.is_empty()) {
+            write!(
+                f,
+                "\nday {} with {}",
+                day.day,
+                params::describe(&day.params)
+            )?;
+        }
+        write!(
+            f,
+            "\n{} runs after {} warmup, {} build",
+            self.runs,
+            self.warmup,
+            if self.optimised {
+                "optimised"
+            } else {
+                "unoptimised"
+            }
+        )
+    }
+}
+
+/// The change in median times between two reports.
+#[derive(Clone, Copy, Debug)]
+pub struct Comparison<'r> {
+    pub baseline: &'r Report,
+    pub current: &'r Report,
+}
+
+impl fmt::Display for Comparison<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "day  phase   {:>9}  {:>9}  change",
+            "baseline", "current"
+        )?;
+        for day in &self.current.days {
+            let Some(baseline) = self.baseline.days.iter().find(|b| b.day == day.day) else {
+                writeln!(f, "{:>3}  not in the baseline", day.day)?;
+                continue;
+            };
+            if baseline.params != day.params {
+                writeln!(f, "{:>3}  run with different parameters", day.day)?;
+                continue;
+            }
+            for (idx, ((phase, current), (_, baseline))) in
+                day.phases().into_iter().zip(baseline.phases()).enumerate()
+            {
+                let label = if idx == 0 {
+                    day.day.to_string()
+                } else {
+                    String::new()
+                };
+                writeln!(
+                    f,
+                    "{label:>3}  {phase:6}  {:>9}  {:>9}  {}",
+                    Short(baseline.median),
+                    Short(current.median),
+                    Change(baseline.median, current.median),
+                )?;
+            }
+        }
+        if self.baseline.optimised != self.current.optimised {
+            writeln!(
+                f,
+                "\nwarning: only one of the reports is from an optimised build"
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats a duration to three significant figures in a unit suited to its size.
+pub(crate) struct Short(pub Duration);
+
+impl fmt::Display for Short {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let nanos = self.0.as_nanos() as f64;
+        let (value, unit) = if nanos < 1e3 {
+            (nanos, "ns")
+        } else if nanos < 1e6 {
+            (nanos / 1e3, "µs")
+        } else if nanos < 1e9 {
+            (nanos / 1e6, "ms")
+        } else {
+            (nanos / 1e9, "s")
+        };
+        let precision = match value {
+            v if v < 10.0 => 2,
+            v if v < 100.0 => 1,
+            _ => 0,
+        };
+        f.pad(&format!("{value:.precision$}{unit}"))
+    }
+}
+
+/// Formats the relative change from one duration to another.
+struct Change(Duration, Duration);
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self(before, after) = *self;
+        if before.is_zero() {
+            return f.pad("-");
+        }
+        let change = (after.as_secs_f64() / before.as_secs_f64() - 1.0) * 100.0;
+        f.pad(&format!("{change:+.1}%"))
+    }
+}
+
+/// Serialises durations as a whole number of nanoseconds.
+pub(crate) mod nanos {
+    use std::time::Duration;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        let nanos = u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX);
+        serializer.serialize_u64(nanos)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        u64::deserialize(deserializer).map(Duration::from_nanos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    fn millis(ms: &[u64]) -> Vec<Duration> {
+        ms.iter().copied().map(Duration::from_millis).collect()
+    }
+
+    #[test_case(&[5], 5, 5, 5; "single sample")]
+    #[test_case(&[4, 1, 3, 2], 2, 4, 4; "even count")]
+    #[test_case(&[10, 9, 8, 7, 6, 5, 4, 3, 2, 1], 5, 9, 10; "ten samples")]
+    fn percentiles(samples: &[u64], median: u64, p90: u64, p99: u64) {
+        let stats = Stats::from_samples(&mut millis(samples)).unwrap();
+        assert_eq!(stats.median, Duration::from_millis(median));
+        assert_eq!(stats.p90, Duration::from_millis(p90));
+        assert_eq!(stats.p99, Duration::from_millis(p99));
+    }
+
+    #[test]
+    fn no_samples() {
+        assert_eq!(Stats::from_samples(&mut []), None);
+    }
+
+    #[test]
+    fn report_round_trips_through_json() {
+        let mut report = Report::new(Options { warmup: 0, runs: 3 });
+        report
+            .bench(&crate::day01::Day01, "1\n2\n\n3\n", "example".into())
+            .unwrap();
+        let json = report.to_json().unwrap();
+        assert_eq!(Report::from_json(&json).unwrap(), report);
+    }
+
+    #[test]
+    fn reports_overridden_parameters() {
+        let overrides = Overrides::from_iter([("part2_rounds".to_owned(), 100.into())]);
+        let solution = crate::day11::Day11.configure(&overrides).unwrap();
+        let mut report = Report::new(Options { warmup: 0, runs: 1 });
+        report
+            .bench(
+                &*solution,
+                include_str!("../input/day11test"),
+                "example".into(),
+            )
+            .unwrap();
+        assert_eq!(report.days[0].params, overrides);
+        assert!(report
+            .to_string()
+            .contains("day 11 with part2_rounds = 100\n"));
+
+        let json = report.to_json().unwrap();
+        assert_eq!(Report::from_json(&json).unwrap(), report);
+    }
+
+    #[test_case(Duration::from_nanos(999), "999ns")]
+    #[test_case(Duration::from_micros(1500), "1.50ms")]
+    #[test_case(Duration::from_micros(12_345), "12.3ms")]
+    #[test_case(Duration::from_secs(2), "2.00s")]
+    fn short_durations(duration: Duration, expected: &str) {
+        assert_eq!(Short(duration).to_string(), expected);
+    }
+
+    #[test_case(100, 50, "-50.0%")]
+    #[test_case(100, 125, "+25.0%")]
+    #[test_case(0, 10, "-")]
+    fn changes(before: u64, after: u64, expected: &str) {
+        let change = Change(Duration::from_millis(before), Duration::from_millis(after));
+        assert_eq!(change.to_string(), expected);
+    }
+}
@@ -0,0 +1,655 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufRead, BufReader, IsTerminal, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+    thread,
+    time::Duration,
+};
+
+use aoc_2022::{
+    batch, bench,
+    day01::{ledger::CalorieLedger, Day01Params, ParseMode},
+    day02::{readings::Readings, Day02Params},
+    params::{self, Config, Overrides},
+    parsing,
+    regression::{self, Manifest},
+    render, trace,
+    watch::{Frame, Watcher},
+    PartAnswers, Parts, Runner, Timings, SOLUTIONS,
+};
+use clap::{Parser, Subcommand, ValueEnum};
+use color_eyre::{
+    eyre::{bail, eyre, WrapErr},
+    Result,
+};
+use serde::Serialize;
+
+/// Runs the Advent of Code 2022 solutions.
+#[derive(Debug, Parser)]
+#[command(
+    version,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
+struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// The day to run, or `all` to run every day.
+    #[arg(required = true)]
+    day: Option<DaySelection>,
+
+    /// Reads the puzzle input from this file rather than `input/dayNN`. Use `-` for stdin.
+    #[arg(short, long)]
+    input: Option<PathBuf>,
+
+    /// How to print the answers.
+    #[arg(short, long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    /// Records the solvers' spans and events which match this filter, such as
+    /// `aoc_2022::day10=trace` or `debug`.
+    #[arg(long, global = true)]
+    trace: Option<String>,
+
+    /// How to write the trace, as `text` or `json` lines.
+    #[arg(long, global = true, default_value_t = trace::Format::Text, requires = "trace")]
+    trace_format: trace::Format,
+
+    /// Writes the trace to this file rather than stderr.
+    #[arg(long, global = true, requires = "trace")]
+    trace_out: Option<PathBuf>,
+
+    /// Draws the day's state once solved, as `dot`, `text` or `svg`.
+    #[arg(short, long, requires = "out")]
+    render: Option<render::Backend>,
+
+    /// The file to write the drawing to.
+    #[arg(short, long, requires = "render")]
+    out: Option<PathBuf>,
+
+    /// Solves the day while reading the input, rather than reading it all first, so that inputs
+    /// too large for memory can be solved. Only some days support this, and it isn't timed.
+    #[arg(short, long, conflicts_with = "render")]
+    stream: bool,
+
+    /// Solves only part `1`, only part `2`, or `both`. Parsing is shared, and a part which isn't
+    /// asked for isn't solved. Streaming always solves both parts.
+    #[arg(long, default_value_t = Parts::Both, conflicts_with = "stream")]
+    part: Parts,
+
+    #[command(flatten)]
+    params: ParamArgs,
+}
+
+/// Overrides for the constants baked into the puzzles.
+#[derive(Debug, clap::Args)]
+struct ParamArgs {
+    /// Overrides one of the day's parameters, such as `-p rows=8`. Can be repeated, and takes
+    /// precedence over the config file.
+    #[arg(short, long = "param", value_name = "NAME=VALUE", value_parser = params::parse_override)]
+    params: Vec<(String, toml::Value)>,
+
+    /// Reads parameter overrides from this file, with a `[dayNN]` table of them for each day.
+    #[arg(long, value_name = "PATH")]
+    config: Option<PathBuf>,
+}
+
+impl ParamArgs {
+    /// Reads the config file, returning it with the overrides given on the command line.
+    fn read(self) -> Result<(Config, Overrides)> {
+        let config = self
+            .config
+            .map(|path| Config::read(&path))
+            .transpose()?
+            .unwrap_or_default();
+        Ok((config, self.params.into_iter().collect()))
+    }
+}
+
+/// The day's solution with the parameters overridden by the config file and the command line.
+fn configure(
+    solution: &dyn Runner,
+    config: &Config,
+    overrides: &Overrides,
+) -> Result<Box<dyn Runner>> {
+    solution.configure(&config.overrides(solution.day(), overrides))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// Human-readable answers.
+    Text,
+    /// One JSON object per day, with the answers, timings and input path.
+    Json,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Checks the answers for every input listed in `answers/dayNN.toml`.
+    Check {
+        /// The day to check, or `all` to check every day.
+        #[arg(default_value = "all")]
+        day: DaySelection,
+
+        /// Also checks inputs marked as slow.
+        #[arg(long)]
+        slow: bool,
+    },
+
+    /// Times parsing and each part of the solutions over repeated runs.
+    Bench {
+        /// The day to time, or `all` to time every day.
+        #[arg(default_value = "all")]
+        day: DaySelection,
+
+        /// Reads the puzzle input from this file rather than `input/dayNN`.
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+
+        /// The number of timed runs of each day.
+        #[arg(short, long, default_value_t = bench::Options::default().runs)]
+        runs: usize,
+
+        /// The number of untimed runs of each day made before timing.
+        #[arg(short, long, default_value_t = bench::Options::default().warmup)]
+        warmup: usize,
+
+        /// Saves the results to this file as JSON.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Compares the results against JSON saved by an earlier run.
+        #[arg(short, long)]
+        baseline: Option<PathBuf>,
+
+        #[command(flatten)]
+        params: ParamArgs,
+    },
+
+    /// Solves a day for every input file in a directory, in parallel.
+    Batch {
+        /// The day to solve.
+        #[arg(value_parser = parse_day)]
+        day: u8,
+
+        /// The directory of input files. Subdirectories and hidden files are skipped.
+        dir: PathBuf,
+
+        /// Checks the answers against this manifest, in the format of `answers/dayNN.toml` with
+        /// paths relative to the directory.
+        #[arg(short, long)]
+        check: Option<PathBuf>,
+
+        #[command(flatten)]
+        params: ParamArgs,
+    },
+
+    /// Re-solves a day whenever its input file changes, showing the answers or the error, and
+    /// the day's drawing if it has one. Runs until interrupted.
+    Watch {
+        /// The day to solve.
+        #[arg(value_parser = parse_day)]
+        day: u8,
+
+        /// The input file to watch.
+        input: PathBuf,
+
+        /// How often to check the file for changes, in milliseconds.
+        #[arg(long, default_value_t = 250)]
+        interval: u64,
+
+        #[command(flatten)]
+        params: ParamArgs,
+    },
+
+    /// Summarises the calories each elf carries in a day 1 input, with statistics on their totals.
+    Ledger {
+        /// Reads the items from this file rather than `input/day01`. Use `-` for stdin.
+        input: Option<PathBuf>,
+
+        /// How to print the report.
+        #[arg(short, long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+
+        /// The most bins to sort the totals into for the histogram.
+        #[arg(long, default_value_t = 10)]
+        bins: usize,
+
+        /// The number of elves carrying the most calories to rank, with their lines and totals.
+        #[arg(long, value_name = "N", default_value_t = Day01Params::default().top)]
+        top: usize,
+
+        /// Whether to fail at the first malformed line (`strict`), or to skip malformed lines and
+        /// ignore stray whitespace and blank lines (`lenient`).
+        #[arg(long, default_value_t = ParseMode::Strict)]
+        mode: ParseMode,
+    },
+
+    /// Scores a day 2 strategy guide under every way of reading its second column as moves,
+    /// showing the best and worst.
+    Readings {
+        /// Reads the guide from this file rather than `input/day02`. Use `-` for stdin.
+        input: Option<PathBuf>,
+
+        /// How to print the scores.
+        #[arg(short, long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+
+        #[command(flatten)]
+        params: ParamArgs,
+    },
+
+    /// Prints a random puzzle input for a day.
+    Gen {
+        /// The day to generate an input for.
+        #[arg(value_parser = parse_day)]
+        day: u8,
+
+        /// Seeds the generator. The same seed and size always give the same input.
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+
+        /// How large an input to generate, such as the number of lines. What this counts
+        /// depends on the day.
+        #[arg(long, default_value_t = 1000)]
+        size: usize,
+
+        #[command(flatten)]
+        params: ParamArgs,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DaySelection {
+    All,
+    Day(u8),
+}
+
+impl FromStr for DaySelection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("all") {
+            return Ok(Self::All);
+        }
+        if s.parse::<u8>().is_err() {
+            return Err(format!("expected a day number or `all`, found `{s}`"));
+        }
+        parse_day(s).map(Self::Day)
+    }
+}
+
+/// Parses the number of a solved day.
+fn parse_day(s: &str) -> Result<u8, String> {
+    let day = s
+        .parse::<u8>()
+        .map_err(|_| format!("expected a day number, found `{s}`"))?;
+    if aoc_2022::solution(day).is_none() {
+        return Err(format!("day {day} has not been solved"));
+    }
+    Ok(day)
+}
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+
+    let args = Args::parse();
+    // Warnings, such as for lines skipped while parsing leniently, are shown even without `--trace`.
+    let filter = args.trace.as_deref().unwrap_or("aoc_2022=warn");
+    trace::init(filter, args.trace_format, args.trace_out.as_deref())?;
+
+    match args.command {
+        Some(Command::Check { day, slow }) => return check(day, slow),
+        Some(Command::Bench {
+            day,
+            input,
+            runs,
+            warmup,
+            output,
+            baseline,
+            params,
+        }) => {
+            return bench(
+                day,
+                input,
+                bench::Options { warmup, runs },
+                output,
+                baseline,
+                params,
+            )
+        }
+        Some(Command::Batch {
+            day,
+            dir,
+            check,
+            params,
+        }) => return run_batch(day, &dir, check, params),
+        Some(Command::Watch {
+            day,
+            input,
+            interval,
+            params,
+        }) => return watch(day, input, Duration::from_millis(interval), params),
+        Some(Command::Ledger {
+            input,
+            format,
+            bins,
+            top,
+            mode,
+        }) => return ledger(input, format, bins, top, mode),
+        Some(Command::Readings {
+            input,
+            format,
+            params,
+        }) => return readings(input, format, params),
+        Some(Command::Gen {
+            day,
+            seed,
+            size,
+            params,
+        }) => {
+            let solution = aoc_2022::solution(day).expect("clap checks the day is solved");
+            let (config, overrides) = params.read()?;
+            let solution = configure(solution, &config, &overrides)?;
+            print!("{}", solution.generate(seed, size));
+            return Ok(());
+        }
+        None => {}
+    }
+
+    let (config, overrides) = args.params.read()?;
+    match args.day.expect("clap requires a day without a subcommand") {
+        DaySelection::All => {
+            if args.input.is_some() {
+                bail!("`--input` cannot be used when running all days");
+            }
+            if args.render.is_some() {
+                bail!("`--render` cannot be used when running all days");
+            }
+            if args.stream {
+                bail!("`--stream` cannot be used when running all days");
+            }
+            if !overrides.is_empty() {
+                bail!("`--param` cannot be used when running all days; use `--config` instead");
+            }
+            for (idx, &solution) in SOLUTIONS.iter().enumerate() {
+                if idx > 0 && args.format == Format::Text {
+                    println!();
+                }
+                let solution = configure(solution, &config, &overrides)?;
+                run(&*solution, None, args.part, args.format, None)?;
+            }
+        }
+        DaySelection::Day(day) => {
+            let solution =
+                aoc_2022::solution(day).ok_or_else(|| eyre!("day {day} has not been solved"))?;
+            let solution = configure(solution, &config, &overrides)?;
+            if args.stream {
+                stream(&*solution, args.input, args.format)?;
+            } else {
+                let drawing = args.render.zip(args.out);
+                run(&*solution, args.input, args.part, args.format, drawing)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn check(day: DaySelection, slow: bool) -> Result<()> {
+    let solutions = match day {
+        DaySelection::All => SOLUTIONS.to_vec(),
+        DaySelection::Day(day) => {
+            vec![aoc_2022::solution(day).ok_or_else(|| eyre!("day {day} has not been solved"))?]
+        }
+    };
+    let report = regression::check(".".as_ref(), solutions, slow)?;
+    println!("{report}");
+    if !report.passed() {
+        bail!("{} answers did not match", report.failures());
+    }
+    Ok(())
+}
+
+fn run_batch(day: u8, dir: &Path, check: Option<PathBuf>, params: ParamArgs) -> Result<()> {
+    let solution = aoc_2022::solution(day).expect("clap checks the day is solved");
+    let (config, overrides) = params.read()?;
+    let solution = configure(solution, &config, &overrides)?;
+    let manifest = check.map(|path| Manifest::read(&path)).transpose()?;
+    let report = batch::run(&*solution, dir, manifest.as_ref())?;
+    println!("{report}");
+    if !report.passed() {
+        bail!(
+            "{} of {} inputs failed",
+            report.failures(),
+            report.files.len()
+        );
+    }
+    Ok(())
+}
+
+fn watch(day: u8, input: PathBuf, interval: Duration, params: ParamArgs) -> Result<()> {
+    let solution = aoc_2022::solution(day).expect("clap checks the day is solved");
+    let (config, overrides) = params.read()?;
+    let solution = configure(solution, &config, &overrides)?;
+    let source_name = input.display().to_string();
+    let mut watcher = Watcher::new(input);
+    let clear = io::stdout().is_terminal();
+    loop {
+        if let Some(snapshot) = watcher.poll() {
+            let frame = Frame::solve(&*solution, &source_name, &snapshot);
+            if clear {
+                // Clears the screen and moves the cursor to the top left.
+                print!("\x1b[2J\x1b[H");
+            } else {
+                println!();
+            }
+            println!("{frame}");
+            io::stdout().flush()?;
+        }
+        thread::sleep(interval);
+    }
+}
+
+fn ledger(
+    input: Option<PathBuf>,
+    format: Format,
+    bins: usize,
+    top: usize,
+    mode: ParseMode,
+) -> Result<()> {
+    if top == 0 {
+        bail!("`--top` must rank at least one elf");
+    }
+    let path = input.unwrap_or_else(|| aoc_2022::default_input_path(1));
+    let (input, source_name) = read(&path)?;
+    let ledger = CalorieLedger::parse_with(&input, mode)
+        .map_err(|e| parsing::name_source(e.into_report(), source_name))?;
+    let report = ledger.report(bins, top);
+    match format {
+        Format::Text => println!("{report}"),
+        Format::Json => println!("{}", report.to_json()?),
+    }
+    Ok(())
+}
+
+fn readings(input: Option<PathBuf>, format: Format, params: ParamArgs) -> Result<()> {
+    let (config, overrides) = params.read()?;
+    let params = params::apply(&Day02Params::default(), &config.overrides(2, &overrides))
+        .wrap_err("configuring day 2")?;
+    let path = input.unwrap_or_else(|| aoc_2022::default_input_path(2));
+    let (input, source_name) = read(&path)?;
+    let readings = Readings::score(&input, &params)
+        .map_err(|e| parsing::name_source(e.into_report(), source_name))?;
+    match format {
+        Format::Text => println!("{readings}"),
+        Format::Json => println!("{}", readings.to_json()?),
+    }
+    Ok(())
+}
+
+fn bench(
+    day: DaySelection,
+    input: Option<PathBuf>,
+    options: bench::Options,
+    output: Option<PathBuf>,
+    baseline: Option<PathBuf>,
+    params: ParamArgs,
+) -> Result<()> {
+    let (config, overrides) = params.read()?;
+    let baseline = baseline
+        .map(|path| bench::Report::from_json(&fs::read_to_string(path)?))
+        .transpose()?;
+
+    let mut report = bench::Report::new(options);
+    let solutions = match day {
+        DaySelection::All => {
+            if input.is_some() {
+                bail!("`--input` cannot be used when timing all days");
+            }
+            if !overrides.is_empty() {
+                bail!("`--param` cannot be used when timing all days; use `--config` instead");
+            }
+            SOLUTIONS.to_vec()
+        }
+        DaySelection::Day(day) => {
+            vec![aoc_2022::solution(day).ok_or_else(|| eyre!("day {day} has not been solved"))?]
+        }
+    };
+    for solution in solutions {
+        let solution = configure(solution, &config, &overrides)?;
+        let path = input
+            .clone()
+            .unwrap_or_else(|| aoc_2022::default_input_path(solution.day()));
+        let input = aoc_2022::read_input(Some(&path))?;
+        let source_name = path.display().to_string();
+        report
+            .bench(&*solution, &input, path)
+            .map_err(|e| parsing::name_source(e, source_name))?;
+    }
+
+    match &baseline {
+        Some(baseline) => println!("{}", report.compare(baseline)),
+        None => println!("{report}"),
+    }
+    if let Some(output) = output {
+        fs::write(output, report.to_json()?)?;
+    }
+
+    Ok(())
+}
+
+/// The results for one day, as printed by `--format json`.
+#[derive(Debug, Serialize)]
+struct DayResult {
+    day: u8,
+    /// The answer to part 1, unless only part 2 was solved.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    part1: Option<String>,
+    /// The answer to part 2, unless only part 1 was solved.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    part2: Option<String>,
+    /// The time taken by each phase, in nanoseconds, unless the input was streamed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timings: Option<Timings>,
+    input: String,
+    /// The parameters overridden for the run.
+    #[serde(skip_serializing_if = "Overrides::is_empty")]
+    params: Overrides,
+}
+
+/// Solves the selected parts of the day and prints their answers, then draws the solved state if
+/// asked to.
+fn run(
+    solution: &dyn Runner,
+    input: Option<PathBuf>,
+    parts: Parts,
+    format: Format,
+    drawing: Option<(render::Backend, PathBuf)>,
+) -> Result<()> {
+    let path = input.unwrap_or_else(|| aoc_2022::default_input_path(solution.day()));
+    let (input, source_name) = read(&path)?;
+
+    let (answers, timings) = solution
+        .run_parts_timed(&input, parts)
+        .map_err(|e| parsing::name_source(e, &source_name))?;
+    print_answers(solution, answers, Some(timings), source_name, format)?;
+
+    if let Some((backend, out)) = drawing {
+        let Some(scene) = solution.visualise(&input)? else {
+            bail!("day {} has nothing to draw", solution.day());
+        };
+        fs::write(out, scene.render(backend)?)?;
+    }
+
+    Ok(())
+}
+
+/// Reads the input from the file, or from stdin if the path is `-`, with a name for errors.
+fn read(path: &Path) -> Result<(String, String)> {
+    Ok(if path.as_os_str() == "-" {
+        (aoc_2022::read_input(None)?, "<stdin>".to_owned())
+    } else {
+        (
+            aoc_2022::read_input(Some(path))?,
+            path.display().to_string(),
+        )
+    })
+}
+
+/// Solves the day while reading the input, then prints the answers.
+fn stream(solution: &dyn Runner, input: Option<PathBuf>, format: Format) -> Result<()> {
+    let path = input.unwrap_or_else(|| aoc_2022::default_input_path(solution.day()));
+    let (mut reader, source_name): (Box<dyn BufRead>, _) = if path.as_os_str() == "-" {
+        (Box::new(io::stdin().lock()), "<stdin>".to_owned())
+    } else {
+        (
+            Box::new(BufReader::new(File::open(&path)?)),
+            path.display().to_string(),
+        )
+    };
+
+    let Some(answers) = solution
+        .run_streaming(&mut reader)
+        .map_err(|e| parsing::name_source(e, &source_name))?
+    else {
+        bail!(
+            "day {} can't be solved while streaming its input",
+            solution.day()
+        );
+    };
+    print_answers(solution, answers.into(), None, source_name, format)
+}
+
+fn print_answers(
+    solution: &dyn Runner,
+    answers: PartAnswers,
+    timings: Option<Timings>,
+    source_name: String,
+    format: Format,
+) -> Result<()> {
+    match format {
+        Format::Text => {
+            println!("Day {}", solution.day());
+            let overrides = solution.overrides();
+            if !overrides.is_empty() {
+                println!("Parameters: {}", params::describe(&overrides));
+            }
+            println!("{answers}");
+        }
+        Format::Json => {
+            let result = DayResult {
+                day: solution.day(),
+                part1: answers.part1,
+                part2: answers.part2,
+                timings,
+                input: source_name,
+                params: solution.overrides(),
+            };
+            println!("{}", serde_json::to_string(&result)?);
+        }
+    }
+    Ok(())
+}
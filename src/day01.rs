@@ -0,0 +1,603 @@
+//! Day 1: Calorie Counting
+
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+    fmt,
+    io::BufRead,
+    ops::Range,
+    str::FromStr,
+};
+
+use itertools::Itertools;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::{
+    params::Params, parsing::ParseError, stream::Input, Error, PuzzleError, Solution, StreamResult,
+};
+
+pub mod ledger;
+mod parallel;
+
+pub struct Day01;
+
+/// Why a list of calories has no answer.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum Day01Error {
+    #[error("the elves carry more calories than can be counted")]
+    TooManyCalories,
+}
+
+impl PuzzleError for Day01Error {}
+
+/// How many of the elves carrying the most calories are counted, and how the list is read.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Day01Params {
+    /// The number of elves whose calories are summed in the second part.
+    pub top: usize,
+    pub mode: ParseMode,
+    /// Whether to read the list in chunks on every thread, for lists of many megabytes.
+    pub parallel: bool,
+    /// The least number of bytes in each chunk read in parallel.
+    pub chunk_size: usize,
+}
+
+impl Default for Day01Params {
+    fn default() -> Self {
+        Self {
+            top: 3,
+            mode: ParseMode::Strict,
+            parallel: false,
+            chunk_size: 1 << 24,
+        }
+    }
+}
+
+impl Params for Day01Params {
+    fn validate(&self) -> Result<(), String> {
+        if self.top == 0 {
+            return Err("at least one elf must be counted".to_owned());
+        }
+        if self.chunk_size == 0 {
+            return Err("chunks must hold at least one byte".to_owned());
+        }
+        Ok(())
+    }
+}
+
+/// How forgiving to be of a list of calories which isn't quite in the puzzle's format.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParseMode {
+    /// Every line must be a number or blank, failing at the first which isn't. Each blank line
+    /// ends an elf, so a second blank line in a row is an elf carrying nothing.
+    #[default]
+    Strict,
+    /// Whitespace around each line is ignored, lines which aren't numbers are skipped with a
+    /// warning, and any run of blank lines separates two elves.
+    Lenient,
+}
+
+impl FromStr for ParseMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "strict" => Ok(Self::Strict),
+            "lenient" => Ok(Self::Lenient),
+            _ => Err(format!("expected `strict` or `lenient`, found `{s}`")),
+        }
+    }
+}
+
+impl fmt::Display for ParseMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Strict => "strict",
+            Self::Lenient => "lenient",
+        })
+    }
+}
+
+/// The items carried by one elf.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Elf {
+    /// The elf's position in the input, counting from 0.
+    pub index: usize,
+    /// The line numbers of the elf's items, counting from 1.
+    pub lines: Range<usize>,
+    /// The total calories of the elf's items.
+    pub calories: u64,
+}
+
+impl Solution for Day01 {
+    const DAY: u8 = 1;
+
+    type Parsed<'i> = Vec<Elf>;
+    type Part1 = u64;
+    type Part2 = u64;
+    type Error = Day01Error;
+    type Params = Day01Params;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, Error<Day01Error>> {
+        Self::parse_with(input, &Day01Params::default())
+    }
+
+    fn parse_with<'i>(
+        input: &'i str,
+        params: &Day01Params,
+    ) -> Result<Self::Parsed<'i>, Error<Day01Error>> {
+        if params.parallel {
+            parallel::elves(input, params.mode, params.chunk_size)
+        } else {
+            elves(input, params.mode)
+        }
+    }
+
+    fn part1(elves: &Self::Parsed<'_>) -> Result<Self::Part1, Error<Day01Error>> {
+        Ok(elves.iter().map(|elf| elf.calories).max().unwrap_or(0))
+    }
+
+    fn part2(elves: &Self::Parsed<'_>) -> Result<Self::Part2, Error<Day01Error>> {
+        Self::part2_with(elves, &Day01Params::default())
+    }
+
+    fn part2_with(
+        elves: &Self::Parsed<'_>,
+        params: &Day01Params,
+    ) -> Result<Self::Part2, Error<Day01Error>> {
+        Ok(sum_calories(&top_elves(elves.iter().cloned(), params.top))?)
+    }
+
+    fn solve_streaming<R: BufRead>(input: &mut Input<R>) -> StreamResult<Self> {
+        Self::solve_streaming_with(input, &Day01Params::default())
+    }
+
+    /// Keeps only the elves carrying the most calories so far, reading blocks of the input in
+    /// parallel if asked to.
+    fn solve_streaming_with<R: BufRead>(
+        input: &mut Input<R>,
+        params: &Day01Params,
+    ) -> StreamResult<Self> {
+        if params.parallel {
+            return Ok(Some(parallel::solve_blocks(input, params)?));
+        }
+        let mut top = TopN::new(params.top);
+        let mut most = 0;
+        let mut finish = |elf: Elf| {
+            most = most.max(elf.calories);
+            top.push(rank(&elf), elf);
+        };
+        let mut grouper = Grouper::new(params.mode, || 0, add_calories);
+        for line in input.lines() {
+            let finished = grouper
+                .line(line.number(), line.text())
+                .map_err(|problem| problem.locate(|at, _, expected| line.expected(at, expected)))?;
+            if let Some(batch) = finished {
+                finish(batch.into());
+            }
+        }
+        if let Some(batch) = grouper.finish() {
+            finish(batch.into());
+        }
+        Ok(Some((most, sum_calories(&top.into_sorted_vec())?)))
+    }
+
+    /// Generates `size` elves, each carrying a handful of items.
+    fn generate<R: Rng>(rng: &mut R, size: usize) -> String {
+        (0..size)
+            .map(|_| {
+                let items = rng.gen_range(1..=15);
+                (0..items)
+                    .map(|_| format!("{}\n", rng.gen_range(1000..=70_000)))
+                    .collect::<String>()
+            })
+            .join("\n")
+    }
+}
+
+/// Reads the items carried by each elf, in input order.
+///
+/// Each elf's items are listed one per line, with elves separated by a blank line.
+pub fn elves(input: &str, mode: ParseMode) -> Result<Vec<Elf>, Error<Day01Error>> {
+    let batches = batches(input, mode, || 0, add_calories)?;
+    Ok(batches.into_iter().map(Elf::from).collect())
+}
+
+fn add_calories(total: &mut u64, calories: u64) -> Result<(), Day01Error> {
+    *total = total
+        .checked_add(calories)
+        .ok_or(Day01Error::TooManyCalories)?;
+    Ok(())
+}
+
+/// Folds each elf's items into a value starting from `init`, in input order.
+fn batches<A>(
+    input: &str,
+    mode: ParseMode,
+    init: impl Fn() -> A,
+    add: impl FnMut(&mut A, u64) -> Result<(), Day01Error>,
+) -> Result<Vec<Batch<A>>, Error<Day01Error>> {
+    let mut grouper = Grouper::new(mode, init, add);
+    let mut batches = Vec::new();
+    for (line, number) in input.lines().zip(1..) {
+        let finished = grouper.line(number, line).map_err(|problem| {
+            problem.locate(|at, _, expected| ParseError::expected(input, at, expected))
+        })?;
+        batches.extend(finished);
+    }
+    batches.extend(grouper.finish());
+    Ok(batches)
+}
+
+/// One elf's items, folded into a value.
+struct Batch<A> {
+    index: usize,
+    /// The line numbers of the elf's items, counting from 1.
+    lines: Range<usize>,
+    value: A,
+}
+
+impl From<Batch<u64>> for Elf {
+    fn from(batch: Batch<u64>) -> Self {
+        Self {
+            index: batch.index,
+            lines: batch.lines,
+            calories: batch.value,
+        }
+    }
+}
+
+/// Sorts the lines of a list of calories into elves one line at a time, so that the list is read
+/// the same way whether it is held in memory or streamed.
+struct Grouper<A, I, F> {
+    mode: ParseMode,
+    init: I,
+    add: F,
+    /// The index of the next elf to start.
+    next: usize,
+    /// The elf whose items are being read.
+    current: Option<Batch<A>>,
+    /// The lines skipped so far, if they are to be warned about later rather than as they are
+    /// skipped.
+    skipped: Option<Vec<Skipped>>,
+}
+
+/// Why a line couldn't be read.
+enum Problem<'l> {
+    /// The line isn't a number, so can't be one of the elf's items.
+    NotANumber {
+        at: &'l str,
+        line: usize,
+        elf: usize,
+    },
+    Puzzle(Day01Error),
+}
+
+/// A line skipped while parsing leniently.
+struct Skipped {
+    line: usize,
+    elf: usize,
+    text: String,
+}
+
+impl Skipped {
+    fn warn(&self) {
+        warn!(
+            line = self.line,
+            elf = self.elf,
+            text = self.text,
+            "skipped a line which isn't a number of calories"
+        );
+    }
+}
+
+impl Problem<'_> {
+    /// Converts to an error, locating a malformed line with `expected`, given the malformed text,
+    /// its line number and what was expected instead.
+    fn locate(self, expected: impl FnOnce(&str, usize, String) -> ParseError) -> Error<Day01Error> {
+        match self {
+            Self::NotANumber { at, line, elf } => {
+                expected(at, line, format!("a number of calories for elf {elf}")).into()
+            }
+            Self::Puzzle(e) => e.into(),
+        }
+    }
+}
+
+impl<A, I, F> Grouper<A, I, F>
+where
+    I: Fn() -> A,
+    F: FnMut(&mut A, u64) -> Result<(), Day01Error>,
+{
+    fn new(mode: ParseMode, init: I, add: F) -> Self {
+        Self {
+            mode,
+            init,
+            add,
+            next: 0,
+            current: None,
+            skipped: None,
+        }
+    }
+
+    /// Reads a line without its line ending, returning the elf it finishes, if any.
+    fn line<'l>(&mut self, number: usize, text: &'l str) -> Result<Option<Batch<A>>, Problem<'l>> {
+        let text = match self.mode {
+            ParseMode::Strict => text,
+            ParseMode::Lenient => text.trim(),
+        };
+        if text.is_empty() {
+            return Ok(match (self.current.take(), self.mode) {
+                (Some(finished), _) => Some(finished),
+                // A blank line with no items before it is an elf carrying nothing.
+                (None, ParseMode::Strict) => Some(self.start(number)),
+                (None, ParseMode::Lenient) => None,
+            });
+        }
+
+        let calories = text.parse::<u64>();
+        if calories.is_err() && self.mode == ParseMode::Strict {
+            let elf = self.current.as_ref().map_or(self.next, |elf| elf.index);
+            return Err(Problem::NotANumber {
+                at: text,
+                line: number,
+                elf,
+            });
+        }
+        // Even a skipped line starts an elf, so that the elves after it keep their indices.
+        if self.current.is_none() {
+            self.current = Some(self.start(number));
+        }
+        let current = self.current.as_mut().expect("an elf was just started");
+        let Ok(calories) = calories else {
+            let skipped = Skipped {
+                line: number,
+                elf: current.index,
+                text: text.to_owned(),
+            };
+            match &mut self.skipped {
+                Some(all) => all.push(skipped),
+                None => skipped.warn(),
+            }
+            return Ok(None);
+        };
+        (self.add)(&mut current.value, calories).map_err(Problem::Puzzle)?;
+        current.lines.end = number + 1;
+        Ok(None)
+    }
+
+    /// The elf being read when the input ends, if any.
+    fn finish(self) -> Option<Batch<A>> {
+        self.current
+    }
+
+    /// Starts a new elf at the given line.
+    fn start(&mut self, number: usize) -> Batch<A> {
+        let index = self.next;
+        self.next += 1;
+        Batch {
+            index,
+            lines: number..number,
+            value: (self.init)(),
+        }
+    }
+}
+
+/// The `n` elves carrying the most calories, from most to least, preferring earlier elves when
+/// they carry the same.
+pub fn top_elves(elves: impl IntoIterator<Item = Elf>, n: usize) -> Vec<Elf> {
+    let mut top = TopN::new(n);
+    for elf in elves {
+        top.push(rank(&elf), elf);
+    }
+    let top = top.into_sorted_vec();
+    for (rank, elf) in top.iter().enumerate() {
+        debug!(
+            rank = rank + 1,
+            elf = elf.index,
+            lines = ?elf.lines,
+            calories = elf.calories,
+            "top elf"
+        );
+    }
+    top
+}
+
+/// Ranks elves by their calories, then by how early they come.
+fn rank(elf: &Elf) -> (u64, Reverse<usize>) {
+    (elf.calories, Reverse(elf.index))
+}
+
+fn sum_calories(elves: &[Elf]) -> Result<u64, Day01Error> {
+    elves.iter().try_fold(0u64, |total, elf| {
+        total
+            .checked_add(elf.calories)
+            .ok_or(Day01Error::TooManyCalories)
+    })
+}
+
+/// Keeps the `n` values with the largest keys pushed so far.
+///
+/// The kept values are held in a min-heap, so that each push only compares against the smallest
+/// of them, and memory stays bounded by `n`.
+#[derive(Clone, Debug)]
+pub struct TopN<K, T> {
+    n: usize,
+    heap: BinaryHeap<Reverse<Ranked<K, T>>>,
+}
+
+impl<K: Ord, T> TopN<K, T> {
+    pub fn new(n: usize) -> Self {
+        Self {
+            n,
+            heap: BinaryHeap::with_capacity(n),
+        }
+    }
+
+    /// Offers a value, keeping it if its key is among the `n` largest so far.
+    pub fn push(&mut self, key: K, value: T) {
+        let ranked = Ranked { key, value };
+        if self.heap.len() < self.n {
+            self.heap.push(Reverse(ranked));
+        } else if let Some(mut smallest) = self.heap.peek_mut() {
+            if ranked > smallest.0 {
+                *smallest = Reverse(ranked);
+            }
+        }
+    }
+
+    /// The kept values, from the largest key to the smallest.
+    pub fn into_sorted_vec(self) -> Vec<T> {
+        // The heap holds reversed keys, so ascending order is from the largest key.
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse(ranked)| ranked.value)
+            .collect()
+    }
+}
+
+/// A value ordered only by its key.
+#[derive(Clone, Debug)]
+struct Ranked<K, T> {
+    key: K,
+    value: T,
+}
+
+impl<K: Ord, T> PartialEq for Ranked<K, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl<K: Ord, T> Eq for Ranked<K, T> {}
+
+impl<K: Ord, T> PartialOrd for Ranked<K, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K: Ord, T> Ord for Ranked<K, T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::proptest;
+    use test_case::test_case;
+
+    use super::*;
+    use crate::{differential, params, Runner};
+
+    #[test_case("1000\n\nabc\n" => matches Err(Error::Parse(_)) ; "not a number")]
+    #[test_case("18446744073709551615\n1\n" => Err(Error::Puzzle(Day01Error::TooManyCalories)) ; "one elf overflows")]
+    #[test_case("18446744073709551615\n\n1\n" => Err(Error::Puzzle(Day01Error::TooManyCalories)) ; "top three overflow")]
+    fn malformed_input(input: &str) -> Result<(u64, u64), Error<Day01Error>> {
+        differential::solve::<Day01>(input)
+    }
+
+    #[test_case("1000\n\nabc\n" => (3, 1, "expected a number of calories for elf 1, found `abc`".to_owned()) ; "second elf")]
+    #[test_case("1000\n 2000\n" => (2, 1, "expected a number of calories for elf 0, found ` 2000`".to_owned()) ; "stray whitespace")]
+    #[test_case("1000\n\n\n2000\nx\n" => (5, 1, "expected a number of calories for elf 2, found `x`".to_owned()) ; "after an empty elf")]
+    fn strict_errors_name_the_line_and_elf(input: &str) -> (usize, usize, String) {
+        let Err(Error::Parse(e)) = differential::solve::<Day01>(input) else {
+            panic!("expected a parse error");
+        };
+        (e.line(), e.column(), e.message().to_owned())
+    }
+
+    #[test]
+    fn lenient_parsing_forgives_stray_lines() {
+        let input = "  1000 \r\n2000\r\n\r\n \r\n\n3000\nabc\n4000\n\n\n\nlots\n\n5000\n\n";
+        let elves = elves(input, ParseMode::Lenient)
+            .unwrap()
+            .into_iter()
+            .map(|elf| (elf.index, elf.lines, elf.calories))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            elves,
+            [
+                (0, 1..3, 3000),
+                (1, 6..9, 7000),
+                (2, 12..12, 0),
+                (3, 14..15, 5000)
+            ]
+        );
+
+        let solution = Day01
+            .configure(&params::overrides(&["mode=lenient", "top=2"]))
+            .unwrap();
+        let answers = solution.run(input).unwrap();
+        assert_eq!(
+            (answers.part1.as_str(), answers.part2.as_str()),
+            ("7000", "12000")
+        );
+        let streamed = solution.run_streaming(&mut input.as_bytes()).unwrap();
+        assert_eq!(streamed, Some(answers));
+        assert!(Day01.run(input).is_err());
+    }
+
+    /// Sums each elf's items and sorts the totals.
+    fn reference(input: &str) -> (u64, u64) {
+        let mut totals = input
+            .split("\n\n")
+            .map(|elf| elf.lines().map(|l| l.parse::<u64>().unwrap()).sum())
+            .collect::<Vec<u64>>();
+        totals.sort_unstable_by(|a, b| b.cmp(a));
+        (totals[0], totals.iter().take(3).sum())
+    }
+
+    #[test]
+    fn finds_the_top_elves_and_their_lines() {
+        let elves = Day01::parse(include_str!("../input/day01test")).unwrap();
+        let top = top_elves(elves, 3)
+            .into_iter()
+            .map(|elf| (elf.index, elf.lines, elf.calories))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            top,
+            [(3, 10..13, 24000), (2, 7..9, 11000), (4, 14..15, 10000)]
+        );
+    }
+
+    #[test]
+    fn prefers_earlier_elves_on_ties() {
+        let mut top = TopN::new(2);
+        for (key, value) in [(1, 'a'), (3, 'b'), (1, 'c'), (3, 'd'), (2, 'e')] {
+            top.push((key, Reverse(value)), value);
+        }
+        assert_eq!(top.into_sorted_vec(), ['b', 'd']);
+        assert_eq!(TopN::<u8, ()>::new(0).into_sorted_vec(), []);
+    }
+
+    #[test_case(&["top=1"] => Ok(("24000".to_owned(), "24000".to_owned())) ; "one")]
+    #[test_case(&["top=9"] => Ok(("24000".to_owned(), "55000".to_owned())) ; "more than there are")]
+    #[test_case(&["top=0"] => Err("configuring day 1: at least one elf must be counted".to_owned()) ; "none")]
+    fn counts_the_top_n(overrides: &[&str]) -> Result<(String, String), String> {
+        let solution = Day01
+            .configure(&params::overrides(overrides))
+            .map_err(|e| format!("{e:#}"))?;
+        let input = include_str!("../input/day01test");
+        let answers = solution.run(input).unwrap();
+        let streamed = solution
+            .run_streaming(&mut input.as_bytes())
+            .unwrap()
+            .unwrap();
+        assert_eq!(streamed, answers);
+        Ok((answers.part1, answers.part2))
+    }
+
+    proptest! {
+        #[test]
+        fn agrees_with_reference(input in differential::inputs(30)) {
+            differential::check::<Day01>(input, reference)?;
+        }
+    }
+}
@@ -0,0 +1,450 @@
+//! A ledger of the items each elf carries, with statistics on their totals.
+//!
+//! The ledger reads the same format as the puzzle, so it can summarise any list of items grouped
+//! by blank lines. Its [`LedgerReport`] is printed as text or serialised as JSON.
+
+use std::{fmt, ops::Range};
+
+use color_eyre::Result;
+use serde::Serialize;
+
+use super::{add_calories, batches, top_elves, Day01Error, Elf, ParseMode};
+use crate::Error;
+
+/// The widest bar drawn in a histogram.
+const BAR_WIDTH: usize = 40;
+
+/// Tukey's factor: totals further than this many interquartile ranges outside the middle half are
+/// outliers.
+const OUTLIER_FACTOR: f64 = 1.5;
+
+/// Every elf's items, in input order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CalorieLedger {
+    elves: Vec<LedgerEntry>,
+}
+
+/// An elf with the calories of each item it carries.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LedgerEntry {
+    pub elf: Elf,
+    pub items: Vec<u64>,
+}
+
+impl CalorieLedger {
+    /// Reads a list of items, one per line, with elves separated by a blank line.
+    pub fn parse(input: &str) -> Result<Self, Error<Day01Error>> {
+        Self::parse_with(input, ParseMode::Strict)
+    }
+
+    /// Reads a list of items, forgiving the mistakes that `mode` allows.
+    pub fn parse_with(input: &str, mode: ParseMode) -> Result<Self, Error<Day01Error>> {
+        let batches = batches(
+            input,
+            mode,
+            || (0, Vec::new()),
+            |(total, items): &mut (u64, Vec<u64>), calories| {
+                add_calories(total, calories)?;
+                items.push(calories);
+                Ok(())
+            },
+        )?;
+        let elves = batches
+            .into_iter()
+            .map(|batch| {
+                let (calories, items) = batch.value;
+                LedgerEntry {
+                    elf: Elf {
+                        index: batch.index,
+                        lines: batch.lines,
+                        calories,
+                    },
+                    items,
+                }
+            })
+            .collect();
+        Ok(Self { elves })
+    }
+
+    pub fn elves(&self) -> &[LedgerEntry] {
+        &self.elves
+    }
+
+    /// Summarises each elf and the spread of their totals, sorting the totals into `bins` bins
+    /// and ranking the `top` elves carrying the most.
+    pub fn report(&self, bins: usize, top: usize) -> LedgerReport {
+        let elves = self
+            .elves
+            .iter()
+            .map(|entry| ElfSummary {
+                index: entry.elf.index,
+                lines: entry.elf.lines.clone(),
+                items: entry.items.len(),
+                calories: entry.elf.calories,
+            })
+            .collect();
+        let top = top_elves(self.elves.iter().map(|entry| entry.elf.clone()), top)
+            .into_iter()
+            .zip(1..)
+            .map(|(elf, rank)| TopElf {
+                rank,
+                index: elf.index,
+                lines: elf.lines,
+                calories: elf.calories,
+            })
+            .collect();
+        LedgerReport {
+            elves,
+            top,
+            statistics: Statistics::new(&self.elves, bins),
+        }
+    }
+}
+
+/// The summary of every elf, and statistics on their totals.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct LedgerReport {
+    pub elves: Vec<ElfSummary>,
+    /// The elves carrying the most calories, from most to least.
+    pub top: Vec<TopElf>,
+    /// The statistics, unless there are no elves.
+    pub statistics: Option<Statistics>,
+}
+
+/// One elf's line in the ledger.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ElfSummary {
+    /// The elf's position in the input, counting from 0.
+    pub index: usize,
+    /// The line numbers of the elf's items, counting from 1.
+    pub lines: Range<usize>,
+    /// The number of items the elf carries.
+    pub items: usize,
+    /// The total calories of the elf's items.
+    pub calories: u64,
+}
+
+/// One of the elves carrying the most calories.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct TopElf {
+    /// The elf's place in the ranking, counting from 1.
+    pub rank: usize,
+    /// The elf's position in the input, counting from 0.
+    pub index: usize,
+    /// The line numbers of the elf's items, counting from 1.
+    pub lines: Range<usize>,
+    pub calories: u64,
+}
+
+/// Statistics on the elves' totals.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Statistics {
+    /// The number of items carried by all the elves.
+    pub items: usize,
+    pub mean: f64,
+    pub median: f64,
+    /// The population standard deviation.
+    pub std_dev: f64,
+    pub histogram: Vec<Bin>,
+    /// The elves whose totals lie outside Tukey's fences, in input order.
+    pub outliers: Vec<Outlier>,
+}
+
+/// A range of totals, and how many elves carry a total within it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct Bin {
+    /// The smallest total in the bin.
+    pub from: u64,
+    /// The largest total in the bin.
+    pub to: u64,
+    pub elves: usize,
+}
+
+/// An elf carrying unusually many or few calories.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct Outlier {
+    /// The elf's position in the input, counting from 0.
+    pub elf: usize,
+    pub calories: u64,
+    pub side: Side,
+}
+
+/// Which side of the usual totals an outlier lies.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    Low,
+    High,
+}
+
+impl Statistics {
+    fn new(elves: &[LedgerEntry], bins: usize) -> Option<Self> {
+        let mut totals = elves.iter().map(|e| e.elf.calories).collect::<Vec<_>>();
+        totals.sort_unstable();
+        let (&min, &max) = (totals.first()?, totals.last()?);
+
+        let count = totals.len() as f64;
+        let mean = totals.iter().map(|&t| t as f64).sum::<f64>() / count;
+        let variance = totals
+            .iter()
+            .map(|&t| (t as f64 - mean).powi(2))
+            .sum::<f64>()
+            / count;
+
+        let (q1, q3) = (quantile(&totals, 0.25), quantile(&totals, 0.75));
+        let fence = OUTLIER_FACTOR * (q3 - q1);
+        let outliers = elves
+            .iter()
+            .filter_map(|e| {
+                let calories = e.elf.calories;
+                let side = if (calories as f64) < q1 - fence {
+                    Side::Low
+                } else if (calories as f64) > q3 + fence {
+                    Side::High
+                } else {
+                    return None;
+                };
+                Some(Outlier {
+                    elf: e.elf.index,
+                    calories,
+                    side,
+                })
+            })
+            .collect();
+
+        Some(Self {
+            items: elves.iter().map(|e| e.items.len()).sum(),
+            mean,
+            median: quantile(&totals, 0.5),
+            std_dev: variance.sqrt(),
+            histogram: histogram(&totals, min, max, bins),
+            outliers,
+        })
+    }
+}
+
+/// The `q` quantile of sorted, non-empty values, interpolating between the nearest two.
+fn quantile(sorted: &[u64], q: f64) -> f64 {
+    let position = q * (sorted.len() - 1) as f64;
+    let (below, above) = (position.floor() as usize, position.ceil() as usize);
+    let fraction = position - below as f64;
+    sorted[below] as f64 + (sorted[above] as f64 - sorted[below] as f64) * fraction
+}
+
+/// Counts the sorted totals into at most `bins` bins of equal width, spanning `min` to `max`.
+fn histogram(sorted: &[u64], min: u64, max: u64, bins: usize) -> Vec<Bin> {
+    let bins = bins.max(1) as u64;
+    let width = ((max - min) / bins).saturating_add(1);
+    let mut histogram = (0..=(max - min) / width)
+        .map(|bin| {
+            let from = min + bin * width;
+            Bin {
+                from,
+                to: from.saturating_add(width - 1).min(max),
+                elves: 0,
+            }
+        })
+        .collect::<Vec<_>>();
+    for &total in sorted {
+        histogram[((total - min) / width) as usize].elves += 1;
+    }
+    histogram
+}
+
+impl LedgerReport {
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+impl fmt::Display for LedgerReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rows = self
+            .elves
+            .iter()
+            .map(|elf| {
+                [
+                    elf.index.to_string(),
+                    describe_lines(&elf.lines),
+                    elf.items.to_string(),
+                    elf.calories.to_string(),
+                ]
+            })
+            .collect::<Vec<_>>();
+        let header = ["elf", "lines", "items", "calories"];
+        let width = |column: usize| {
+            rows.iter()
+                .map(|row| row[column].len())
+                .chain([header[column].len()])
+                .max()
+                .unwrap_or(0)
+        };
+        let widths = [0, 1, 2, 3].map(width);
+        for row in [header.map(str::to_owned)].iter().chain(&rows) {
+            writeln!(
+                f,
+                "{:>w0$}  {:<w1$}  {:>w2$}  {:>w3$}",
+                row[0],
+                row[1],
+                row[2],
+                row[3],
+                w0 = widths[0],
+                w1 = widths[1],
+                w2 = widths[2],
+                w3 = widths[3],
+            )?;
+        }
+
+        let Some(stats) = &self.statistics else {
+            return write!(f, "\nno elves");
+        };
+        writeln!(
+            f,
+            "\n{} elves carrying {} items",
+            self.elves.len(),
+            stats.items
+        )?;
+        writeln!(f, "mean:               {:.1}", stats.mean)?;
+        writeln!(f, "median:             {:.1}", stats.median)?;
+        writeln!(f, "standard deviation: {:.1}", stats.std_dev)?;
+
+        writeln!(f, "\ncarrying the most:")?;
+        for elf in &self.top {
+            writeln!(
+                f,
+                "  {}. elf {} on lines {}: {}",
+                elf.rank,
+                elf.index,
+                describe_lines(&elf.lines),
+                elf.calories
+            )?;
+        }
+
+        writeln!(f)?;
+        let most = stats.histogram.iter().map(|b| b.elves).max().unwrap_or(0);
+        let from_width = stats.histogram.iter().map(|b| b.from.to_string().len());
+        let from_width = from_width.max().unwrap_or(0);
+        let to_width = stats.histogram.iter().map(|b| b.to.to_string().len());
+        let to_width = to_width.max().unwrap_or(0);
+        for bin in &stats.histogram {
+            let bar = "#".repeat((bin.elves * BAR_WIDTH).div_ceil(most.max(1)));
+            let bar = format!("{bar} {}", bin.elves);
+            writeln!(
+                f,
+                "{:>from_width$}-{:<to_width$} | {}",
+                bin.from,
+                bin.to,
+                bar.trim_start()
+            )?;
+        }
+
+        writeln!(f)?;
+        if stats.outliers.is_empty() {
+            return write!(f, "no outliers");
+        }
+        write!(f, "outliers:")?;
+        for outlier in &stats.outliers {
+            let side = match outlier.side {
+                Side::Low => "few",
+                Side::High => "many",
+            };
+            write!(
+                f,
+                "\n  elf {} carries unusually {side} calories: {}",
+                outlier.elf, outlier.calories
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The first and last of the line numbers, or `-` if there are none.
+fn describe_lines(lines: &Range<usize>) -> String {
+    match lines.len() {
+        0 => "-".to_owned(),
+        1 => lines.start.to_string(),
+        _ => format!("{}-{}", lines.start, lines.end - 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> LedgerReport {
+        let ledger = CalorieLedger::parse(include_str!("../../input/day01test")).unwrap();
+        assert_eq!(ledger.elves()[3].items, [7000, 8000, 9000]);
+        ledger.report(4, 3)
+    }
+
+    #[test]
+    fn summarises_the_totals() {
+        let stats = example().statistics.unwrap();
+        assert_eq!(stats.items, 10);
+        assert_eq!((stats.mean, stats.median), (11000.0, 10000.0));
+        assert!((stats.std_dev - 48_800_000f64.sqrt()).abs() < 1e-6);
+        assert_eq!(
+            stats.outliers,
+            [Outlier {
+                elf: 3,
+                calories: 24000,
+                side: Side::High
+            }]
+        );
+    }
+
+    #[test]
+    fn counts_totals_into_bins() {
+        let histogram = example().statistics.unwrap().histogram;
+        let counts = histogram
+            .iter()
+            .map(|bin| (bin.from, bin.to, bin.elves))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            counts,
+            [
+                (4000, 9000, 2),
+                (9001, 14001, 2),
+                (14002, 19002, 0),
+                (19003, 24000, 1)
+            ]
+        );
+        let equal = CalorieLedger::parse("5\n\n5\n").unwrap().report(10, 3);
+        let bins = equal.statistics.unwrap().histogram;
+        assert_eq!(
+            bins,
+            [Bin {
+                from: 5,
+                to: 5,
+                elves: 2
+            }]
+        );
+    }
+
+    #[test]
+    fn prints_text_and_json() {
+        let report = example();
+        let text = report.to_string();
+        assert!(text.starts_with("elf  lines  items  calories\n  0  1-3        3      6000\n"));
+        assert!(text.contains("  3  10-12      3     24000\n"));
+        assert!(text.contains("median:             10000.0\n"));
+        assert!(text.contains(
+            "carrying the most:\n  1. elf 3 on lines 10-12: 24000\n  2. elf 2 on lines 7-8: 11000\n  \
+             3. elf 4 on lines 14: 10000\n"
+        ));
+        assert!(text.ends_with("elf 3 carries unusually many calories: 24000"));
+
+        let json = serde_json::from_str::<serde_json::Value>(&report.to_json().unwrap()).unwrap();
+        assert_eq!(json["elves"][3]["lines"]["start"], 10);
+        assert_eq!(json["statistics"]["outliers"][0]["side"], "high");
+        assert_eq!(json["top"][1]["index"], 2);
+        assert_eq!(json["top"][1]["lines"]["end"], 9);
+    }
+
+    #[test]
+    fn reports_an_empty_ledger() {
+        let report = CalorieLedger::parse("").unwrap().report(10, 3);
+        assert_eq!(report.statistics, None);
+        assert!(report.to_string().ends_with("no elves"));
+    }
+}
@@ -0,0 +1,281 @@
+//! Reading a list of calories in parallel, for lists too large to read quickly on one thread.
+//!
+//! The list is cut into chunks after blank lines, which no elf spans, and each chunk is read on
+//! its own thread with its elves and lines numbered from the start of the chunk. The chunks are
+//! then renumbered in input order, so the elves, and any failure, are exactly those of reading the
+//! list in one go.
+
+use std::io::BufRead;
+
+use rayon::prelude::*;
+
+use super::{
+    add_calories, rank, sum_calories, Day01Error, Day01Params, Elf, Grouper, ParseMode, Problem,
+    Skipped, TopN,
+};
+use crate::{parsing::ParseError, stream::Input, Error};
+
+/// Whether a line, including its line ending, ends an elf's items wherever it appears.
+///
+/// A line of only whitespace separates elves when parsing leniently, and is malformed when parsing
+/// strictly, so no elf continues past it either way.
+fn separates(line: &[u8]) -> bool {
+    line.iter().all(u8::is_ascii_whitespace)
+}
+
+/// Cuts the input into chunks of at least `size` bytes unless the input ends first, each ending
+/// after a separating line, just as [`Input::blocks`] cuts a stream.
+fn split(input: &str, size: usize) -> Vec<&str> {
+    let mut chunks = Vec::new();
+    let (mut start, mut end) = (0, 0);
+    for line in input.split_inclusive('\n') {
+        end += line.len();
+        if end - start >= size && line.ends_with('\n') && separates(line.as_bytes()) {
+            chunks.push(&input[start..end]);
+            start = end;
+        }
+    }
+    if start < input.len() {
+        chunks.push(&input[start..]);
+    }
+    chunks
+}
+
+/// The elves read from some chunks, numbered in input order.
+struct Grouped {
+    elves: Vec<Elf>,
+    /// The number of elves in the chunks, including any not kept.
+    count: usize,
+    /// The number of lines in the chunks.
+    lines: usize,
+}
+
+/// The elves read from one chunk, numbered from the start of the chunk.
+struct Chunk {
+    elves: Vec<Elf>,
+    count: usize,
+    lines: usize,
+    skipped: Vec<Skipped>,
+}
+
+/// Reads one chunk, keeping the elves chosen by `keep`.
+fn group<'c>(
+    text: &'c str,
+    mode: ParseMode,
+    keep: impl Fn(Vec<Elf>) -> Vec<Elf>,
+) -> Result<Chunk, Problem<'c>> {
+    let mut grouper = Grouper::new(mode, || 0, add_calories);
+    grouper.skipped = Some(Vec::new());
+    let mut elves = Vec::new();
+    let mut lines = 0;
+    for (line, number) in text.lines().zip(1..) {
+        elves.extend(grouper.line(number, line)?.map(Elf::from));
+        lines = number;
+    }
+    let skipped = grouper.skipped.take().unwrap_or_default();
+    elves.extend(grouper.finish().map(Elf::from));
+    Ok(Chunk {
+        count: elves.len(),
+        elves: keep(elves),
+        lines,
+        skipped,
+    })
+}
+
+/// Reads the chunks in parallel, keeping the elves chosen by `keep` from each, then numbers them
+/// in input order following `first_line` and `first_elf`.
+///
+/// Fails with the first problem in the chunks, as reading them in order would.
+fn group_all(
+    chunks: &[&str],
+    mode: ParseMode,
+    (first_line, first_elf): (usize, usize),
+    keep: impl Fn(Vec<Elf>) -> Vec<Elf> + Sync,
+) -> Result<Grouped, Error<Day01Error>> {
+    let chunks = chunks
+        .par_iter()
+        .map(|&text| group(text, mode, &keep).map_err(|problem| (problem, text)))
+        .collect::<Vec<_>>();
+
+    let mut grouped = Grouped {
+        elves: Vec::new(),
+        count: 0,
+        lines: 0,
+    };
+    for chunk in chunks {
+        let (line_offset, elf_offset) = (first_line - 1 + grouped.lines, first_elf + grouped.count);
+        let chunk = chunk.map_err(|(problem, text)| {
+            let problem = match problem {
+                Problem::NotANumber { at, line, elf } => Problem::NotANumber {
+                    at,
+                    line: line + line_offset,
+                    elf: elf + elf_offset,
+                },
+                problem => problem,
+            };
+            problem
+                .locate(|at, line, expected| ParseError::expected(text, at, expected).on_line(line))
+        })?;
+        for skipped in chunk.skipped {
+            Skipped {
+                line: skipped.line + line_offset,
+                elf: skipped.elf + elf_offset,
+                ..skipped
+            }
+            .warn();
+        }
+        grouped.elves.extend(chunk.elves.into_iter().map(|elf| Elf {
+            index: elf.index + elf_offset,
+            lines: elf.lines.start + line_offset..elf.lines.end + line_offset,
+            ..elf
+        }));
+        grouped.count += chunk.count;
+        grouped.lines += chunk.lines;
+    }
+    Ok(grouped)
+}
+
+/// Reads the items carried by each elf, reading chunks of about `chunk_size` bytes in parallel.
+pub(super) fn elves(
+    input: &str,
+    mode: ParseMode,
+    chunk_size: usize,
+) -> Result<Vec<Elf>, Error<Day01Error>> {
+    let chunks = split(input, chunk_size);
+    Ok(group_all(&chunks, mode, (1, 0), |elves| elves)?.elves)
+}
+
+/// Solves both parts while reading the input in blocks of about the chunk size, reading as many
+/// blocks at once as there are threads.
+///
+/// Each block keeps only its own top elves, which are then merged.
+pub(super) fn solve_blocks<R: BufRead>(
+    input: &mut Input<R>,
+    params: &Day01Params,
+) -> Result<(u64, u64), Error<Day01Error>> {
+    let keep = |elves: Vec<Elf>| {
+        let mut top = TopN::new(params.top);
+        for elf in elves {
+            top.push(rank(&elf), elf);
+        }
+        top.into_sorted_vec()
+    };
+
+    let mut top = TopN::new(params.top);
+    let mut most = 0;
+    let (mut lines, mut count) = (0, 0);
+    let mut blocks = input.blocks(params.chunk_size, separates);
+    loop {
+        let round = blocks
+            .by_ref()
+            .take(rayon::current_num_threads())
+            .collect::<Vec<_>>();
+        if round.is_empty() {
+            break;
+        }
+        let texts = round.iter().map(|block| block.text()).collect::<Vec<_>>();
+        let grouped = group_all(&texts, params.mode, (lines + 1, count), keep)?;
+        for elf in grouped.elves {
+            most = most.max(elf.calories);
+            top.push(rank(&elf), elf);
+        }
+        lines += grouped.lines;
+        count += grouped.count;
+    }
+    Ok((most, sum_calories(&top.into_sorted_vec())?))
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::{prelude::*, sample::select};
+    use test_case::test_case;
+
+    use super::*;
+    use crate::{day01::Day01, params, Runner, Solution};
+
+    #[test_case("1\n2\n\n3\n\n\n4\n5", 3 => vec!["1\n2\n\n", "3\n\n", "\n4\n5"] ; "blank lines")]
+    #[test_case("1\n \n2\r\n\r\n3\n", 1 => vec!["1\n \n", "2\r\n\r\n", "3\n"] ; "whitespace")]
+    #[test_case("1000\n2000\n3000\n", 1 => vec!["1000\n2000\n3000\n"] ; "no separator")]
+    #[test_case("", 1 => Vec::<&str>::new() ; "empty")]
+    fn splits_after_separating_lines(input: &str, size: usize) -> Vec<&str> {
+        split(input, size)
+    }
+
+    #[test]
+    fn renumbers_failures_in_later_chunks() {
+        let input = "1\n\n2\n\n\nx\n";
+        assert_eq!(
+            elves(input, ParseMode::Strict, 1),
+            super::super::elves(input, ParseMode::Strict)
+        );
+    }
+
+    /// A list of calories with some of the mistakes lenient parsing forgives.
+    fn messy_lines() -> impl Strategy<Value = String> {
+        let line = select(vec![
+            "1000",
+            "2000",
+            "35",
+            "0",
+            "",
+            "",
+            " ",
+            "\r",
+            " 7 ",
+            "x",
+            "18446744073709551615",
+        ]);
+        prop::collection::vec(line, 0..60).prop_map(|lines| lines.join("\n"))
+    }
+
+    proptest! {
+        #[test]
+        fn agrees_with_reading_in_one_go(
+            input in messy_lines(),
+            lenient: bool,
+            chunk_size in 1..40usize,
+            top in 1..5usize,
+        ) {
+            let mode = if lenient { ParseMode::Lenient } else { ParseMode::Strict };
+            prop_assert_eq!(
+                elves(&input, mode, chunk_size),
+                super::super::elves(&input, mode)
+            );
+
+            let overrides = params::overrides(&[
+                &format!("mode={mode}"),
+                &format!("top={top}"),
+                &format!("chunk_size={chunk_size}"),
+            ]);
+            let sequential = Day01.configure(&overrides).unwrap();
+            let parallel = sequential
+                .configure(&params::overrides(&["parallel=true"]))
+                .unwrap();
+            let describe = |e: color_eyre::Report| format!("{e:#}");
+            prop_assert_eq!(
+                parallel.run(&input).map_err(describe),
+                sequential.run(&input).map_err(describe)
+            );
+            prop_assert_eq!(
+                parallel
+                    .run_streaming(&mut input.as_bytes())
+                    .map_err(describe),
+                sequential
+                    .run_streaming(&mut input.as_bytes())
+                    .map_err(describe)
+            );
+        }
+
+        #[test]
+        fn solves_generated_inputs_alike(seed: u64, size in 1..30usize, chunk_size in 1..200usize) {
+            let input = Day01.generate(seed, size);
+            let parallel = Day01
+                .configure(&params::overrides(&["parallel=true", &format!("chunk_size={chunk_size}")]))
+                .unwrap();
+            let streamed = parallel.run_streaming(&mut input.as_bytes()).unwrap();
+            prop_assert_eq!(streamed, Some(parallel.run(&input).unwrap()));
+            prop_assert_eq!(parallel.run(&input).unwrap(), Day01.run(&input).unwrap());
+            prop_assert!(Day01::parse(&input).is_ok());
+        }
+    }
+}
@@ -0,0 +1,822 @@
+//! Day 2: Rock Paper Scissors
+
+use std::{cmp, collections::BTreeSet, fmt, io::BufRead, str};
+
+use color_eyre::Result;
+use itertools::Itertools;
+use nom::{
+    combinator::{map, peek},
+    error::context,
+    sequence::{pair, separated_pair},
+};
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    params::Params,
+    parsing::{self, lines, symbol, tag, IResult, ParseError},
+    stream::Input,
+    Error, PuzzleError, Solution, StreamResult,
+};
+
+pub mod readings;
+
+pub struct Day02;
+
+/// Why a strategy guide can't be read.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum Day02Error {
+    /// The parameters don't make a legend, as checked by [`Params::validate`].
+    #[error("the guide's legend is invalid: {0}")]
+    Legend(String),
+}
+
+impl PuzzleError for Day02Error {}
+
+/// The game being played, and what the strategy guide's symbols stand for.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Day02Params {
+    pub game: Game,
+    /// The moves meant by the first column, such as `A=Rock`.
+    pub opponent: SymbolMap,
+    /// The moves meant by the second column in the first part.
+    pub moves: SymbolMap,
+    /// The results meant by the second column in the second part, such as `X=Loss`.
+    pub results: SymbolMap,
+}
+
+impl Default for Day02Params {
+    fn default() -> Self {
+        let map = |s: &str| s.parse().expect("the puzzle's symbols are valid");
+        Self {
+            game: Game::RockPaperScissors,
+            opponent: map("A=Rock, B=Paper, C=Scissors"),
+            moves: map("X=Rock, Y=Paper, Z=Scissors"),
+            results: map("X=Loss, Y=Draw, Z=Win"),
+        }
+    }
+}
+
+impl Params for Day02Params {
+    fn validate(&self) -> Result<(), String> {
+        Legend::new(self).map(drop)
+    }
+}
+
+/// One of the built-in games.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Game {
+    /// The puzzle's game, from [`Ruleset::rock_paper_scissors`].
+    #[default]
+    #[serde(rename = "rps")]
+    RockPaperScissors,
+    /// The five move game from [`Ruleset::rock_paper_scissors_lizard_spock`].
+    #[serde(rename = "rpsls")]
+    RockPaperScissorsLizardSpock,
+}
+
+impl Game {
+    pub fn ruleset(self) -> Ruleset {
+        match self {
+            Self::RockPaperScissors => Ruleset::rock_paper_scissors(),
+            Self::RockPaperScissorsLizardSpock => Ruleset::rock_paper_scissors_lizard_spock(),
+        }
+    }
+}
+
+impl str::FromStr for Game {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "rps" => Ok(Self::RockPaperScissors),
+            "rpsls" => Ok(Self::RockPaperScissorsLizardSpock),
+            _ => Err(format!("expected `rps` or `rpsls`, found `{s}`")),
+        }
+    }
+}
+
+impl fmt::Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::RockPaperScissors => "rps",
+            Self::RockPaperScissorsLizardSpock => "rpsls",
+        })
+    }
+}
+
+/// Symbols and the names they stand for, written as `A=Rock, B=Paper, C=Scissors`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct SymbolMap(Vec<(String, String)>);
+
+impl SymbolMap {
+    /// Each symbol with the name it stands for, in the order given.
+    pub fn entries(&self) -> &[(String, String)] {
+        &self.0
+    }
+
+    pub fn symbols(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(|(symbol, _)| symbol.as_str())
+    }
+}
+
+impl str::FromStr for SymbolMap {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut entries = Vec::<(String, String)>::new();
+        for entry in s.split(',') {
+            let (symbol, name) = entry
+                .split_once('=')
+                .map(|(symbol, name)| (symbol.trim(), name.trim()))
+                .filter(|(symbol, name)| !symbol.is_empty() && !name.is_empty())
+                .ok_or_else(|| format!("expected `SYMBOL=NAME`, found `{}`", entry.trim()))?;
+            if symbol.contains(char::is_whitespace) {
+                return Err(format!(
+                    "symbols cannot contain whitespace, found `{symbol}`"
+                ));
+            }
+            if entries.iter().any(|(s, _)| s == symbol) {
+                return Err(format!("`{symbol}` stands for two things"));
+            }
+            entries.push((symbol.to_owned(), name.to_owned()));
+        }
+        Ok(Self(entries))
+    }
+}
+
+impl fmt::Display for SymbolMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let entries = self
+            .0
+            .iter()
+            .map(|(symbol, name)| format!("{symbol}={name}"));
+        f.write_str(&entries.format(", ").to_string())
+    }
+}
+
+impl TryFrom<String> for SymbolMap {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<SymbolMap> for String {
+    fn from(map: SymbolMap) -> Self {
+        map.to_string()
+    }
+}
+
+/// The key to a strategy guide: the game, and the move or result each symbol stands for.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Legend {
+    rules: Ruleset,
+    opponent: Vec<(String, Move)>,
+    moves: Vec<(String, Move)>,
+    results: Vec<(String, RoundResult)>,
+}
+
+impl Legend {
+    /// Looks up each symbol's meaning in the game, checking that the second column's symbols are
+    /// the same whether read as moves or as results.
+    pub fn new(params: &Day02Params) -> Result<Self, String> {
+        let rules = params.game.ruleset();
+        let moves = |map: &SymbolMap| {
+            map.entries()
+                .iter()
+                .map(|(symbol, name)| {
+                    let m = rules
+                        .find(name)
+                        .ok_or_else(|| format!("`{}` has no move called `{name}`", params.game))?;
+                    Ok((symbol.clone(), m))
+                })
+                .collect::<Result<Vec<_>, String>>()
+        };
+        let (opponent, ours) = (moves(&params.opponent)?, moves(&params.moves)?);
+        let results = params
+            .results
+            .entries()
+            .iter()
+            .map(|(symbol, name)| Ok((symbol.clone(), name.parse()?)))
+            .collect::<Result<Vec<_>, String>>()?;
+        if params.moves.symbols().collect::<BTreeSet<_>>()
+            != params.results.symbols().collect::<BTreeSet<_>>()
+        {
+            return Err(format!(
+                "the second column must have the same symbols read as moves ({}) as read as \
+                 results ({})",
+                params.moves.symbols().join(", "),
+                params.results.symbols().join(", ")
+            ));
+        }
+        Ok(Self {
+            rules,
+            opponent,
+            moves: ours,
+            results,
+        })
+    }
+
+    pub fn rules(&self) -> &Ruleset {
+        &self.rules
+    }
+}
+
+impl Solution for Day02 {
+    const DAY: u8 = 2;
+
+    type Parsed<'i> = Vec<(Round, EspRound)>;
+    type Part1 = u32;
+    type Part2 = u32;
+    type Error = Day02Error;
+    type Params = Day02Params;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, Error<Day02Error>> {
+        Self::parse_with(input, &Day02Params::default())
+    }
+
+    fn parse_with<'i>(
+        input: &'i str,
+        params: &Day02Params,
+    ) -> Result<Self::Parsed<'i>, Error<Day02Error>> {
+        let legend = Legend::new(params).map_err(Day02Error::Legend)?;
+        Ok(parsing::parse_all(input, lines(both_rounds(&legend)))?)
+    }
+
+    fn part1(rounds: &Self::Parsed<'_>) -> Result<Self::Part1, Error<Day02Error>> {
+        Self::part1_with(rounds, &Day02Params::default())
+    }
+
+    fn part2(rounds: &Self::Parsed<'_>) -> Result<Self::Part2, Error<Day02Error>> {
+        Self::part2_with(rounds, &Day02Params::default())
+    }
+
+    fn part1_with(
+        rounds: &Self::Parsed<'_>,
+        params: &Day02Params,
+    ) -> Result<Self::Part1, Error<Day02Error>> {
+        let rules = params.game.ruleset();
+        Ok(rounds.iter().map(|(round, _)| round.score(&rules)).sum())
+    }
+
+    fn part2_with(
+        rounds: &Self::Parsed<'_>,
+        params: &Day02Params,
+    ) -> Result<Self::Part2, Error<Day02Error>> {
+        let rules = params.game.ruleset();
+        Ok(rounds
+            .iter()
+            .map(|(_, esp_round)| esp_round.score(&rules))
+            .sum())
+    }
+
+    fn solve_streaming<R: BufRead>(input: &mut Input<R>) -> StreamResult<Self> {
+        Self::solve_streaming_with(input, &Day02Params::default())
+    }
+
+    fn solve_streaming_with<R: BufRead>(
+        input: &mut Input<R>,
+        params: &Day02Params,
+    ) -> StreamResult<Self> {
+        let legend = Legend::new(params).map_err(Day02Error::Legend)?;
+        let (mut part1, mut part2) = (0, 0);
+        for line in input.lines() {
+            let (round, esp_round) = line.parse(both_rounds(&legend))?;
+            part1 += round.score(&legend.rules);
+            part2 += esp_round.score(&legend.rules);
+        }
+        Ok(Some((part1, part2)))
+    }
+
+    fn generate<R: Rng>(rng: &mut R, size: usize) -> String {
+        Self::generate_with(rng, size, &Day02Params::default())
+    }
+
+    /// Generates `size` rounds from the guide's symbols.
+    fn generate_with<R: Rng>(rng: &mut R, size: usize, params: &Day02Params) -> String {
+        let opponent = params.opponent.symbols().collect::<Vec<_>>();
+        let us = params.moves.symbols().collect::<Vec<_>>();
+        (0..size)
+            .map(|_| {
+                let opponent = opponent.choose(rng).unwrap();
+                let us = us.choose(rng).unwrap();
+                format!("{opponent} {us}\n")
+            })
+            .collect()
+    }
+}
+
+/// A line read both ways.
+fn both_rounds<'l, 'i: 'l>(
+    legend: &'l Legend,
+) -> impl FnMut(&'i str) -> IResult<'i, (Round, EspRound)> + 'l {
+    context("round", pair(peek(round(legend)), esp_round(legend)))
+}
+
+fn round<'l, 'i: 'l>(legend: &'l Legend) -> impl FnMut(&'i str) -> IResult<'i, Round> + 'l {
+    map(
+        separated_pair(symbol(&legend.opponent), tag(" "), symbol(&legend.moves)),
+        |(opponent, us)| Round {
+            opponent: OpponentThrow(opponent),
+            us: OurThrow(us),
+        },
+    )
+}
+
+fn esp_round<'l, 'i: 'l>(legend: &'l Legend) -> impl FnMut(&'i str) -> IResult<'i, EspRound> + 'l {
+    map(
+        separated_pair(symbol(&legend.opponent), tag(" "), symbol(&legend.results)),
+        |(opponent, expected_result)| EspRound {
+            opponent: OpponentThrow(opponent),
+            expected_result,
+        },
+    )
+}
+
+/// A round read as the opponent's throw and the result the elf wants.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct EspRound {
+    pub opponent: OpponentThrow,
+    pub expected_result: RoundResult,
+}
+
+impl str::FromStr for EspRound {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let legend = Legend::new(&Day02Params::default()).expect("the puzzle's legend is valid");
+        parsing::parse_all(s, esp_round(&legend))
+    }
+}
+
+impl EspRound {
+    /// The throw which produces the expected result, choosing the highest scoring if several do.
+    pub fn best_move(&self, rules: &Ruleset) -> OurThrow {
+        OurThrow(rules.answer(self.opponent.r#move(), self.expected_result))
+    }
+
+    pub fn score(&self, rules: &Ruleset) -> u32 {
+        let throw_score = self.best_move(rules).score(rules);
+        let round_score = rules.outcome_score(self.expected_result);
+        round_score + throw_score
+    }
+}
+
+/// A round read as the opponent's throw and our throw.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Round {
+    pub opponent: OpponentThrow,
+    pub us: OurThrow,
+}
+
+impl str::FromStr for Round {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let legend = Legend::new(&Day02Params::default()).expect("the puzzle's legend is valid");
+        parsing::parse_all(s, round(&legend))
+    }
+}
+
+impl Round {
+    pub fn result(self, rules: &Ruleset) -> RoundResult {
+        rules.result(self.us.r#move(), self.opponent.r#move())
+    }
+
+    pub fn score(self, rules: &Ruleset) -> u32 {
+        let round_score = rules.outcome_score(self.result(rules));
+        let throw_score = self.us.score(rules);
+        round_score + throw_score
+    }
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct OurThrow(pub Move);
+
+impl OurThrow {
+    pub fn r#move(self) -> Move {
+        self.0
+    }
+
+    pub fn score(self, rules: &Ruleset) -> u32 {
+        rules.score(self.0)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct OpponentThrow(pub Move);
+
+impl OpponentThrow {
+    pub fn r#move(self) -> Move {
+        self.0
+    }
+}
+
+/// A move, by its position in the moves of a [`Ruleset`].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Move(usize);
+
+impl Move {
+    /// The moves of [`Ruleset::rock_paper_scissors`], which begin those of
+    /// [`Ruleset::rock_paper_scissors_lizard_spock`].
+    pub const ROCK: Self = Self(0);
+    pub const PAPER: Self = Self(1);
+    pub const SCISSORS: Self = Self(2);
+    pub const SPOCK: Self = Self(3);
+    pub const LIZARD: Self = Self(4);
+
+    /// The move's position in its ruleset, counting from 0.
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum RoundResult {
+    Loss,
+    Draw,
+    Win,
+}
+
+impl str::FromStr for RoundResult {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "loss" => Ok(Self::Loss),
+            "draw" => Ok(Self::Draw),
+            "win" => Ok(Self::Win),
+            _ => Err(format!("expected `Loss`, `Draw` or `Win`, found `{s}`")),
+        }
+    }
+}
+
+/// The rules of a game like rock paper scissors: its moves, which move beats which, and how
+/// rounds are scored.
+///
+/// Every pair of different moves must be decided one way, and every move must beat another and be
+/// beaten by another, so that any result can be played against any move.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ruleset {
+    names: Vec<String>,
+    /// The score for throwing each move.
+    scores: Vec<u32>,
+    /// Whether each move beats each other move.
+    beats: Vec<Vec<bool>>,
+    /// The scores for a loss, a draw and a win.
+    outcome_scores: [u32; 3],
+}
+
+/// Why a set of moves and the moves they beat aren't a game.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum RulesetError {
+    #[error("a game needs at least one move")]
+    NoMoves,
+    #[error("there are two moves called `{0}`")]
+    DuplicateMove(String),
+    #[error("there is no move called `{0}`")]
+    UnknownMove(String),
+    #[error("`{0}` beats itself")]
+    BeatsItself(String),
+    #[error("`{0}` and `{1}` beat each other")]
+    BeatEachOther(String, String),
+    #[error("neither `{0}` nor `{1}` beats the other")]
+    Undecided(String, String),
+    #[error("`{0}` beats no other move")]
+    BeatsNothing(String),
+    #[error("no move beats `{0}`")]
+    Unbeatable(String),
+    #[error("a cyclic game needs an odd number of moves, found {0}")]
+    EvenCycle(usize),
+}
+
+impl Ruleset {
+    /// A game of the given moves, each with the score for throwing it, where each pair in `beats`
+    /// names a move and a move it beats.
+    ///
+    /// Rounds are scored 0 for a loss, 3 for a draw and 6 for a win, as in the puzzle.
+    pub fn new(moves: &[(&str, u32)], beats: &[(&str, &str)]) -> Result<Self, RulesetError> {
+        let mut rules = Self {
+            names: Vec::new(),
+            scores: Vec::new(),
+            beats: vec![vec![false; moves.len()]; moves.len()],
+            outcome_scores: [0, 3, 6],
+        };
+        for &(name, score) in moves {
+            if rules.find(name).is_some() {
+                return Err(RulesetError::DuplicateMove(name.to_owned()));
+            }
+            rules.names.push(name.to_owned());
+            rules.scores.push(score);
+        }
+        for &(winner, loser) in beats {
+            let find = |name: &str| {
+                rules
+                    .find(name)
+                    .ok_or_else(|| RulesetError::UnknownMove(name.to_owned()))
+            };
+            let (winner, loser) = (find(winner)?, find(loser)?);
+            rules.beats[winner.0][loser.0] = true;
+        }
+        rules.check()?;
+        Ok(rules)
+    }
+
+    /// A balanced game of an odd number of moves in a cycle, where each move beats the moves an
+    /// odd number of places before it.
+    ///
+    /// Every move beats as many moves as beat it. In rock paper scissors, each move beats the one
+    /// before it, going round so that rock beats scissors.
+    pub fn cyclic(moves: &[(&str, u32)]) -> Result<Self, RulesetError> {
+        let n = moves.len();
+        if n.is_multiple_of(2) {
+            return Err(RulesetError::EvenCycle(n));
+        }
+        let beats = (0..n)
+            .flat_map(|i| (1..n).step_by(2).map(move |d| (i, (i + n - d) % n)))
+            .map(|(winner, loser)| (moves[winner].0, moves[loser].0))
+            .collect::<Vec<_>>();
+        Self::new(moves, &beats)
+    }
+
+    /// The puzzle's game, scoring rock, paper and scissors 1, 2 and 3.
+    pub fn rock_paper_scissors() -> Self {
+        Self::cyclic(&[("Rock", 1), ("Paper", 2), ("Scissors", 3)])
+            .expect("rock paper scissors is a game")
+    }
+
+    /// Rock paper scissors with two more moves: Spock smashes scissors and vaporises rock, and
+    /// lizard eats paper and poisons Spock, but each loses to the other two.
+    pub fn rock_paper_scissors_lizard_spock() -> Self {
+        Self::cyclic(&[
+            ("Rock", 1),
+            ("Paper", 2),
+            ("Scissors", 3),
+            ("Spock", 4),
+            ("Lizard", 5),
+        ])
+        .expect("rock paper scissors lizard Spock is a game")
+    }
+
+    /// Scores rounds `loss`, `draw` and `win` for their results instead.
+    pub fn with_outcome_scores(mut self, loss: u32, draw: u32, win: u32) -> Self {
+        self.outcome_scores = [loss, draw, win];
+        self
+    }
+
+    /// The game's moves, in order.
+    pub fn moves(&self) -> impl Iterator<Item = Move> {
+        (0..self.names.len()).map(Move)
+    }
+
+    /// The move with the given name, ignoring case.
+    pub fn find(&self, name: &str) -> Option<Move> {
+        let index = self
+            .names
+            .iter()
+            .position(|n| n.eq_ignore_ascii_case(name))?;
+        Some(Move(index))
+    }
+
+    pub fn name(&self, m: Move) -> &str {
+        &self.names[m.0]
+    }
+
+    /// The score for throwing a move.
+    pub fn score(&self, m: Move) -> u32 {
+        self.scores[m.0]
+    }
+
+    /// The score for a round with the given result.
+    pub fn outcome_score(&self, result: RoundResult) -> u32 {
+        self.outcome_scores[result as usize]
+    }
+
+    /// Whether `winner` beats `loser`.
+    pub fn beats(&self, winner: Move, loser: Move) -> bool {
+        self.beats[winner.0][loser.0]
+    }
+
+    /// The result of throwing `us` against `opponent`.
+    pub fn result(&self, us: Move, opponent: Move) -> RoundResult {
+        if self.beats(us, opponent) {
+            RoundResult::Win
+        } else if self.beats(opponent, us) {
+            RoundResult::Loss
+        } else {
+            RoundResult::Draw
+        }
+    }
+
+    /// The highest scoring move giving the result against `opponent`, the earliest if several
+    /// score the same.
+    pub fn answer(&self, opponent: Move, result: RoundResult) -> Move {
+        self.moves()
+            .filter(|&m| self.result(m, opponent) == result)
+            .max_by_key(|&m| (self.score(m), cmp::Reverse(m)))
+            .expect("every result can be played against every move")
+    }
+
+    /// Checks that every round has a result, and that every result can be played.
+    fn check(&self) -> Result<(), RulesetError> {
+        if self.names.is_empty() {
+            return Err(RulesetError::NoMoves);
+        }
+        for a in self.moves() {
+            let name = || self.name(a).to_owned();
+            if self.beats(a, a) {
+                return Err(RulesetError::BeatsItself(name()));
+            }
+            for b in self.moves().filter(|&b| b > a) {
+                match (self.beats(a, b), self.beats(b, a)) {
+                    (true, true) => {
+                        return Err(RulesetError::BeatEachOther(name(), self.name(b).to_owned()))
+                    }
+                    (false, false) => {
+                        return Err(RulesetError::Undecided(name(), self.name(b).to_owned()))
+                    }
+                    _ => {}
+                }
+            }
+            if !self.moves().any(|b| self.beats(a, b)) {
+                return Err(RulesetError::BeatsNothing(name()));
+            }
+            if !self.moves().any(|b| self.beats(b, a)) {
+                return Err(RulesetError::Unbeatable(name()));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl cmp::PartialEq<OurThrow> for OpponentThrow {
+    fn eq(&self, other: &OurThrow) -> bool {
+        self.0 == other.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::proptest;
+    use test_case::test_case;
+
+    use super::*;
+    use crate::{differential, params, Runner};
+
+    #[test]
+    fn spock_and_lizard_balance_the_game() {
+        let rules = Ruleset::rock_paper_scissors_lizard_spock();
+        for m in rules.moves() {
+            assert_eq!(rules.moves().filter(|&o| rules.beats(m, o)).count(), 2);
+        }
+        assert!(rules.beats(Move::SPOCK, Move::SCISSORS) && rules.beats(Move::SPOCK, Move::ROCK));
+        assert!(rules.beats(Move::LIZARD, Move::PAPER) && rules.beats(Move::LIZARD, Move::SPOCK));
+        assert_eq!(rules.result(Move::ROCK, Move::LIZARD), RoundResult::Win);
+        assert_eq!(rules.find("spock"), Some(Move::SPOCK));
+    }
+
+    #[test_case(Move::ROCK, RoundResult::Win => Move::SPOCK ; "win against rock")]
+    #[test_case(Move::ROCK, RoundResult::Loss => Move::LIZARD ; "lose against rock")]
+    #[test_case(Move::LIZARD, RoundResult::Win => Move::SCISSORS ; "win against lizard")]
+    #[test_case(Move::PAPER, RoundResult::Draw => Move::PAPER ; "draw")]
+    fn plays_the_highest_scoring_move(opponent: Move, expected_result: RoundResult) -> Move {
+        let round = EspRound {
+            opponent: OpponentThrow(opponent),
+            expected_result,
+        };
+        round
+            .best_move(&Ruleset::rock_paper_scissors_lizard_spock())
+            .r#move()
+    }
+
+    #[test]
+    fn scores_outcomes_by_the_rules() {
+        let round = "A Y".parse::<Round>().unwrap();
+        assert_eq!(round.score(&Ruleset::rock_paper_scissors()), 8);
+        let rules = Ruleset::rock_paper_scissors().with_outcome_scores(0, 1, 2);
+        assert_eq!(round.score(&rules), 4);
+        let esp = "C Z".parse::<EspRound>().unwrap();
+        assert_eq!(esp.score(&rules), 3);
+    }
+
+    #[test_case(&[], &[] => RulesetError::NoMoves ; "no moves")]
+    #[test_case(&[("a", 1), ("A", 2)], &[] => RulesetError::DuplicateMove("A".to_owned()) ; "duplicate")]
+    #[test_case(&[("a", 1)], &[("a", "b")] => RulesetError::UnknownMove("b".to_owned()) ; "unknown")]
+    #[test_case(&[("a", 1)], &[("a", "a")] => RulesetError::BeatsItself("a".to_owned()) ; "itself")]
+    #[test_case(&[("a", 1), ("b", 2)], &[("a", "b"), ("b", "a")]
+        => RulesetError::BeatEachOther("a".to_owned(), "b".to_owned()) ; "each other")]
+    #[test_case(&[("a", 1), ("b", 2)], &[] => RulesetError::Undecided("a".to_owned(), "b".to_owned()) ; "undecided")]
+    #[test_case(&[("a", 1), ("b", 2)], &[("a", "b")] => RulesetError::Unbeatable("a".to_owned()) ; "unbeatable")]
+    #[test_case(&[("a", 1), ("b", 2), ("c", 3)], &[("a", "b"), ("c", "a"), ("c", "b")]
+        => RulesetError::BeatsNothing("b".to_owned()) ; "beats nothing")]
+    fn rejects_games_without_every_result(
+        moves: &[(&str, u32)],
+        beats: &[(&str, &str)],
+    ) -> RulesetError {
+        Ruleset::new(moves, beats).unwrap_err()
+    }
+
+    #[test_case("A=Rock, B=Paper" => Ok(owned(&[("A", "Rock"), ("B", "Paper")])) ; "pairs")]
+    #[test_case(" X = Loss ,Y=Win" => Ok(owned(&[("X", "Loss"), ("Y", "Win")])) ; "spaced")]
+    #[test_case("A=Rock, A=Paper" => Err("`A` stands for two things".to_owned()) ; "repeated")]
+    #[test_case("A Rock" => Err("expected `SYMBOL=NAME`, found `A Rock`".to_owned()) ; "no equals")]
+    #[test_case("A=" => Err("expected `SYMBOL=NAME`, found `A=`".to_owned()) ; "no name")]
+    #[test_case("A B=Rock" => Err("symbols cannot contain whitespace, found `A B`".to_owned()) ; "spaced symbol")]
+    fn parses_symbol_maps(s: &str) -> Result<Vec<(String, String)>, String> {
+        let map = s.parse::<SymbolMap>()?;
+        assert_eq!(map.to_string().parse(), Ok(map.clone()));
+        Ok(map.entries().to_vec())
+    }
+
+    fn owned(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|&(symbol, name)| (symbol.to_owned(), name.to_owned()))
+            .collect()
+    }
+
+    #[test]
+    fn reads_the_guide_by_its_legend() {
+        // Reading `X` as a win and `Y` as a loss changes only the second part.
+        let configured = Day02
+            .configure(&params::overrides(&["results=X=Win, Y=Loss, Z=Draw"]))
+            .unwrap();
+        let answers = configured.run("A Y\nB X\nC Z\n").unwrap();
+        assert_eq!((&*answers.part1, &*answers.part2), ("15", "18"));
+
+        let configured = Day02
+            .configure(&params::overrides(&[
+                "game=rpsls",
+                "opponent=R=Rock, P=Paper, S=Scissors, K=Spock, L=Lizard",
+                "moves=1=Spock, 2=Lizard",
+                "results=1=Win, 2=Loss",
+            ]))
+            .unwrap();
+        let answers = configured.run("R 1\nK 2\nL 1\n").unwrap();
+        // Spock vaporises rock and lizard poisons Spock, but Spock loses to lizard. Asked to win,
+        // the elf throws Spock against rock and scissors against lizard, and asked to lose to
+        // Spock, scissors, which score more than rock.
+        assert_eq!((&*answers.part1, &*answers.part2), ("25", "22"));
+        let error = configured.run("R 1\nQ 2\n").unwrap_err();
+        assert!(format!("{error:#}").contains("expected `R`, `P`, `S`, `K` or `L`, found `Q`"));
+    }
+
+    #[test_case("game=chess" => "unknown variant `chess`, expected `rps` or `rpsls` for key `game`" ; "unknown game")]
+    #[test_case("opponent=A=Rock, B=Spock" => "`rps` has no move called `Spock`" ; "unknown move")]
+    #[test_case("results=X=Loss, Y=Tie, Z=Win" => "expected `Loss`, `Draw` or `Win`, found `Tie`" ; "unknown result")]
+    #[test_case("results=X=Loss, Y=Win" => "the second column must have the same symbols read as moves (X, Y, Z) as read as results (X, Y)" ; "different symbols")]
+    fn rejects_bad_legends(param: &str) -> String {
+        let Err(error) = Day02.configure(&params::overrides(&[param])) else {
+            panic!("expected `{param}` to be rejected");
+        };
+        let error = format!("{error:#}");
+        let start = error.rfind(": ").map_or(0, |i| i + 2);
+        error[start..].lines().next().unwrap_or_default().to_owned()
+    }
+
+    #[test]
+    fn rejects_unchecked_parameters() {
+        let params = Day02Params {
+            results: "X=Loss, Y=Win".parse().unwrap(),
+            ..Day02Params::default()
+        };
+        let error = Error::Puzzle(Day02Error::Legend(params.validate().unwrap_err()));
+        assert_eq!(Day02::parse_with("A X\n", &params), Err(error.clone()));
+        let mut input = Input::new("A X\n".as_bytes());
+        assert_eq!(Day02::solve_streaming_with(&mut input, &params), Err(error));
+    }
+
+    #[test]
+    fn cycles_need_an_odd_number_of_moves() {
+        let moves = [("a", 1), ("b", 2), ("c", 3), ("d", 4)];
+        assert_eq!(Ruleset::cyclic(&moves), Err(RulesetError::EvenCycle(4)));
+        assert_eq!(
+            Ruleset::cyclic(&moves[..1]),
+            Err(RulesetError::BeatsNothing("a".to_owned()))
+        );
+    }
+
+    /// Scores each round by the shapes' positions in the cycle of rock, paper and scissors.
+    fn reference(input: &str) -> (u32, u32) {
+        let (mut part1, mut part2) = (0, 0);
+        for line in input.lines() {
+            let opponent = u32::from(line.as_bytes()[0] - b'A');
+            let column = u32::from(line.as_bytes()[2] - b'X');
+            // A loss, draw and win are 2, 0 and 1 shapes further round the cycle.
+            part1 += column + 1 + (column + 4 - opponent) % 3 * 3;
+            part2 += (opponent + column + 2) % 3 + 1 + column * 3;
+        }
+        (part1, part2)
+    }
+
+    proptest! {
+        #[test]
+        fn agrees_with_reference(input in differential::inputs(30)) {
+            differential::check::<Day02>(input, reference)?;
+        }
+    }
+}
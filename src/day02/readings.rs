@@ -0,0 +1,182 @@
+//! Scoring a strategy guide under every reading of its second column as moves.
+//!
+//! The puzzle never says for certain which move each of `X`, `Y` and `Z` stands for. [`Readings`]
+//! tries every way of assigning the legend's moves to those symbols, scoring the first part under
+//! each, so the best and worst readings of a guide can be compared.
+
+use std::fmt;
+
+use color_eyre::Result;
+use itertools::Itertools;
+use nom::sequence::separated_pair;
+use serde::Serialize;
+
+use super::{Day02Error, Day02Params, Legend, Move, SymbolMap};
+use crate::{
+    parsing::{self, lines, symbol, tag},
+    Error,
+};
+
+/// The first part's score under every reading of the second column, with the best and worst.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Readings {
+    /// Each distinct reading, starting with the legend's own.
+    pub readings: Vec<Reading>,
+    /// The highest scoring reading, the earliest if several score the same.
+    pub best: Reading,
+    /// The lowest scoring reading, the earliest if several score the same.
+    pub worst: Reading,
+}
+
+/// The moves the second column's symbols are read as, and the score they give.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Reading {
+    pub moves: SymbolMap,
+    pub score: u32,
+}
+
+impl Readings {
+    /// Scores the guide under every arrangement of the moves in `params.moves`.
+    pub fn score(input: &str, params: &Day02Params) -> Result<Self, Error<Day02Error>> {
+        let legend = Legend::new(params).map_err(Day02Error::Legend)?;
+        // Read each line as the opponent's move and the position of our symbol in the legend.
+        let positions = legend
+            .moves
+            .iter()
+            .enumerate()
+            .map(|(i, (symbol, _))| (symbol.clone(), i))
+            .collect::<Vec<_>>();
+        let rounds = parsing::parse_all(
+            input,
+            lines(separated_pair(
+                symbol(&legend.opponent),
+                tag(" "),
+                symbol(&positions),
+            )),
+        )?;
+
+        let ours = legend.moves.iter().map(|&(_, m)| m).collect::<Vec<_>>();
+        let readings = ours
+            .iter()
+            .copied()
+            .permutations(ours.len())
+            .unique()
+            .map(|moves| {
+                let score = rounds
+                    .iter()
+                    .map(|&(opponent, us)| score(&legend, moves[us], opponent))
+                    .sum();
+                let moves = legend
+                    .moves
+                    .iter()
+                    .zip(&moves)
+                    .map(|((symbol, _), &m)| (symbol.clone(), legend.rules.name(m).to_owned()))
+                    .collect();
+                Reading {
+                    moves: SymbolMap(moves),
+                    score,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let best = readings.iter().rev().max_by_key(|r| r.score);
+        let worst = readings.iter().min_by_key(|r| r.score);
+        Ok(Self {
+            best: best.expect("a legend has a move").clone(),
+            worst: worst.expect("a legend has a move").clone(),
+            readings,
+        })
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// The score for throwing `us` against `opponent`.
+fn score(legend: &Legend, us: Move, opponent: Move) -> u32 {
+    let rules = &legend.rules;
+    rules.outcome_score(rules.result(us, opponent)) + rules.score(us)
+}
+
+impl fmt::Display for Readings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let moves = self
+            .readings
+            .iter()
+            .map(|reading| reading.moves.to_string())
+            .collect::<Vec<_>>();
+        let width = moves.iter().map(String::len).chain(["moves".len()]).max();
+        let width = width.unwrap_or(0);
+        writeln!(f, "{:<width$}  score", "moves")?;
+        for (moves, reading) in moves.iter().zip(&self.readings) {
+            writeln!(f, "{moves:<width$}  {:>5}", reading.score)?;
+        }
+        writeln!(f)?;
+        writeln!(f, "best:  {} scores {}", self.best.moves, self.best.score)?;
+        write!(f, "worst: {} scores {}", self.worst.moves, self.worst.score)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params;
+
+    #[test]
+    fn scores_every_reading() {
+        let readings = Readings::score(
+            include_str!("../../input/day02test"),
+            &Day02Params::default(),
+        )
+        .unwrap();
+        let scores = readings
+            .readings
+            .iter()
+            .map(|reading| (reading.moves.to_string(), reading.score))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            scores,
+            [
+                ("X=Rock, Y=Paper, Z=Scissors", 15),
+                ("X=Rock, Y=Scissors, Z=Paper", 6),
+                ("X=Paper, Y=Rock, Z=Scissors", 15),
+                ("X=Paper, Y=Scissors, Z=Rock", 15),
+                ("X=Scissors, Y=Rock, Z=Paper", 15),
+                ("X=Scissors, Y=Paper, Z=Rock", 24),
+            ]
+            .map(|(moves, score)| (moves.to_owned(), score))
+        );
+        assert_eq!(readings.best, readings.readings[5]);
+        assert_eq!(readings.worst, readings.readings[1]);
+        assert!(readings.to_string().ends_with(
+            "best:  X=Scissors, Y=Paper, Z=Rock scores 24\n\
+             worst: X=Rock, Y=Scissors, Z=Paper scores 6"
+        ));
+    }
+
+    #[test]
+    fn rejects_unchecked_parameters() {
+        let params = Day02Params {
+            moves: "X=Rock, Y=Fire, Z=Paper".parse().unwrap(),
+            ..Day02Params::default()
+        };
+        assert_eq!(
+            Readings::score("A X\n", &params),
+            Err(Error::Puzzle(Day02Error::Legend(
+                "`rps` has no move called `Fire`".to_owned()
+            )))
+        );
+    }
+
+    #[test]
+    fn reads_repeated_moves_once() {
+        let overrides = params::overrides(&["moves=X=Rock,Y=Rock,Z=Paper"]);
+        let params = params::apply(&Day02Params::default(), &overrides).unwrap();
+        let readings = Readings::score("A X\nB Z\n", &params).unwrap();
+        assert_eq!(readings.readings.len(), 3);
+        assert_eq!(readings.best.moves.to_string(), "X=Rock, Y=Rock, Z=Paper");
+        let json = serde_json::from_str::<serde_json::Value>(&readings.to_json().unwrap()).unwrap();
+        assert_eq!(json["worst"]["moves"], "X=Rock, Y=Paper, Z=Rock");
+    }
+}
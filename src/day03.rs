@@ -0,0 +1,359 @@
+//! Day 3: Rucksack Reorganization
+
+use std::{fmt, io::BufRead, iter};
+
+use color_eyre::Result;
+use itertools::Itertools;
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    params::NoParams, parsing::ParseError, stream::Input, Error, PuzzleError, Solution,
+    StreamResult,
+};
+
+pub struct Day03;
+
+/// Why a list of rucksacks has no answer.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum Day03Error {
+    #[error("rucksack {rucksack} doesn't have exactly one item in both compartments")]
+    NoSharedItem { rucksack: usize },
+    #[error("the elves in group {group} don't share exactly one badge")]
+    NoBadge { group: usize },
+    #[error("{elves} elves can't be split into groups of three")]
+    UnevenGroups { elves: usize },
+}
+
+impl PuzzleError for Day03Error {}
+
+impl Solution for Day03 {
+    const DAY: u8 = 3;
+
+    type Parsed<'i> = Vec<&'i str>;
+    type Part1 = u32;
+    type Part2 = u32;
+    type Error = Day03Error;
+    type Params = NoParams;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, Error<Day03Error>> {
+        let rucksacks = input.lines().collect_vec();
+        for &rucksack in &rucksacks {
+            check_rucksack(input, rucksack)?;
+        }
+        Ok(rucksacks)
+    }
+
+    fn part1(rucksacks: &Self::Parsed<'_>) -> Result<Self::Part1, Error<Day03Error>> {
+        rucksacks
+            .iter()
+            .enumerate()
+            .map(|(idx, &rucksack)| {
+                rucksack_priority(rucksack).ok_or(Day03Error::NoSharedItem { rucksack: idx + 1 })
+            })
+            .sum::<Result<_, _>>()
+            .map_err(Error::from)
+    }
+
+    fn part2(rucksacks: &Self::Parsed<'_>) -> Result<Self::Part2, Error<Day03Error>> {
+        if rucksacks.len() % 3 != 0 {
+            return Err(Day03Error::UnevenGroups {
+                elves: rucksacks.len(),
+            }
+            .into());
+        }
+        rucksacks
+            .iter()
+            .copied()
+            .tuples()
+            .enumerate()
+            .map(|(idx, (e1, e2, e3))| {
+                intersect_contents([e1, e2, e3].map(contents_set))
+                    .ok_or(Day03Error::NoBadge { group: idx + 1 })
+            })
+            .sum::<Result<_, _>>()
+            .map_err(Error::from)
+    }
+
+    /// Holds on to no more than a group of rucksacks at a time, and reports the first failure of
+    /// each part once the input has been read, in the order [`solve`](crate::solve) would.
+    fn solve_streaming<R: BufRead>(input: &mut Input<R>) -> StreamResult<Self> {
+        let (mut part1, mut part2) = (Ok(0), Ok(0));
+        let mut group = Vec::with_capacity(3);
+        let mut elves = 0;
+        for line in input.lines() {
+            let rucksack = line.text();
+            check_rucksack(rucksack, rucksack).map_err(|e| e.on_line(line.number()))?;
+            elves += 1;
+
+            if let Ok(sum) = &mut part1 {
+                match rucksack_priority(rucksack) {
+                    Some(priority) => *sum += u32::from(priority.0),
+                    None => part1 = Err(Day03Error::NoSharedItem { rucksack: elves }),
+                }
+            }
+
+            group.push(contents_set(rucksack));
+            if group.len() == 3 {
+                let badge = intersect_contents(group.drain(..));
+                if let Ok(sum) = &mut part2 {
+                    match badge {
+                        Some(priority) => *sum += u32::from(priority.0),
+                        None => part2 = Err(Day03Error::NoBadge { group: elves / 3 }),
+                    }
+                }
+            }
+        }
+
+        let part1 = part1?;
+        if elves % 3 != 0 {
+            return Err(Day03Error::UnevenGroups { elves }.into());
+        }
+        Ok(Some((part1, part2?)))
+    }
+
+    /// Generates `size` rucksacks, rounded up to a whole number of groups of three elves.
+    fn generate<R: Rng>(rng: &mut R, size: usize) -> String {
+        let mut out = String::new();
+        for _ in 0..size.div_ceil(3) {
+            let mut items = (b'a'..=b'z').chain(b'A'..=b'Z').collect_vec();
+            items.shuffle(rng);
+            let (badge, rest) = items.split_first().unwrap();
+            // Each elf draws from its own pool of items, so the badge is the only item they share.
+            for pool in rest.chunks(17) {
+                out.push_str(&generate_rucksack(rng, *badge, pool));
+                out.push('\n');
+            }
+        }
+        out
+    }
+}
+
+/// A rucksack holding the badge and items from the pool, where `pool[0]` is the item which is in
+/// both compartments.
+fn generate_rucksack<R: Rng>(rng: &mut R, badge: u8, pool: &[u8]) -> String {
+    let (&shared, rest) = pool.split_first().unwrap();
+    let (left_only, right_only) = rest.split_at(rest.len() / 2);
+    let half = rng.gen_range(4..=16);
+    let badge_left = rng.gen_bool(0.5);
+    let mut compartment = |only: &[u8], has_badge: bool| {
+        let mut items = vec![shared];
+        if has_badge {
+            items.push(badge);
+        }
+        items.extend((items.len()..half).map(|_| *only.choose(rng).unwrap()));
+        items.shuffle(rng);
+        items
+    };
+    let mut rucksack = compartment(left_only, badge_left);
+    rucksack.extend(compartment(right_only, !badge_left));
+    String::from_utf8(rucksack).unwrap()
+}
+
+/// Checks that a rucksack, which must be a slice of `input`, holds only item letters, split evenly
+/// between its compartments.
+fn check_rucksack(input: &str, rucksack: &str) -> Result<(), ParseError> {
+    if let Some(idx) = rucksack.find(|c: char| !c.is_ascii_alphabetic()) {
+        return Err(ParseError::expected(
+            input,
+            &rucksack[idx..],
+            "an item letter",
+        ));
+    }
+    if !rucksack.len().is_multiple_of(2) {
+        return Err(ParseError::new(
+            input,
+            &rucksack[rucksack.len()..],
+            "a rucksack must hold an even number of items, to split between its compartments",
+        ));
+    }
+    Ok(())
+}
+
+/// The priority of the item found in both compartments of a rucksack, unless there isn't exactly
+/// one.
+pub fn rucksack_priority(elf: &str) -> Option<RucksackPriority> {
+    let mid = elf.len() / 2;
+    let (first, last) = elf.split_at(mid);
+    let first_set = contents_set(first);
+    let last_set = contents_set(last);
+
+    intersect_contents([first_set, last_set])
+}
+
+/// The priority of the single item common to every rucksack, unless there isn't exactly one.
+pub fn intersect_contents<I: IntoIterator<Item = ContentsSet>>(
+    sacks: I,
+) -> Option<RucksackPriority> {
+    let intersection = sacks
+        .into_iter()
+        .fold(ContentsSet::FULL, ContentsSet::intersect);
+
+    intersection.only()
+}
+
+/// The set of item types in a rucksack (or compartment), ignoring anything which isn't an item.
+pub fn contents_set(s: &str) -> ContentsSet {
+    let mut set = ContentsSet::EMPTY;
+    for priority in s.bytes().filter_map(calc_priority) {
+        set.insert(priority);
+    }
+    set
+}
+
+/// The priority of an item letter, or `None` if `c` isn't one.
+pub fn calc_priority(c: u8) -> Option<RucksackPriority> {
+    match c {
+        b'a'..=b'z' => Some(RucksackPriority(c - b'a' + 1)),
+        b'A'..=b'Z' => Some(RucksackPriority(c - b'A' + 27)),
+        _ => None,
+    }
+}
+
+/// A set of item types, stored as a bit per priority.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct ContentsSet(u64);
+
+impl ContentsSet {
+    pub const EMPTY: Self = Self(0);
+    pub const FULL: Self = Self(u64::MAX);
+
+    pub fn insert(&mut self, priority: RucksackPriority) {
+        self.0 |= 1 << (63 - priority.0);
+    }
+
+    pub fn intersect(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    /// The priority in the set, if it is a singleton.
+    pub fn only(self) -> Option<RucksackPriority> {
+        (self.0.count_ones() == 1).then(|| RucksackPriority(self.0.leading_zeros() as u8))
+    }
+}
+
+impl fmt::Debug for ContentsSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("ContentsSet")
+            .field(&format_args!("{:064b}", self.0))
+            .finish()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RucksackPriority(pub u8);
+
+impl iter::Sum<RucksackPriority> for u32 {
+    fn sum<I: Iterator<Item = RucksackPriority>>(iter: I) -> Self {
+        iter.fold(0, |acc, p| acc + p.0 as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::proptest;
+    use test_case::test_case;
+
+    use super::*;
+    use crate::differential;
+
+    #[test_case("ab\n" => Err(Error::Puzzle(Day03Error::NoSharedItem { rucksack: 1 })) ; "nothing shared")]
+    #[test_case("aa\nabab\n" => Err(Error::Puzzle(Day03Error::NoSharedItem { rucksack: 2 })) ; "two shared")]
+    #[test_case("aa\nbb\n" => Err(Error::Puzzle(Day03Error::UnevenGroups { elves: 2 })) ; "uneven groups")]
+    #[test_case("aa\nbb\ncc\n" => Err(Error::Puzzle(Day03Error::NoBadge { group: 1 })) ; "no badge")]
+    #[test_case("aa\nbb\ncc\nab\n" => Err(Error::Puzzle(Day03Error::NoSharedItem { rucksack: 4 })) ; "no shared item before no badge")]
+    #[test_case("aa\nbb\ncc\ndd\n" => Err(Error::Puzzle(Day03Error::UnevenGroups { elves: 4 })) ; "uneven groups before no badge")]
+    #[test_case("a1\n" => matches Err(Error::Parse(_)) ; "not a letter")]
+    #[test_case("ab\nabc\n" => matches Err(Error::Parse(_)) ; "odd length")]
+    fn malformed_input(input: &str) -> Result<(u32, u32), Error<Day03Error>> {
+        differential::solve::<Day03>(input)
+    }
+
+    #[test]
+    fn test_calc_priority() {
+        assert_eq!(calc_priority(b'a'), Some(RucksackPriority(1)));
+        assert_eq!(calc_priority(b'p'), Some(RucksackPriority(16)));
+        assert_eq!(calc_priority(b'z'), Some(RucksackPriority(26)));
+        assert_eq!(calc_priority(b'A'), Some(RucksackPriority(27)));
+        assert_eq!(calc_priority(b'Z'), Some(RucksackPriority(52)));
+        assert_eq!(calc_priority(b'!'), None);
+    }
+
+    #[test]
+    fn test_find_rucksack_priority() {
+        assert_eq!(
+            rucksack_priority("vJrwpWtwJgWrhcsFMMfFFhFp"),
+            Some(RucksackPriority(16))
+        );
+        assert_eq!(
+            rucksack_priority("jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL"),
+            Some(RucksackPriority(38))
+        );
+        assert_eq!(
+            rucksack_priority("PmmdzqPrVvPwwTWBwg"),
+            Some(RucksackPriority(42))
+        );
+        assert_eq!(
+            rucksack_priority("wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn"),
+            Some(RucksackPriority(22))
+        );
+        assert_eq!(
+            rucksack_priority("ttgJtRGJQctTZtZT"),
+            Some(RucksackPriority(20))
+        );
+        assert_eq!(
+            rucksack_priority("CrZsJsPPZsGzwwsLwLmpwMDw"),
+            Some(RucksackPriority(19))
+        );
+    }
+
+    #[test]
+    fn test_contents_set() {
+        assert_eq!(
+            contents_set("a"),
+            ContentsSet(0b0100000000000000000000000000000000000000000000000000000000000000)
+        );
+        assert_eq!(
+            contents_set("aa"),
+            ContentsSet(0b0100000000000000000000000000000000000000000000000000000000000000)
+        );
+        assert_eq!(
+            contents_set("aaZ"),
+            ContentsSet(0b0100000000000000000000000000000000000000000000000000100000000000)
+        );
+    }
+
+    /// Searches each rucksack for its shared item character by character.
+    fn reference(input: &str) -> (u32, u32) {
+        let priority = |c: char| match c {
+            'a'..='z' => c as u32 - 'a' as u32 + 1,
+            _ => c as u32 - 'A' as u32 + 27,
+        };
+        let rucksacks = input.lines().collect::<Vec<_>>();
+        let part1 = rucksacks
+            .iter()
+            .map(|rucksack| {
+                let (left, right) = rucksack.split_at(rucksack.len() / 2);
+                priority(left.chars().find(|&c| right.contains(c)).unwrap())
+            })
+            .sum();
+        let part2 = rucksacks
+            .chunks(3)
+            .map(|group| {
+                let mut items = group[0].chars();
+                priority(
+                    items
+                        .find(|&c| group[1].contains(c) && group[2].contains(c))
+                        .unwrap(),
+                )
+            })
+            .sum();
+        (part1, part2)
+    }
+
+    proptest! {
+        #[test]
+        fn agrees_with_reference(input in differential::inputs(30)) {
+            differential::check::<Day03>(input, reference)?;
+        }
+    }
+}
@@ -0,0 +1,187 @@
+//! Day 4: Camp Cleanup
+
+use std::{convert::Infallible, io::BufRead, ops::RangeInclusive, str};
+
+use color_eyre::Result;
+use nom::{
+    combinator::{map, map_res},
+    error::context,
+    sequence::separated_pair,
+};
+use rand::Rng;
+
+use crate::{
+    interval,
+    params::NoParams,
+    parsing::{self, lines, number, tag, IResult, ParseError},
+    stream::Input,
+    Error, Solution, StreamResult,
+};
+
+pub struct Day04;
+
+impl Solution for Day04 {
+    const DAY: u8 = 4;
+
+    type Parsed<'i> = Vec<ElfPair>;
+    type Part1 = usize;
+    type Part2 = usize;
+    type Error = Infallible;
+    type Params = NoParams;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, Error<Infallible>> {
+        Ok(parsing::parse_all(input, lines(elf_pair))?)
+    }
+
+    fn part1(pairs: &Self::Parsed<'_>) -> Result<Self::Part1, Error<Infallible>> {
+        Ok(pairs.iter().filter(|p| p.is_one_subset_of_other()).count())
+    }
+
+    fn part2(pairs: &Self::Parsed<'_>) -> Result<Self::Part2, Error<Infallible>> {
+        Ok(pairs.iter().filter(|p| p.overlaps()).count())
+    }
+
+    fn solve_streaming<R: BufRead>(input: &mut Input<R>) -> StreamResult<Self> {
+        let (mut contained, mut overlapping) = (0, 0);
+        for line in input.lines() {
+            let pair = line.parse(elf_pair)?;
+            contained += usize::from(pair.is_one_subset_of_other());
+            overlapping += usize::from(pair.overlaps());
+        }
+        Ok(Some((contained, overlapping)))
+    }
+
+    /// Generates `size` pairs of elves.
+    fn generate<R: Rng>(rng: &mut R, size: usize) -> String {
+        let mut range = || {
+            let (a, b) = (rng.gen_range(1..=99), rng.gen_range(1..=99));
+            format!("{}-{}", a.min(b), a.max(b))
+        };
+        (0..size)
+            .map(|_| format!("{},{}\n", range(), range()))
+            .collect()
+    }
+}
+
+/// The sections assigned to each elf in a pair.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ElfPair(pub RangeInclusive<u8>, pub RangeInclusive<u8>);
+
+impl ElfPair {
+    /// Whether the elves share any sections.
+    pub fn overlaps(&self) -> bool {
+        interval::overlap(&self.0, &self.1)
+    }
+
+    /// Whether one elf's sections are entirely contained within the other's.
+    pub fn is_one_subset_of_other(&self) -> bool {
+        is_superset(&self.0, &self.1) || is_superset(&self.1, &self.0)
+    }
+}
+
+pub fn is_superset(r1: &RangeInclusive<u8>, r2: &RangeInclusive<u8>) -> bool {
+    interval::covers(r1, r2)
+}
+
+impl str::FromStr for ElfPair {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parsing::parse_all(s, elf_pair)
+    }
+}
+
+fn elf_pair(s: &str) -> IResult<'_, ElfPair> {
+    context(
+        "elf pair",
+        map(separated_pair(range, tag(","), range), |(e1, e2)| {
+            ElfPair(e1, e2)
+        }),
+    )(s)
+}
+
+fn range(s: &str) -> IResult<'_, RangeInclusive<u8>> {
+    context(
+        "section range",
+        map_res(separated_pair(number, tag("-"), number), |(start, end)| {
+            if start <= end {
+                Ok(start..=end)
+            } else {
+                Err(format!("section {start} comes after section {end}"))
+            }
+        }),
+    )(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::proptest;
+
+    use super::*;
+    use crate::differential;
+
+    #[test]
+    fn test_elf_pair() {
+        assert_eq!(elf_pair("2-4,6-8"), Ok(("", ElfPair(2..=4, 6..=8))));
+    }
+
+    #[test]
+    fn rejects_reversed_ranges() {
+        let Err(Error::Parse(error)) = differential::solve::<Day04>("2-4,6-8\n1-9,5-3\n") else {
+            panic!("expected the reversed range to be rejected");
+        };
+        assert_eq!((error.line(), error.column()), (2, 5));
+        assert!(
+            error.message().ends_with("section 5 comes after section 3"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn test_contains() {
+        assert!(is_superset(&(1..=5), &(2..=4)));
+        assert!(is_superset(&(1..=5), &(1..=4)));
+        assert!(is_superset(&(1..=5), &(2..=5)));
+        assert!(is_superset(&(1..=5), &(1..=5)));
+        assert!(!is_superset(&(1..=5), &(0..=5)));
+        assert!(!is_superset(&(1..=5), &(1..=6)));
+    }
+
+    #[test]
+    fn test_is_one_superset_of_other() {
+        assert!(ElfPair(1..=5, 2..=4).is_one_subset_of_other());
+        assert!(ElfPair(1..=5, 1..=4).is_one_subset_of_other());
+        assert!(ElfPair(1..=5, 2..=5).is_one_subset_of_other());
+        assert!(ElfPair(1..=5, 1..=5).is_one_subset_of_other());
+        assert!(ElfPair(1..=5, 0..=5).is_one_subset_of_other());
+        assert!(ElfPair(1..=5, 1..=6).is_one_subset_of_other());
+        assert!(!ElfPair(2..=4, 1..=3).is_one_subset_of_other());
+    }
+
+    /// Compares the ends of each pair of ranges directly.
+    fn reference(input: &str) -> (usize, usize) {
+        let pairs = input
+            .lines()
+            .map(|line| {
+                let ends = line
+                    .split(['-', ','])
+                    .map(|n| n.parse::<u8>().unwrap())
+                    .collect::<Vec<_>>();
+                (ends[0], ends[1], ends[2], ends[3])
+            })
+            .collect::<Vec<_>>();
+        let contained = pairs
+            .iter()
+            .filter(|(a, b, c, d)| (a <= c && d <= b) || (c <= a && b <= d))
+            .count();
+        let overlapping = pairs.iter().filter(|(a, b, c, d)| a <= d && c <= b).count();
+        (contained, overlapping)
+    }
+
+    proptest! {
+        #[test]
+        fn agrees_with_reference(input in differential::inputs(30)) {
+            differential::check::<Day04>(input, reference)?;
+        }
+    }
+}
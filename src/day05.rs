@@ -0,0 +1,414 @@
+//! Day 5: Supply Stacks
+
+use std::{io::BufRead, iter};
+
+use color_eyre::Result;
+use nom::{
+    branch::alt,
+    character::complete::{digit1, line_ending},
+    combinator::{map, map_res, value},
+    error::context,
+    multi::separated_list1,
+    sequence::{delimited, pair, preceded, separated_pair, terminated, tuple},
+};
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    params::NoParams,
+    parsing::{self, lines, number, tag, IResult},
+    stream::Input,
+    Error, PuzzleError, Solution, StreamResult,
+};
+
+pub struct Day05;
+
+/// Why a rearrangement procedure can't be carried out.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum Day05Error {
+    #[error("there is no stack {stack}, as the ship has {stacks} stacks")]
+    NoSuchStack { stack: usize, stacks: usize },
+    #[error("can't move {} crates from stack {}, which holds {held}", .mv.count, .mv.source)]
+    NotEnoughCrates { mv: Move, held: usize },
+    #[error("stack {stack} is empty once the crates are moved, so has no top crate")]
+    EmptyStack { stack: usize },
+}
+
+impl PuzzleError for Day05Error {}
+
+impl Solution for Day05 {
+    const DAY: u8 = 5;
+
+    type Parsed<'i> = (Ship, Vec<Move>);
+    type Part1 = String;
+    type Part2 = String;
+    type Error = Day05Error;
+    type Params = NoParams;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, Error<Day05Error>> {
+        Ok(parsing::parse_all(input, pair(ship, lines(crate_move)))?)
+    }
+
+    fn part1((ship, moves): &Self::Parsed<'_>) -> Result<Self::Part1, Error<Day05Error>> {
+        let mut ship = ship.clone();
+        for &mv in moves {
+            ship.move_crate(mv)?;
+        }
+        Ok(ship.stack_tops()?)
+    }
+
+    fn part2((ship, moves): &Self::Parsed<'_>) -> Result<Self::Part2, Error<Day05Error>> {
+        let mut ship = ship.clone();
+        for &mv in moves {
+            ship.move_several_crates(mv)?;
+        }
+        Ok(ship.stack_tops()?)
+    }
+
+    /// Reads the drawing of the ship, then carries out each move on both cranes' ships as it is
+    /// read.
+    fn solve_streaming<R: BufRead>(input: &mut Input<R>) -> StreamResult<Self> {
+        let mut lines = input.lines();
+        let mut drawing = String::new();
+        for line in lines.by_ref() {
+            drawing.push_str(line.text());
+            drawing.push('\n');
+            if line.text().is_empty() {
+                break;
+            }
+        }
+        let ship = parsing::parse_all(&drawing, ship)?;
+
+        // Each crane stops at the first move it can't make.
+        let (mut one_at_a_time, mut all_at_once) = (Ok(ship.clone()), Ok(ship));
+        for line in lines {
+            let mv = line.parse(crate_move)?;
+            if let Ok(ship) = &mut one_at_a_time {
+                if let Err(e) = ship.move_crate(mv) {
+                    one_at_a_time = Err(e);
+                }
+            }
+            if let Ok(ship) = &mut all_at_once {
+                if let Err(e) = ship.move_several_crates(mv) {
+                    all_at_once = Err(e);
+                }
+            }
+        }
+
+        let part1 = one_at_a_time?.stack_tops()?;
+        let part2 = all_at_once?.stack_tops()?;
+        Ok(Some((part1, part2)))
+    }
+
+    /// Generates nine stacks of crates and `size` moves between them.
+    fn generate<R: Rng>(rng: &mut R, size: usize) -> String {
+        const STACKS: usize = 9;
+        let stacks = (0..STACKS)
+            .map(|_| {
+                (0..rng.gen_range(2..=8))
+                    .map(|_| rng.gen_range(b'A'..=b'Z') as char)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let mut out = String::new();
+        let height = stacks.iter().map(Vec::len).max().unwrap();
+        for level in (0..height).rev() {
+            let row = stacks
+                .iter()
+                .map(|stack| match stack.get(level) {
+                    Some(crt) => format!("[{crt}]"),
+                    None => "   ".to_owned(),
+                })
+                .collect::<Vec<_>>();
+            out.push_str(&row.join(" "));
+            out.push('\n');
+        }
+        let labels = (1..=STACKS)
+            .map(|label| format!(" {label} "))
+            .collect::<Vec<_>>();
+        out.push_str(&labels.join(" "));
+        out.push_str("\n\n");
+
+        // Every stack keeps at least one crate, so each has a top once the moves are done.
+        let mut heights = stacks.iter().map(Vec::len).collect::<Vec<_>>();
+        for _ in 0..size {
+            let sources = (0..STACKS)
+                .filter(|&idx| heights[idx] >= 2)
+                .collect::<Vec<_>>();
+            let source = *sources.choose(rng).unwrap();
+            let destination = (source + rng.gen_range(1..STACKS)) % STACKS;
+            let count = rng.gen_range(1..heights[source]);
+            heights[source] -= count;
+            heights[destination] += count;
+            out.push_str(&format!(
+                "move {count} from {} to {}\n",
+                source + 1,
+                destination + 1
+            ));
+        }
+        out
+    }
+}
+
+/// A crate, labelled with a single letter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SupplyCrate(pub char);
+
+impl iter::Sum<SupplyCrate> for String {
+    fn sum<I: Iterator<Item = SupplyCrate>>(iter: I) -> Self {
+        let mut s = String::with_capacity(iter.size_hint().0);
+        for crt in iter {
+            s.push(crt.0);
+        }
+        s
+    }
+}
+
+/// A stack of crates, from bottom to top.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Stack(Vec<SupplyCrate>);
+
+impl Stack {
+    pub fn push(&mut self, crt: SupplyCrate) {
+        self.0.push(crt);
+    }
+
+    pub fn pop(&mut self) -> Option<SupplyCrate> {
+        self.0.pop()
+    }
+
+    pub fn peek(&self) -> Option<SupplyCrate> {
+        self.0.last().copied()
+    }
+
+    /// The crates in the stack, from bottom to top.
+    pub fn crates(&self) -> &[SupplyCrate] {
+        &self.0
+    }
+}
+
+/// The stacks of crates on the ship, in label order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ship(Vec<Stack>);
+
+impl Ship {
+    pub fn stacks(&self) -> &[Stack] {
+        &self.0
+    }
+
+    fn stack(&mut self, idx: usize) -> &mut Stack {
+        &mut self.0[idx]
+    }
+
+    /// Checks that both stacks exist, and that the source holds enough crates.
+    fn check(&self, mv: Move) -> Result<(), Day05Error> {
+        let stacks = self.0.len();
+        for stack in [mv.source, mv.destination] {
+            if !(1..=stacks).contains(&stack) {
+                return Err(Day05Error::NoSuchStack { stack, stacks });
+            }
+        }
+        let held = self.0[mv.source - 1].0.len();
+        if held < mv.count {
+            return Err(Day05Error::NotEnoughCrates { mv, held });
+        }
+        Ok(())
+    }
+
+    /// Moves crates one at a time, as the CrateMover 9000 does.
+    pub fn move_crate(&mut self, mv: Move) -> Result<(), Day05Error> {
+        self.check(mv)?;
+        for _ in 0..mv.count {
+            if let Some(crt) = self.stack(mv.source - 1).pop() {
+                self.stack(mv.destination - 1).push(crt);
+            }
+        }
+        Ok(())
+    }
+
+    /// Moves crates all at once, as the CrateMover 9001 does.
+    pub fn move_several_crates(&mut self, mv: Move) -> Result<(), Day05Error> {
+        self.check(mv)?;
+        if mv.source == mv.destination {
+            return Ok(());
+        }
+
+        let first = mv.source.min(mv.destination) - 1;
+        let second = mv.source.max(mv.destination) - first - 1;
+        let (_, left) = self.0.split_at_mut(first);
+        let (left, right) = left.split_at_mut(second);
+
+        let (origin, dest) = if mv.source < mv.destination {
+            (&mut left[0].0, &mut right[0].0)
+        } else {
+            (&mut right[0].0, &mut left[0].0)
+        };
+
+        dest.extend(origin.drain((origin.len() - mv.count)..));
+        Ok(())
+    }
+
+    /// The labels of the crates at the top of each stack.
+    pub fn stack_tops(&self) -> Result<String, Day05Error> {
+        self.0
+            .iter()
+            .enumerate()
+            .map(|(idx, stack)| {
+                stack
+                    .peek()
+                    .ok_or(Day05Error::EmptyStack { stack: idx + 1 })
+            })
+            .sum()
+    }
+}
+
+fn supply_crate(s: &str) -> IResult<'_, SupplyCrate> {
+    map(
+        delimited(tag("["), nom::character::complete::anychar, tag("]")),
+        SupplyCrate,
+    )(s)
+}
+
+fn maybe_crate(s: &str) -> IResult<'_, Option<SupplyCrate>> {
+    alt((map(supply_crate, Some), value(None, tag("   "))))(s)
+}
+
+fn crate_row(s: &str) -> IResult<'_, Vec<Option<SupplyCrate>>> {
+    context("crate row", separated_list1(tag(" "), maybe_crate))(s)
+}
+
+fn labels_row(s: &str) -> IResult<'_, usize> {
+    context(
+        "stack labels",
+        map(
+            delimited(tag(" "), separated_list1(tag("   "), digit1), tag(" ")),
+            |l| l.len(),
+        ),
+    )(s)
+}
+
+fn ship(s: &str) -> IResult<'_, Ship> {
+    map_res(
+        terminated(
+            separated_pair(
+                separated_list1(line_ending, crate_row),
+                line_ending,
+                labels_row,
+            ),
+            pair(line_ending, line_ending),
+        ),
+        |(rows, stacks)| {
+            let mut ship = Ship(vec![Stack::default(); stacks]);
+            for row in rows.into_iter().rev() {
+                if row.len() > stacks {
+                    return Err(format!(
+                        "a row of {} crates is wider than the {stacks} stacks",
+                        row.len()
+                    ));
+                }
+                for (idx, crt) in row.into_iter().enumerate() {
+                    if let Some(crt) = crt {
+                        ship.stack(idx).push(crt);
+                    }
+                }
+            }
+            Ok(ship)
+        },
+    )(s)
+}
+
+/// A step of the rearrangement procedure. Stacks are numbered from 1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Move {
+    pub count: usize,
+    pub source: usize,
+    pub destination: usize,
+}
+
+fn crate_move(s: &str) -> IResult<'_, Move> {
+    context(
+        "crate move",
+        map(
+            tuple((
+                preceded(tag("move "), number),
+                preceded(tag(" from "), number),
+                preceded(tag(" to "), number),
+            )),
+            |(count, source, destination)| Move {
+                count,
+                source,
+                destination,
+            },
+        ),
+    )(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::proptest;
+    use test_case::test_case;
+
+    use super::*;
+    use crate::differential;
+
+    const SHIP: &str = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n\n";
+
+    #[test_case("move 1 from 4 to 1" => Err(Error::Puzzle(Day05Error::NoSuchStack { stack: 4, stacks: 3 })) ; "no such stack")]
+    #[test_case("move 1 from 1 to 0" => Err(Error::Puzzle(Day05Error::NoSuchStack { stack: 0, stacks: 3 })) ; "stack zero")]
+    #[test_case("move 4 from 1 to 2" => Err(Error::Puzzle(Day05Error::NotEnoughCrates { mv: Move { count: 4, source: 1, destination: 2 }, held: 2 })) ; "not enough crates")]
+    #[test_case("move 1 from 3 to 1" => Err(Error::Puzzle(Day05Error::EmptyStack { stack: 3 })) ; "empty stack")]
+    fn impossible_moves(moves: &str) -> Result<(String, String), Error<Day05Error>> {
+        differential::solve::<Day05>(&format!("{SHIP}{moves}\n"))
+    }
+
+    #[test_case("[A] [B] [C]\n 1   2 \n\nmove 1 from 1 to 2\n" ; "row wider than the labels")]
+    #[test_case("[A] [B]\n 1   2 \nmove 1 from 1 to 2\n" ; "no blank line")]
+    #[test_case("[A] [B]\n 1   2 \n\nmove 1 from 1 to 2\nmove 1\n" ; "bad move")]
+    fn malformed_input(input: &str) {
+        assert!(matches!(
+            differential::solve::<Day05>(input),
+            Err(Error::Parse(_))
+        ));
+    }
+
+    /// Reads the crates by column from the drawing and moves them between vectors.
+    fn reference(input: &str) -> (String, String) {
+        let (drawing, moves) = input.split_once("\n\n").unwrap();
+        let mut rows = drawing.lines().rev();
+        let count = rows.next().unwrap().split_whitespace().count();
+        let mut stacks = vec![Vec::new(); count];
+        for row in rows {
+            for (idx, stack) in stacks.iter_mut().enumerate() {
+                if let Some(&crt @ b'A'..=b'Z') = row.as_bytes().get(idx * 4 + 1) {
+                    stack.push(char::from(crt));
+                }
+            }
+        }
+
+        let (mut one_at_a_time, mut all_at_once) = (stacks.clone(), stacks);
+        for line in moves.lines() {
+            let numbers = line
+                .split(' ')
+                .filter_map(|word| word.parse::<usize>().ok())
+                .collect::<Vec<_>>();
+            let (count, from, to) = (numbers[0], numbers[1] - 1, numbers[2] - 1);
+            for _ in 0..count {
+                let crt = one_at_a_time[from].pop().unwrap();
+                one_at_a_time[to].push(crt);
+            }
+            let remaining = all_at_once[from].len() - count;
+            let moved = all_at_once[from].split_off(remaining);
+            all_at_once[to].extend(moved);
+        }
+
+        let tops = |stacks: &[Vec<char>]| stacks.iter().map(|s| s.last().unwrap()).collect();
+        (tops(&one_at_a_time), tops(&all_at_once))
+    }
+
+    proptest! {
+        #[test]
+        fn agrees_with_reference(input in differential::inputs(30)) {
+            differential::check::<Day05>(input, reference)?;
+        }
+    }
+}
@@ -0,0 +1,251 @@
+//! Day 6: Tuning Trouble
+
+use std::io::BufRead;
+
+use color_eyre::Result;
+use rand::{seq::SliceRandom, Rng};
+use tracing::{debug, debug_span, trace};
+
+use crate::{
+    params::NoParams, parsing::ParseError, stream::Input, Error, PuzzleError, Solution,
+    StreamResult,
+};
+
+const USE_BIT_SET: bool = true;
+const FORCE_SLOW_MODE: bool = false;
+
+pub struct Day06;
+
+/// Why a datastream has no answer.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum Day06Error {
+    #[error("the datastream has no run of {len} different characters")]
+    NoMarker { len: usize },
+}
+
+impl PuzzleError for Day06Error {}
+
+impl Solution for Day06 {
+    const DAY: u8 = 6;
+
+    type Parsed<'i> = &'i str;
+    type Part1 = usize;
+    type Part2 = usize;
+    type Error = Day06Error;
+    type Params = NoParams;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, Error<Day06Error>> {
+        let datastream = input.trim_end_matches(['\n', '\r']);
+        if let Some(idx) = datastream.find(|c: char| !c.is_ascii_lowercase()) {
+            return Err(
+                ParseError::expected(input, &datastream[idx..], "a lowercase letter").into(),
+            );
+        }
+        Ok(datastream)
+    }
+
+    fn part1(input: &Self::Parsed<'_>) -> Result<Self::Part1, Error<Day06Error>> {
+        let (_marker, idx) = find_marker::<4>(input).ok_or(Day06Error::NoMarker { len: 4 })?;
+        Ok(idx)
+    }
+
+    fn part2(input: &Self::Parsed<'_>) -> Result<Self::Part2, Error<Day06Error>> {
+        let (_marker, idx) = find_marker::<14>(input).ok_or(Day06Error::NoMarker { len: 14 })?;
+        Ok(idx)
+    }
+
+    fn solve_streaming<R: BufRead>(input: &mut Input<R>) -> StreamResult<Self> {
+        let mut packet = MarkerSearch::<4>::default();
+        let mut message = MarkerSearch::<14>::default();
+        // The datastream ends at the first line ending, after which only line endings may follow.
+        let mut ended = None;
+        let mut bytes = input.bytes();
+        while let Some(byte) = bytes.next() {
+            if matches!(byte, b'\n' | b'\r') {
+                ended.get_or_insert_with(|| bytes.expected("a lowercase letter"));
+            } else if let Some(e) = ended {
+                return Err(e.into());
+            } else if byte.is_ascii_lowercase() {
+                packet.push(byte);
+                message.push(byte);
+            } else {
+                return Err(bytes.expected("a lowercase letter").into());
+            }
+        }
+
+        let part1 = packet.found().ok_or(Day06Error::NoMarker { len: 4 })?;
+        let part2 = message.found().ok_or(Day06Error::NoMarker { len: 14 })?;
+        Ok(Some((part1, part2)))
+    }
+
+    /// Generates a datastream of `size` characters (at least 20), with both markers some way into
+    /// it.
+    fn generate<R: Rng>(rng: &mut R, size: usize) -> String {
+        let mut letters = (b'a'..=b'z').collect::<Vec<_>>();
+        letters.shuffle(rng);
+        let (distinct, few) = (&letters[..14], &letters[..3]);
+
+        let len = size.max(20);
+        let start = rng.gen_range(0..=len - 14);
+        let mut stream = (0..start)
+            .map(|_| *few.choose(rng).unwrap())
+            .collect::<Vec<_>>();
+        stream.extend(distinct);
+        stream.extend((stream.len()..len).map(|_| *letters.choose(rng).unwrap()));
+        stream.push(b'\n');
+        String::from_utf8(stream).unwrap()
+    }
+}
+
+/// Finds the first run of `N` distinct characters, returning the run and the number of characters
+/// processed up to the end of it.
+///
+/// The input must be lowercase letters. Returns `None` if there is no such run.
+pub fn find_marker<const N: usize>(input: &str) -> Option<(&str, usize)> {
+    let _span = debug_span!("find_marker", len = N).entered();
+    let bytes = input.as_bytes();
+    if bytes.len() < N {
+        return None;
+    }
+    let mut window: [u8; N] = std::array::from_fn(|i| bytes[i]);
+    let mut idx = N;
+    let mut cmps = 0;
+
+    loop {
+        let (fill, cmp) = calculate_fill(&window);
+        cmps += cmp;
+        if fill == 0 {
+            break;
+        }
+        assert!(fill < N);
+        if bytes.len() < idx + fill {
+            return None;
+        }
+        window.copy_within(fill.., 0);
+        window[N - fill..].copy_from_slice(&bytes[idx..(idx + fill)]);
+        idx += fill;
+    }
+
+    debug!(comparisons = cmps, "found the marker");
+
+    Some((&input[idx - N..idx], idx))
+}
+
+/// Finds the first run of `N` distinct letters in a datastream read a letter at a time.
+#[derive(Clone, Debug, Default)]
+pub struct MarkerSearch<const N: usize> {
+    /// How many letters have been read.
+    read: usize,
+    /// How many letters had been read when each letter was last read, or 0 if it hasn't been.
+    last_seen: [usize; 26],
+    /// How many letters had been read before the current run of distinct letters.
+    run_start: usize,
+    found: Option<usize>,
+}
+
+impl<const N: usize> MarkerSearch<N> {
+    /// Reads the next letter, which must be lowercase.
+    pub fn push(&mut self, letter: u8) {
+        if self.found.is_some() {
+            return;
+        }
+        self.read += 1;
+        let seen = &mut self.last_seen[usize::from(letter - b'a')];
+        self.run_start = self.run_start.max(*seen);
+        *seen = self.read;
+        if self.read - self.run_start == N {
+            self.found = Some(self.read);
+        }
+    }
+
+    /// The number of letters read up to the end of the marker, once it has been read.
+    pub fn found(&self) -> Option<usize> {
+        self.found
+    }
+}
+
+fn calculate_fill(window: &[u8]) -> (usize, usize) {
+    let mut set = 1u32 << (window.last().copied().unwrap_or_default() - b'a');
+    let mut fill = 0;
+    let mut cmp = 0;
+    'outer: for (idx, val) in window[..window.len() - 1].iter().copied().enumerate().rev() {
+        if USE_BIT_SET {
+            trace!(
+                letter = %char::from(val),
+                idx,
+                set = format_args!("{set:026b}"),
+                "checking letter against the letters after it"
+            );
+            let val = 1 << (val - b'a');
+            cmp += 1;
+            if val & set != 0 {
+                fill = if FORCE_SLOW_MODE { 1 } else { idx + 1 };
+                break 'outer;
+            }
+            set |= val;
+        } else {
+            for &test in &window[idx + 1..] {
+                cmp += 1;
+                if val == test {
+                    fill = if FORCE_SLOW_MODE { 1 } else { idx + 1 };
+                    break 'outer;
+                }
+            }
+        }
+    }
+
+    (fill, cmp)
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::proptest;
+    use test_case::test_case;
+
+    use super::*;
+    use crate::differential;
+
+    #[test_case("abc\n" => Err(Error::Puzzle(Day06Error::NoMarker { len: 4 })) ; "too short")]
+    #[test_case("abcabcabcabc\n" => Err(Error::Puzzle(Day06Error::NoMarker { len: 4 })) ; "no marker")]
+    #[test_case("abcdabcdabcd\n" => Err(Error::Puzzle(Day06Error::NoMarker { len: 14 })) ; "no message marker")]
+    #[test_case("abcdefghijklmnopqrstuvwxyz\r\n" => Ok((4, 14)) ; "crlf")]
+    #[test_case("abcD\n" => matches Err(Error::Parse(_)) ; "not lowercase")]
+    #[test_case("abcd\nefgh\n" => matches Err(Error::Parse(_)) ; "second line")]
+    #[test_case("abcdefghijklmnopqrstuvwxyz\n\r\n\n" => Ok((4, 14)) ; "blank lines")]
+    fn malformed_input(input: &str) -> Result<(usize, usize), Error<Day06Error>> {
+        differential::solve::<Day06>(input)
+    }
+
+    #[test]
+    fn reports_a_window_of_long_lines_both_ways() {
+        let input = format!("{}X{}\n", "ab".repeat(3000), "cd".repeat(3000));
+        let Err(Error::Parse(error)) = differential::solve::<Day06>(&input) else {
+            panic!("expected the capital letter to be rejected");
+        };
+        let report = error.to_string();
+        let window = format!("{}X{}c", "ab".repeat(16), "cd".repeat(15));
+        assert!(report.contains(&format!("1 | …{window}…\n")), "{report}");
+        assert!(report.len() < 250, "{report}");
+    }
+
+    /// Checks every window in turn, comparing each pair of characters.
+    fn reference(input: &str) -> (usize, usize) {
+        let bytes = input.as_bytes();
+        let marker = |len: usize| {
+            (len..=bytes.len())
+                .find(|&end| {
+                    let window = &bytes[end - len..end];
+                    (0..len).all(|i| !window[i + 1..].contains(&window[i]))
+                })
+                .unwrap()
+        };
+        (marker(4), marker(14))
+    }
+
+    proptest! {
+        #[test]
+        fn agrees_with_reference(input in differential::inputs(30)) {
+            differential::check::<Day06>(input, reference)?;
+        }
+    }
+}
@@ -0,0 +1,580 @@
+//! Day 7: No Space Left On Device
+
+use core::fmt;
+use std::collections::HashSet;
+
+use color_eyre::Result;
+use nom::{
+    branch::alt,
+    character::complete::{line_ending, not_line_ending},
+    combinator::{eof, map, peek, value},
+    error::context,
+    multi::many_till,
+    sequence::{delimited, preceded, separated_pair, terminated},
+    Finish,
+};
+use petgraph::{algo::toposort, prelude::*, stable_graph::NodeIndex, visit::EdgeRef};
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    params::Params,
+    parsing::{number, tag, IResult, ParseError},
+    render::{self, Colour, Render, Scene},
+    Error, PuzzleError, Solution,
+};
+
+pub struct Day07;
+
+/// Why a file system has no directory to delete.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum Day07Error {
+    #[error("the files are too large to add up")]
+    TooLarge,
+    #[error("the files take up {used}, more than the disk's capacity")]
+    DiskTooFull { used: usize },
+    #[error("the files take up {used}, leaving enough space for the update already")]
+    EnoughSpace { used: usize },
+}
+
+impl PuzzleError for Day07Error {}
+
+/// The sizes the puzzle is set with.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Day07Params {
+    /// The size of the disk.
+    pub capacity: usize,
+    /// The free space needed to install the update.
+    pub needed: usize,
+    /// The largest directory counted in the first part.
+    pub small_directory_limit: usize,
+}
+
+impl Default for Day07Params {
+    fn default() -> Self {
+        Self {
+            capacity: 70_000_000,
+            needed: 30_000_000,
+            small_directory_limit: 100_000,
+        }
+    }
+}
+
+impl Params for Day07Params {
+    fn validate(&self) -> Result<(), String> {
+        if self.needed > self.capacity {
+            return Err(format!(
+                "the update needs {}, more than the disk's capacity of {}",
+                self.needed, self.capacity
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Solution for Day07 {
+    const DAY: u8 = 7;
+
+    type Parsed<'i> = FileSystem<'i>;
+    type Part1 = usize;
+    type Part2 = usize;
+    type Error = Day07Error;
+    type Params = Day07Params;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, Error<Day07Error>> {
+        FileSystem::parse(input)
+    }
+
+    fn part1(fs: &Self::Parsed<'_>) -> Result<Self::Part1, Error<Day07Error>> {
+        Self::part1_with(fs, &Day07Params::default())
+    }
+
+    fn part2(fs: &Self::Parsed<'_>) -> Result<Self::Part2, Error<Day07Error>> {
+        Self::part2_with(fs, &Day07Params::default())
+    }
+
+    fn part1_with(
+        fs: &Self::Parsed<'_>,
+        params: &Day07Params,
+    ) -> Result<Self::Part1, Error<Day07Error>> {
+        let part1_sum = fs
+            .directory_sizes()
+            .filter(|&size| size <= params.small_directory_limit)
+            .sum();
+        Ok(part1_sum)
+    }
+
+    fn part2_with(
+        fs: &Self::Parsed<'_>,
+        params: &Day07Params,
+    ) -> Result<Self::Part2, Error<Day07Error>> {
+        let total_size = fs.total_size();
+        let remaining = params
+            .capacity
+            .checked_sub(total_size)
+            .ok_or(Day07Error::DiskTooFull { used: total_size })?;
+        let to_free = params
+            .needed
+            .checked_sub(remaining)
+            .filter(|&to_free| to_free > 0)
+            .ok_or(Day07Error::EnoughSpace { used: total_size })?;
+
+        let part2_ans = fs
+            .directory_sizes()
+            .filter(|&size| size >= to_free)
+            .min()
+            .unwrap_or_default();
+
+        Ok(part2_ans)
+    }
+
+    fn visualise(fs: &Self::Parsed<'_>) -> Result<Option<Scene>, Error<Day07Error>> {
+        Ok(Some(fs.scene()))
+    }
+
+    fn generate<R: Rng>(rng: &mut R, size: usize) -> String {
+        Self::generate_with(rng, size, &Day07Params::default())
+    }
+
+    /// Generates a transcript exploring `size` directories, filled to leave some space to free.
+    fn generate_with<R: Rng>(rng: &mut R, size: usize, params: &Day07Params) -> String {
+        let size = size.max(1);
+        let mut dirs = vec![GeneratedDirectory::default(); size];
+        for idx in 1..size {
+            // Choosing from recent directories gives a tree which is neither flat nor one long path.
+            let parent = rng.gen_range(idx.saturating_sub(20)..idx);
+            dirs[parent].subdirs.push(idx);
+        }
+        for (idx, dir) in dirs.iter_mut().enumerate() {
+            let count = rng.gen_range(usize::from(idx == 0)..=4);
+            dir.files = (0..count).map(|_| rng.gen_range(1000..=300_000)).collect();
+        }
+        let weight: usize = dirs.iter().flat_map(|dir| &dir.files).sum();
+        // Leave between a sixth and five sixths of the space needed to free.
+        let (free, margin) = (params.capacity - params.needed, params.needed / 6);
+        let total = rng.gen_range(free + margin..=params.capacity - margin);
+        for file in dirs.iter_mut().flat_map(|dir| &mut dir.files) {
+            *file = (*file * total / weight).max(1);
+        }
+
+        let mut out = String::from("$ cd /\n");
+        let mut stack = vec![dirs[0].list(rng, &mut out)];
+        while let Some(pending) = stack.last_mut() {
+            match pending.pop() {
+                Some((name, idx)) => {
+                    out.push_str(&format!("$ cd {name}\n"));
+                    stack.push(dirs[idx].list(rng, &mut out));
+                }
+                None => {
+                    stack.pop();
+                    if !stack.is_empty() {
+                        out.push_str("$ cd ..\n");
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+/// A directory of a generated file system, before its contents are named.
+#[derive(Clone, Debug, Default)]
+struct GeneratedDirectory {
+    /// The indices of the directories within this one.
+    subdirs: Vec<usize>,
+    /// The sizes of the files within this one.
+    files: Vec<usize>,
+}
+
+impl GeneratedDirectory {
+    /// Writes the output of `ls` for this directory, returning the name and index of each
+    /// subdirectory.
+    fn list<R: Rng>(&self, rng: &mut R, out: &mut String) -> Vec<(String, usize)> {
+        let mut names = HashSet::new();
+        let mut unique_name = |rng: &mut R, extension: bool| loop {
+            let len = rng.gen_range(1..=8);
+            let mut name = (0..len)
+                .map(|_| rng.gen_range('a'..='z'))
+                .collect::<String>();
+            if extension && rng.gen_bool(0.5) {
+                name.push('.');
+                name.extend((0..3).map(|_| rng.gen_range('a'..='z')));
+            }
+            if names.insert(name.clone()) {
+                break name;
+            }
+        };
+
+        let mut lines = Vec::new();
+        let mut subdirs = Vec::new();
+        for &idx in &self.subdirs {
+            let name = unique_name(rng, false);
+            lines.push(format!("dir {name}"));
+            subdirs.push((name, idx));
+        }
+        for &size in &self.files {
+            lines.push(format!("{size} {}", unique_name(rng, true)));
+        }
+        lines.shuffle(rng);
+
+        out.push_str("$ ls\n");
+        for line in lines {
+            out.push_str(&line);
+            out.push('\n');
+        }
+        subdirs
+    }
+}
+
+/// A file system reconstructed from a shell transcript, with directory sizes calculated.
+pub struct FileSystem<'a> {
+    graph: DiGraph<NodeWithDirSize, Edge<'a>>,
+    root: NodeIndex,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Node {
+    Directory,
+    File { size: usize },
+}
+
+/// A file or directory, with the total size of its contents.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NodeWithDirSize {
+    Directory { size: usize },
+    File { size: usize },
+}
+
+impl NodeWithDirSize {
+    pub fn size(&self) -> usize {
+        match self {
+            Self::Directory { size } | Self::File { size } => *size,
+        }
+    }
+}
+
+impl fmt::Display for NodeWithDirSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.size())
+    }
+}
+
+/// The name of a file or directory within its parent directory.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Edge<'a>(pub &'a str);
+
+impl fmt::Display for Edge<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl<'a> FileSystem<'a> {
+    /// Reconstructs the file system from the `cd` and `ls` commands in a shell transcript.
+    pub fn parse(transcript: &'a str) -> Result<Self, Error<Day07Error>> {
+        let mut input = transcript;
+        let mut graph = petgraph::graph::DiGraph::<Node, Edge>::new();
+        let root_node = graph.add_node(Node::Directory);
+
+        let mut current_path = Vec::new();
+        let mut current_node = root_node;
+
+        let located = |e| ParseError::from_nom(transcript, e);
+        while let (rest, Some(cmd)) = parse_command_or_end(input).finish().map_err(located)? {
+            input = match cmd {
+                Command::ChangeDirectory("..") => {
+                    current_path.pop();
+                    current_node = graph
+                        .neighbors_directed(current_node, petgraph::Direction::Incoming)
+                        .next()
+                        .ok_or_else(|| {
+                            ParseError::new(
+                                transcript,
+                                input,
+                                "can't change directory up from the root",
+                            )
+                        })?;
+                    rest
+                }
+                Command::ChangeDirectory("/") => {
+                    current_path.clear();
+                    current_node = root_node;
+                    rest
+                }
+                Command::ChangeDirectory(name) => {
+                    current_node = add_or_get_directory(&mut graph, current_node, name);
+                    current_path.push(name);
+                    rest
+                }
+                Command::List => {
+                    let (rest, resp) = parse_list_response(rest).finish().map_err(located)?;
+
+                    for line in resp {
+                        match line {
+                            ListResponseLine::Directory(name) => {
+                                add_or_get_directory(&mut graph, current_node, name)
+                            }
+                            ListResponseLine::File(name, size) => {
+                                add_child(&mut graph, current_node, name, Node::File { size })
+                            }
+                        };
+                    }
+
+                    rest
+                }
+            }
+        }
+
+        let graph = calculate_sizes(&graph).ok_or(Day07Error::TooLarge)?;
+        Ok(Self {
+            graph,
+            root: root_node,
+        })
+    }
+
+    /// The graph of the file system, with edges from each directory to its contents.
+    pub fn graph(&self) -> &DiGraph<NodeWithDirSize, Edge<'a>> {
+        &self.graph
+    }
+
+    /// The root directory.
+    pub fn root(&self) -> NodeIndex {
+        self.root
+    }
+
+    /// The total size of every file in the file system.
+    pub fn total_size(&self) -> usize {
+        self.graph
+            .node_weight(self.root)
+            .map(|n| n.size())
+            .unwrap_or_default()
+    }
+
+    /// The size of every directory, including the root.
+    pub fn directory_sizes(&self) -> impl Iterator<Item = usize> + '_ {
+        self.graph.node_weights().filter_map(|n| match n {
+            NodeWithDirSize::Directory { size } => Some(*size),
+            NodeWithDirSize::File { .. } => None,
+        })
+    }
+}
+
+impl Render for FileSystem<'_> {
+    /// A tree of the file system, with each file and directory labelled with its total size.
+    fn scene(&self) -> Scene {
+        render::Graph::from_petgraph(
+            &self.graph,
+            |_, node| {
+                let drawn = render::Node::new(node);
+                match node {
+                    NodeWithDirSize::Directory { .. } => drawn.fill(Colour::new(0xdd, 0xe6, 0xf5)),
+                    NodeWithDirSize::File { .. } => drawn,
+                }
+            },
+            |edge| render::Edge::between(edge).label(edge.weight()),
+        )
+        .into()
+    }
+}
+
+/// Adds up the size of each directory, or returns `None` if a size overflows.
+fn calculate_sizes<'e>(
+    graph: &DiGraph<Node, Edge<'e>>,
+) -> Option<DiGraph<NodeWithDirSize, Edge<'e>>> {
+    let mut new_graph = graph.map(
+        |_, node| match node {
+            Node::Directory => NodeWithDirSize::Directory { size: 0 },
+            Node::File { size } => NodeWithDirSize::File { size: *size },
+        },
+        |_, e| *e,
+    );
+    // Sizing the contents of each directory before the directory itself avoids recursing, which
+    // could overflow the stack for deeply nested directories.
+    let order = toposort(&new_graph, None).expect("file system is acyclic");
+    for node in order.into_iter().rev() {
+        if let NodeWithDirSize::Directory { .. } = new_graph[node] {
+            let size = new_graph
+                .edges_directed(node, Direction::Outgoing)
+                .try_fold(0usize, |total, e| {
+                    total.checked_add(new_graph[e.target()].size())
+                })?;
+            new_graph[node] = NodeWithDirSize::Directory { size };
+        }
+    }
+
+    Some(new_graph)
+}
+
+fn add_or_get_directory<'a>(
+    graph: &mut DiGraph<Node, Edge<'a>>,
+    current_node: NodeIndex,
+    name: &'a str,
+) -> NodeIndex {
+    if let Some(edge) = graph
+        .edges_directed(current_node, petgraph::Direction::Outgoing)
+        .find(|e| e.weight().0 == name)
+    {
+        edge.target()
+    } else {
+        add_child(graph, current_node, name, Node::Directory)
+    }
+}
+
+fn add_child<'a>(
+    graph: &mut DiGraph<Node, Edge<'a>>,
+    current_node: NodeIndex,
+    name: &'a str,
+    node: Node,
+) -> NodeIndex {
+    let node = graph.add_node(node);
+    graph.add_edge(current_node, node, Edge(name));
+    node
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Command<'a> {
+    ChangeDirectory(&'a str),
+    List,
+}
+
+fn parse_command(s: &str) -> IResult<'_, Command<'_>> {
+    context(
+        "command",
+        delimited(
+            tag("$ "),
+            alt((
+                value(Command::List, tag("ls")),
+                map(
+                    preceded(tag("cd "), not_line_ending),
+                    Command::ChangeDirectory,
+                ),
+            )),
+            line_ending,
+        ),
+    )(s)
+}
+
+fn parse_command_or_end(s: &str) -> IResult<'_, Option<Command<'_>>> {
+    alt((value(None, eof), map(parse_command, Some)))(s)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ListResponseLine<'a> {
+    Directory(&'a str),
+    File(&'a str, usize),
+}
+
+fn parse_list_line(s: &str) -> IResult<'_, ListResponseLine<'_>> {
+    context(
+        "directory listing",
+        terminated(
+            alt((
+                map(
+                    separated_pair(number, tag(" "), not_line_ending),
+                    |(size, name)| ListResponseLine::File(name, size),
+                ),
+                map(
+                    preceded(tag("dir "), not_line_ending),
+                    ListResponseLine::Directory,
+                ),
+            )),
+            line_ending,
+        ),
+    )(s)
+}
+
+fn parse_list_response(s: &str) -> IResult<'_, Vec<ListResponseLine<'_>>> {
+    map(
+        many_till(parse_list_line, peek(alt((tag("$"), eof)))),
+        |(list, _)| list,
+    )(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use proptest::proptest;
+    use test_case::test_case;
+
+    use super::*;
+    use crate::{differential, params, Runner};
+
+    #[test_case(include_str!("../input/day07test") => matches Ok((95437, 24933642)))]
+    fn default_tests(input: &str) -> Result<(usize, usize), Error<Day07Error>> {
+        crate::solve::<Day07>(input)
+    }
+
+    #[test_case("$ cd /\n$ ls\n70000000 a\n" => Ok((0, 70_000_000)) ; "full disk")]
+    #[test_case("$ cd /\n$ ls\n70000001 a\n" => Err(Error::Puzzle(Day07Error::DiskTooFull { used: 70_000_001 })) ; "overfull disk")]
+    #[test_case("$ cd /\n$ ls\n10 a\n" => Err(Error::Puzzle(Day07Error::EnoughSpace { used: 10 })) ; "nothing to free")]
+    #[test_case("$ ls\n18446744073709551615 a\n1 b\n" => Err(Error::Puzzle(Day07Error::TooLarge)) ; "overflow")]
+    fn unsolvable_input(input: &str) -> Result<(usize, usize), Error<Day07Error>> {
+        crate::solve::<Day07>(input)
+    }
+
+    #[test_case(&["capacity=100", "needed=50", "small_directory_limit=70"] => Ok(("130".to_owned(), "60".to_owned())) ; "small disk")]
+    #[test_case(&["capacity=100", "needed=101"] => Err("configuring day 7: the update needs 101, more than the disk's capacity of 100".to_owned()) ; "update too large")]
+    fn overridden_sizes(overrides: &[&str]) -> Result<(String, String), String> {
+        let input = "$ cd /\n$ ls\ndir a\n10 b\n$ cd a\n$ ls\n60 c\n";
+        let solution = Day07
+            .configure(&params::overrides(overrides))
+            .map_err(|e| format!("{e:#}"))?;
+        let answers = solution.run(input).unwrap();
+        Ok((answers.part1, answers.part2))
+    }
+
+    #[test]
+    fn cd_up_from_root_is_malformed() {
+        let Err(Error::Parse(e)) = Day07::parse("$ cd /\n$ cd ..\n") else {
+            panic!("expected a parse error");
+        };
+        assert_eq!(
+            (e.line(), e.message()),
+            (2, "can't change directory up from the root")
+        );
+    }
+
+    /// Adds each file's size to every directory on its path, keyed by the path.
+    fn reference(input: &str) -> (usize, usize) {
+        let mut path = Vec::new();
+        let mut sizes = HashMap::from([(Vec::new(), 0)]);
+        for line in input.lines() {
+            match line.split(' ').collect::<Vec<_>>()[..] {
+                ["$", "cd", "/"] => path.clear(),
+                ["$", "cd", ".."] => {
+                    path.pop();
+                }
+                ["$", "cd", name] => path.push(name),
+                ["$", "ls"] => {}
+                ["dir", name] => {
+                    sizes.entry([&path[..], &[name]].concat()).or_insert(0);
+                }
+                [size, _] => {
+                    let size = size.parse::<usize>().unwrap();
+                    for depth in 0..=path.len() {
+                        *sizes.entry(path[..depth].to_vec()).or_insert(0) += size;
+                    }
+                }
+                _ => panic!("unexpected line `{line}`"),
+            }
+        }
+
+        let to_free = sizes[&Vec::new()] - 40_000_000;
+        let part1 = sizes.values().filter(|&&size| size <= 100_000).sum();
+        let part2 = sizes
+            .values()
+            .copied()
+            .filter(|&size| size >= to_free)
+            .min();
+        (part1, part2.unwrap())
+    }
+
+    proptest! {
+        #[test]
+        fn agrees_with_reference(input in differential::inputs(30)) {
+            differential::check::<Day07>(input, reference)?;
+        }
+    }
+}
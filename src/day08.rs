@@ -0,0 +1,245 @@
+//! Day 8: Treetop Tree House
+
+use std::convert::Infallible;
+
+use color_eyre::Result;
+use fxhash::FxHashSet;
+use rand::Rng;
+use tracing::trace;
+
+use crate::{
+    geometry::{Direction4, Point},
+    grid::Grid,
+    params::NoParams,
+    parsing::ParseError,
+    Error, Solution,
+};
+
+pub struct Day08;
+
+impl Solution for Day08 {
+    const DAY: u8 = 8;
+
+    type Parsed<'i> = Forest;
+    type Part1 = usize;
+    type Part2 = usize;
+    type Error = Infallible;
+    type Params = NoParams;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, Error<Infallible>> {
+        Ok(Forest::parse(input)?)
+    }
+
+    fn part1(forest: &Self::Parsed<'_>) -> Result<Self::Part1, Error<Infallible>> {
+        Ok(trees_seen_from_edge(forest))
+    }
+
+    fn part2(forest: &Self::Parsed<'_>) -> Result<Self::Part2, Error<Infallible>> {
+        Ok(best_view_score(forest))
+    }
+
+    /// Generates a square forest `size` trees across.
+    fn generate<R: Rng>(rng: &mut R, size: usize) -> String {
+        let size = size.max(1);
+        let mut out = String::with_capacity((size + 1) * size);
+        for _ in 0..size {
+            out.extend((0..size).map(|_| char::from(b'0' + rng.gen_range(0..=9))));
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// A grid of tree heights, from `0` to `9`.
+#[derive(Clone, Debug)]
+pub struct Forest {
+    trees: Grid<u8>,
+}
+
+/// The position of a tree, from the top-left of the forest.
+pub type Position = Point<usize>;
+
+impl Forest {
+    /// Parses a map of tree heights, one row per line.
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let trees = Grid::parse(input, |c| c.to_digit(10).map(|d| d as u8))?;
+        Ok(Self { trees })
+    }
+
+    pub fn trees(&self) -> &Grid<u8> {
+        &self.trees
+    }
+
+    pub fn width(&self) -> usize {
+        self.trees.width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.trees.height()
+    }
+
+    /// The height of the given tree.
+    pub fn get(&self, tree: Position) -> Option<u8> {
+        self.trees.get(tree).copied()
+    }
+
+    /// The scenic score of a tree: the product of the viewing distances in each direction.
+    ///
+    /// Returns `None` if the position is outside the forest.
+    pub fn view_score(&self, tree: Position) -> Option<usize> {
+        let view_height = self.get(tree)?;
+        let score = Direction4::ALL
+            .into_iter()
+            .map(|direction| self.view_from(direction, tree, view_height).count())
+            .product();
+        Some(score)
+    }
+
+    /// The trees visible from the given tree, looking in the given direction.
+    pub fn view_from(
+        &self,
+        direction: Direction4,
+        tree: Position,
+        view_height: u8,
+    ) -> impl Iterator<Item = Position> + '_ {
+        let mut stop = false;
+        self.trees.ray(tree, direction).map_while(move |tree| {
+            if stop {
+                return None;
+            }
+
+            let tree_height = self.get(tree)?;
+            if tree_height >= view_height {
+                stop = true;
+            }
+            Some(tree)
+        })
+    }
+
+    fn taller_than_priors(&self, initial_height: u8) -> impl FnMut(&Position) -> bool + '_ {
+        let mut max_height = initial_height;
+        move |&tree| {
+            let tree_height = self.get(tree).unwrap_or_default();
+            let taller = tree_height > max_height;
+            trace!(?tree, height = tree_height, taller, "looked at tree");
+            if taller {
+                max_height = tree_height;
+            }
+            taller
+        }
+    }
+}
+
+/// The highest scenic score of any tree in the forest.
+pub fn best_view_score(forest: &Forest) -> usize {
+    forest
+        .trees
+        .positions()
+        .filter_map(|tree| forest.view_score(tree))
+        .max()
+        .unwrap_or_default()
+}
+
+/// The number of trees visible from outside the forest.
+pub fn trees_seen_from_edge(forest: &Forest) -> usize {
+    let (width, height) = (forest.width(), forest.height());
+    if width == 0 || height == 0 {
+        return 0;
+    }
+
+    let mut seen = FxHashSet::default();
+    seen.extend([
+        Position::new(0, 0),
+        Position::new(width - 1, 0),
+        Position::new(0, height - 1),
+        Position::new(width - 1, height - 1),
+    ]);
+
+    let mut check = |tree, direction| {
+        let initial_height = forest.get(tree).unwrap_or_default();
+        trace!(
+            ?tree,
+            ?direction,
+            height = initial_height,
+            "looking in from the edge"
+        );
+        seen.insert(tree);
+        seen.extend(
+            forest
+                .view_from(direction, tree, 9)
+                .filter(forest.taller_than_priors(initial_height)),
+        )
+    };
+
+    for x in 1..width.saturating_sub(1) {
+        check(Position::new(x, 0), Direction4::Down);
+        check(Position::new(x, height - 1), Direction4::Up);
+    }
+
+    for y in 1..height.saturating_sub(1) {
+        check(Position::new(0, y), Direction4::Right);
+        check(Position::new(width - 1, y), Direction4::Left);
+    }
+
+    seen.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::proptest;
+    use test_case::test_case;
+
+    use super::*;
+    use crate::differential;
+
+    #[test_case(include_str!("../input/day08test") => matches Ok((21, 8)))]
+    #[test_case("30373\r\n25512\r\n65332\r\n33549\r\n35390\r\n" => matches Ok((21, 8)) ; "crlf")]
+    #[test_case("303\n255\n653\n335\n353\n" => matches Ok((14, 2)) ; "not square")]
+    fn default_tests(input: &str) -> Result<(usize, usize), Error<Infallible>> {
+        crate::solve::<Day08>(input)
+    }
+
+    #[test_case(include_str!("../input/day08test"), Position::new(2, 1) => Some(4))]
+    #[test_case(include_str!("../input/day08test"), Position::new(2, 3) => Some(8))]
+    #[test_case(include_str!("../input/day08test"), Position::new(5, 0) => None ; "outside")]
+    fn trees_seen_tests(input: &str, tree: Position) -> Option<usize> {
+        let forest = Forest::parse(input).unwrap();
+        forest.view_score(tree)
+    }
+
+    /// Looks along the row and column from every tree.
+    fn reference(input: &str) -> (usize, usize) {
+        let grid = input.lines().map(str::as_bytes).collect::<Vec<_>>();
+        let (mut visible, mut best) = (0, 0);
+        for (y, row) in grid.iter().enumerate() {
+            for (x, &tree) in row.iter().enumerate() {
+                let views: [Vec<u8>; 4] = [
+                    row[..x].iter().rev().copied().collect(),
+                    row[x + 1..].to_vec(),
+                    grid[..y].iter().rev().map(|r| r[x]).collect(),
+                    grid[y + 1..].iter().map(|r| r[x]).collect(),
+                ];
+                if views.iter().any(|view| view.iter().all(|&t| t < tree)) {
+                    visible += 1;
+                }
+                let score = views
+                    .iter()
+                    .map(|view| {
+                        view.iter()
+                            .position(|&t| t >= tree)
+                            .map_or(view.len(), |idx| idx + 1)
+                    })
+                    .product();
+                best = best.max(score);
+            }
+        }
+        (visible, best)
+    }
+
+    proptest! {
+        #[test]
+        fn agrees_with_reference(input in differential::inputs(30)) {
+            differential::check::<Day08>(input, reference)?;
+        }
+    }
+}
@@ -0,0 +1,254 @@
+//! Day 9: Rope Bridge
+
+use std::{convert::Infallible, io::BufRead};
+
+use color_eyre::Result;
+use fxhash::FxHashSet;
+use nom::{
+    branch::alt,
+    combinator::{map, value},
+    error::context,
+    sequence::separated_pair,
+};
+use rand::{seq::SliceRandom, Rng};
+use tracing::{debug_span, trace};
+
+use crate::{
+    geometry::{Direction4, Point},
+    params::NoParams,
+    parsing::{self, lines, number, tag, IResult},
+    stream::Input,
+    Error, Solution, StreamResult,
+};
+
+pub struct Day09;
+
+impl Solution for Day09 {
+    const DAY: u8 = 9;
+
+    type Parsed<'i> = Vec<Move>;
+    type Part1 = usize;
+    type Part2 = usize;
+    type Error = Infallible;
+    type Params = NoParams;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, Error<Infallible>> {
+        Ok(parsing::parse_all(input, lines(step))?)
+    }
+
+    fn part1(moves: &Self::Parsed<'_>) -> Result<Self::Part1, Error<Infallible>> {
+        Ok(run::<2>(moves))
+    }
+
+    fn part2(moves: &Self::Parsed<'_>) -> Result<Self::Part2, Error<Infallible>> {
+        Ok(run::<10>(moves))
+    }
+
+    /// Moves both ropes as each motion is read, but still records every position their tails
+    /// visit, so memory grows with the area the tails cover rather than the length of the input.
+    fn solve_streaming<R: BufRead>(input: &mut Input<R>) -> StreamResult<Self> {
+        let (mut short, mut long) = (Snake::<2>::new(), Snake::<10>::new());
+        for line in input.lines() {
+            let step = line.parse(step)?;
+            for _ in 0..step.distance {
+                short.advance(step.direction);
+                long.advance(step.direction);
+            }
+        }
+        Ok(Some((short.tail_visits(), long.tail_visits())))
+    }
+
+    /// Generates `size` motions of the head.
+    fn generate<R: Rng>(rng: &mut R, size: usize) -> String {
+        (0..size)
+            .map(|_| {
+                let direction = ['U', 'D', 'L', 'R'].choose(rng).unwrap();
+                format!("{direction} {}\n", rng.gen_range(1..=20))
+            })
+            .collect()
+    }
+}
+
+/// A motion of the head of the rope.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Move {
+    pub direction: Direction4,
+    pub distance: u8,
+}
+
+fn step(s: &str) -> IResult<'_, Move> {
+    context(
+        "motion",
+        map(
+            separated_pair(dir, tag(" "), number),
+            |(direction, distance)| Move {
+                direction,
+                distance,
+            },
+        ),
+    )(s)
+}
+
+fn dir(s: &str) -> IResult<'_, Direction4> {
+    alt((
+        value(Direction4::Up, tag("U")),
+        value(Direction4::Down, tag("D")),
+        value(Direction4::Left, tag("L")),
+        value(Direction4::Right, tag("R")),
+    ))(s)
+}
+
+/// The number of positions visited by the tail of a rope with `N` knots.
+pub fn run<const N: usize>(moves: &[Move]) -> usize {
+    let _rope = debug_span!("rope", knots = N).entered();
+    let mut snake = Snake::<N>::new();
+    for step in moves {
+        for _ in 0..step.distance {
+            snake.advance(step.direction);
+        }
+        trace!(direction = ?step.direction, distance = step.distance, knots = ?snake.segments, "moved");
+    }
+
+    snake.tail_visits()
+}
+
+/// The position of a knot, relative to where the rope started.
+///
+/// Each motion moves the head at most 255 steps, so with `i64` coordinates no realistic number of
+/// motions can take a knot out of range.
+pub type Position = Point<i64>;
+
+/// Whether the knots touch, including diagonally and overlapping.
+pub fn is_adjacent(a: Position, b: Position) -> bool {
+    a.chebyshev_distance(b) <= 1
+}
+
+/// Moves `tail` one step towards `head`.
+pub fn pull(head: Position, tail: &mut Position) {
+    *tail += (head - *tail).signum();
+}
+
+/// A rope with `N` knots, which records every position its tail has visited in a set that grows
+/// as the tail reaches new ground.
+#[derive(Clone, Debug)]
+pub struct Snake<const N: usize> {
+    segments: [Position; N],
+    tail_positions: FxHashSet<Position>,
+}
+
+impl<const N: usize> Default for Snake<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Snake<N> {
+    pub fn new() -> Self {
+        Self {
+            segments: [Position::origin(); N],
+            tail_positions: [Position::origin()].into_iter().collect(),
+        }
+    }
+
+    /// Moves the head one step, with the rest of the knots following.
+    pub fn advance(&mut self, direction: Direction4) {
+        let prior_tail = self.tail_position();
+        self.segments[0] = self.segments[0].step(direction);
+
+        let mut head = self.segments[0];
+        for tail in &mut self.segments[1..] {
+            if !is_adjacent(*tail, head) {
+                pull(head, tail);
+                head = *tail;
+            } else {
+                break;
+            }
+        }
+
+        if prior_tail != self.tail_position() {
+            self.tail_positions.insert(self.tail_position());
+        }
+    }
+
+    /// The position of every knot, from head to tail.
+    pub fn segments(&self) -> &[Position; N] {
+        &self.segments
+    }
+
+    pub fn tail_position(&self) -> Position {
+        self.segments[N - 1]
+    }
+
+    /// The number of distinct positions the tail has visited.
+    pub fn tail_visits(&self) -> usize {
+        self.tail_positions.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use proptest::proptest;
+    use test_case::test_case;
+
+    use super::*;
+    use crate::differential;
+
+    #[test_case(include_str!("../input/day09test") => matches Ok((13, 1)))]
+    #[test_case(include_str!("../input/day09test2") => matches Ok((_, 36)))]
+    fn default_tests(input: &str) -> Result<(usize, usize), Error<Infallible>> {
+        crate::solve::<Day09>(input)
+    }
+
+    #[test]
+    fn reports_overlong_steps_as_out_of_range() {
+        let Err(Error::Parse(error)) = differential::solve::<Day09>("R 4\nR 2000000000\n") else {
+            panic!("expected the step to be rejected");
+        };
+        assert_eq!((error.line(), error.column()), (2, 3));
+        assert!(
+            error
+                .message()
+                .ends_with("number out of range for u8, found `2000000000`"),
+            "{error}"
+        );
+    }
+
+    /// Moves the head one step at a time, pulling each knot after it.
+    fn reference(input: &str) -> (usize, usize) {
+        let visited = |knots: usize| {
+            let mut rope = vec![(0i32, 0i32); knots];
+            let mut seen = HashSet::from([(0, 0)]);
+            for line in input.lines() {
+                let (direction, distance) = line.split_once(' ').unwrap();
+                let (dx, dy) = match direction {
+                    "U" => (0, 1),
+                    "D" => (0, -1),
+                    "L" => (-1, 0),
+                    _ => (1, 0),
+                };
+                for _ in 0..distance.parse::<u32>().unwrap() {
+                    rope[0] = (rope[0].0 + dx, rope[0].1 + dy);
+                    for idx in 1..knots {
+                        let (ahead, knot) = (rope[idx - 1], &mut rope[idx]);
+                        if ahead.0.abs_diff(knot.0) > 1 || ahead.1.abs_diff(knot.1) > 1 {
+                            knot.0 += (ahead.0 - knot.0).signum();
+                            knot.1 += (ahead.1 - knot.1).signum();
+                        }
+                    }
+                    seen.insert(rope[knots - 1]);
+                }
+            }
+            seen.len()
+        };
+        (visited(2), visited(10))
+    }
+
+    proptest! {
+        #[test]
+        fn agrees_with_reference(input in differential::inputs(30)) {
+            differential::check::<Day09>(input, reference)?;
+        }
+    }
+}
@@ -0,0 +1,583 @@
+//! Day 10: Cathode-Ray Tube
+
+use std::{
+    convert::Infallible,
+    fmt::{self, Write},
+    iter,
+    marker::PhantomData,
+    ops::{self, ControlFlow},
+};
+
+use color_eyre::Result;
+use nom::{
+    branch::alt,
+    combinator::{map, value},
+    error::context,
+    sequence::preceded,
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, trace, trace_span};
+
+use crate::{
+    grid::Grid,
+    params::Params,
+    parsing::{self, lines, number, tag, IResult},
+    render::{Colour, Pixel, Render, Scene},
+    Error, PuzzleError, Solution,
+};
+
+pub struct Day10;
+
+/// The most rows the CRT can have.
+pub const MAX_ROWS: usize = 1024;
+
+/// The most pixels in each row of the CRT, one for each bit of a `u64`.
+pub const MAX_COLUMNS: usize = 64;
+
+/// Why a program has no answer.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum Day10Error {
+    #[error("the signal strength is too large to add up")]
+    SignalTooStrong,
+    #[error(
+        "the screen must have between 1 and {MAX_ROWS} rows and between 1 and {MAX_COLUMNS} \
+         columns, not {rows} by {columns}"
+    )]
+    ScreenSize { rows: usize, columns: usize },
+}
+
+impl PuzzleError for Day10Error {}
+
+/// The size of the CRT.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Day10Params {
+    /// The number of rows, which can't be more than [`MAX_ROWS`].
+    pub rows: usize,
+    /// The number of pixels in each row, which can't be more than [`MAX_COLUMNS`].
+    pub columns: usize,
+}
+
+impl Default for Day10Params {
+    fn default() -> Self {
+        Self {
+            rows: 6,
+            columns: 40,
+        }
+    }
+}
+
+impl Params for Day10Params {
+    fn validate(&self) -> Result<(), String> {
+        check_screen_size(self.rows, self.columns).map_err(|err| err.to_string())
+    }
+}
+
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+
+    type Parsed<'i> = Vec<OpCode>;
+    type Part1 = i64;
+    type Part2 = String;
+    type Error = Day10Error;
+    type Params = Day10Params;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, Error<Day10Error>> {
+        Ok(parsing::parse_all(input, lines(OpCode::token))?)
+    }
+
+    fn part1(ops: &Self::Parsed<'_>) -> Result<Self::Part1, Error<Day10Error>> {
+        Self::part1_with(ops, &Day10Params::default())
+    }
+
+    fn part2(ops: &Self::Parsed<'_>) -> Result<Self::Part2, Error<Day10Error>> {
+        Self::part2_with(ops, &Day10Params::default())
+    }
+
+    fn part1_with(
+        ops: &Self::Parsed<'_>,
+        params: &Day10Params,
+    ) -> Result<Self::Part1, Error<Day10Error>> {
+        let (signal, _) = run(ops, params)?;
+        Ok(signal)
+    }
+
+    fn part2_with(
+        ops: &Self::Parsed<'_>,
+        params: &Day10Params,
+    ) -> Result<Self::Part2, Error<Day10Error>> {
+        let (_, computer) = run(ops, params)?;
+        Ok(computer.screen.to_string())
+    }
+
+    fn visualise(ops: &Self::Parsed<'_>) -> Result<Option<Scene>, Error<Day10Error>> {
+        Self::visualise_with(ops, &Day10Params::default())
+    }
+
+    fn visualise_with(
+        ops: &Self::Parsed<'_>,
+        params: &Day10Params,
+    ) -> Result<Option<Scene>, Error<Day10Error>> {
+        let (_, computer) = run(ops, params)?;
+        Ok(Some(computer.screen.scene()))
+    }
+
+    fn generate<R: Rng>(rng: &mut R, size: usize) -> String {
+        Self::generate_with(rng, size, &Day10Params::default())
+    }
+
+    /// Generates a program of `size` instructions, keeping the sprite near the screen.
+    fn generate_with<R: Rng>(rng: &mut R, size: usize, params: &Day10Params) -> String {
+        let right = params.columns as i64 + 5;
+        let mut x = 1i64;
+        (0..size)
+            .map(|_| {
+                if rng.gen_bool(0.3) {
+                    return "noop\n".to_owned();
+                }
+                let target = (x + rng.gen_range(-20..=20)).clamp(-5, right);
+                let step = if target == x { 1 } else { target - x };
+                x += step;
+                format!("addx {step}\n")
+            })
+            .collect()
+    }
+}
+
+/// Checks that a screen of the given size can be drawn on.
+fn check_screen_size(rows: usize, columns: usize) -> Result<(), Day10Error> {
+    if !(1..=MAX_ROWS).contains(&rows) || !(1..=MAX_COLUMNS).contains(&columns) {
+        return Err(Day10Error::ScreenSize { rows, columns });
+    }
+    Ok(())
+}
+
+/// Runs the program to completion, returning the sum of the signal strengths sampled every 40 cycles
+/// from the 20th, and the computer in its final state.
+pub fn run(ops: &[OpCode], params: &Day10Params) -> Result<(i64, Computer), Day10Error> {
+    let mut computer = Computer::new(params.rows, params.columns)?;
+    let mut signal = 0;
+    let mut exec = computer.execute(ops.iter().copied().map(Ok::<_, Infallible>));
+
+    loop {
+        match exec.tick() {
+            ControlFlow::Continue(_) => {}
+            ControlFlow::Break(Ok(_)) => break,
+            ControlFlow::Break(Err(err)) => match err {},
+        }
+
+        let cycle = exec.computer.clock.cycle.0 + 1;
+        if (cycle + 20).is_multiple_of(40) {
+            let x = exec.computer.cpu.registers.x;
+            signal = (cycle as i64)
+                .checked_mul(x)
+                .and_then(|strength| strength.checked_add(signal))
+                .ok_or(Day10Error::SignalTooStrong)?;
+            debug!(cycle, x, signal, "sampled signal strength");
+        }
+    }
+
+    Ok((signal, computer))
+}
+
+/// The handheld device and its CRT.
+#[derive(Debug)]
+pub struct Computer {
+    clock: Clock,
+    cpu: Cpu,
+    screen: Crt,
+}
+
+impl Computer {
+    /// A computer whose CRT has the given number of rows and columns, failing if either is zero
+    /// or more than [`MAX_ROWS`] or [`MAX_COLUMNS`] respectively.
+    pub fn new(rows: usize, columns: usize) -> Result<Self, Day10Error> {
+        Ok(Self {
+            clock: Clock::default(),
+            cpu: Cpu::default(),
+            screen: Crt::new(rows, columns)?,
+        })
+    }
+
+    /// The number of cycles completed so far.
+    pub fn cycle(&self) -> u32 {
+        self.clock.cycle.0
+    }
+
+    /// The current value of the `X` register.
+    pub fn x(&self) -> i64 {
+        self.cpu.registers.x
+    }
+
+    pub fn screen(&self) -> &Crt {
+        &self.screen
+    }
+
+    /// Prepares to run a program, one cycle per call to [`Execution::tick`].
+    pub fn execute<I, E>(&mut self, ops: I) -> Execution<'_, I::IntoIter, E>
+    where
+        I: IntoIterator<Item = Result<OpCode, E>>,
+    {
+        let ops = ops.into_iter().fuse();
+        Execution {
+            computer: self,
+            ops,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+/// A program running on a [`Computer`].
+#[derive(Debug)]
+pub struct Execution<'a, I, E> {
+    computer: &'a mut Computer,
+    ops: iter::Fuse<I>,
+    _phantom: PhantomData<*const E>,
+}
+
+impl<'a, I, E> Execution<'a, I, E>
+where
+    I: Iterator<Item = Result<OpCode, E>>,
+{
+    /// Runs a single cycle, breaking once the program ends or fails to provide an instruction.
+    pub fn tick(&mut self) -> ControlFlow<Result<(), E>> {
+        let _cycle = trace_span!("cycle", number = self.computer.clock.cycle.0 + 1).entered();
+        self.computer.cpu.read_instruction(&mut self.ops)?;
+        self.computer.clock.tick();
+        self.computer.screen.tick(&self.computer.cpu.registers);
+        self.computer.cpu.tick();
+        ControlFlow::Continue(())
+    }
+}
+
+#[derive(Debug)]
+struct Clock {
+    cycle: Cycles,
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Self {
+            cycle: Cycles::ZERO,
+        }
+    }
+}
+
+impl Clock {
+    fn tick(&mut self) {
+        self.cycle.incr();
+    }
+}
+
+#[derive(Debug)]
+struct Cpu {
+    registers: Registers,
+    delay: Cycles,
+    current_op: OpCode,
+}
+
+impl Default for Cpu {
+    fn default() -> Self {
+        Self {
+            registers: Registers::default(),
+            delay: Cycles::ZERO,
+            current_op: OpCode::Noop,
+        }
+    }
+}
+
+impl Cpu {
+    fn read_instruction<E>(
+        &mut self,
+        ops: &mut impl Iterator<Item = Result<OpCode, E>>,
+    ) -> ControlFlow<Result<(), E>> {
+        if self.delay == Cycles::ZERO {
+            let Some(op) = ops.next() else {
+                return ControlFlow::Break(Ok(()));
+            };
+            let op = match op {
+                Ok(op) => op,
+                Err(err) => return ControlFlow::Break(Err(err)),
+            };
+
+            self.current_op = op;
+            self.delay = op.delay();
+            trace!(?op, cycles = op.cycles(), "starting instruction");
+        }
+
+        ControlFlow::Continue(())
+    }
+
+    fn tick(&mut self) {
+        self.delay.decr();
+        if self.delay == Cycles::ZERO {
+            self.registers.apply(self.current_op);
+            trace!(op = ?self.current_op, x = self.registers.x, "finished instruction");
+        }
+    }
+}
+
+/// The CRT display, drawing one pixel per cycle.
+#[derive(Clone, Debug)]
+pub struct Crt {
+    columns: usize,
+    column: usize,
+    row: usize,
+    /// The lit pixels of each row, as a bit per column.
+    screen: Vec<u64>,
+}
+
+impl fmt::Display for Crt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in &self.screen {
+            for i in 0..self.columns {
+                f.write_char(if (1 << i) & row != 0 { '#' } else { '.' })?;
+            }
+            f.write_char('\n')?;
+        }
+        Ok(())
+    }
+}
+
+impl Render for Crt {
+    fn scene(&self) -> Scene {
+        let lit = Pixel::new('#', Colour::new(0xff, 0xff, 0x66));
+        let dark = Pixel::new('.', Colour::new(0x0f, 0x0f, 0x23));
+        let pixels = (0..self.screen.len())
+            .flat_map(|row| (0..self.columns).map(move |column| self.is_lit(row, column)))
+            .map(|is_lit| if is_lit { lit } else { dark })
+            .collect();
+        Grid::from_vec(self.columns, pixels)
+            .expect("every row has the same number of columns")
+            .into()
+    }
+}
+
+impl Crt {
+    fn new(rows: usize, columns: usize) -> Result<Self, Day10Error> {
+        check_screen_size(rows, columns)?;
+        Ok(Self {
+            columns,
+            column: 0,
+            row: 0,
+            screen: vec![0; rows],
+        })
+    }
+
+    /// Whether the pixel at the given row and column is lit.
+    pub fn is_lit(&self, row: usize, column: usize) -> bool {
+        column < self.columns && self.screen.get(row).is_some_and(|r| (1 << column) & r != 0)
+    }
+
+    fn tick_cursor(&mut self) {
+        self.column = (self.column + 1) % self.columns;
+        if self.column == 0 {
+            self.row = (self.row + 1) % self.screen.len();
+        }
+    }
+
+    fn tick(&mut self, registers: &Registers) {
+        let lit = (registers.x - 1..=registers.x + 1).contains(&(self.column as i64));
+        if lit {
+            self.screen[self.row] |= 1 << self.column;
+        }
+        trace!(row = self.row, column = self.column, lit, "drew pixel");
+        self.tick_cursor()
+    }
+}
+
+#[derive(Debug)]
+struct Registers {
+    x: i64,
+}
+
+impl Registers {
+    fn apply(&mut self, op: OpCode) {
+        match op {
+            OpCode::Noop => {}
+            OpCode::Addx(val) => self.x += i64::from(val),
+        }
+    }
+}
+
+impl Default for Registers {
+    fn default() -> Self {
+        Self { x: 1 }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Cycles(u32);
+
+impl Cycles {
+    const ZERO: Self = Self(0);
+
+    fn decr(&mut self) {
+        self.0 -= 1;
+    }
+
+    fn incr(&mut self) {
+        self.0 += 1;
+    }
+}
+
+impl ops::AddAssign for Cycles {
+    fn add_assign(&mut self, rhs: Self) {
+        self.0 += rhs.0
+    }
+}
+
+impl ops::Add for Cycles {
+    type Output = Self;
+
+    fn add(mut self, rhs: Self) -> Self::Output {
+        self += rhs;
+        self
+    }
+}
+
+/// An instruction for the CPU.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OpCode {
+    Noop,
+    Addx(i32),
+}
+
+impl OpCode {
+    /// The number of cycles the instruction takes to complete.
+    pub fn cycles(&self) -> u32 {
+        self.delay().0
+    }
+
+    fn delay(&self) -> Cycles {
+        match self {
+            Self::Noop => Cycles(1),
+            Self::Addx(_) => Cycles(2),
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Self> {
+        Ok(parsing::parse_all(s, Self::token)?)
+    }
+
+    fn token(s: &str) -> IResult<'_, Self> {
+        context(
+            "instruction",
+            alt((
+                value(Self::Noop, tag("noop")),
+                map(preceded(tag("addx "), number), Self::Addx),
+            )),
+        )(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::proptest;
+    use test_case::test_case;
+
+    use super::*;
+    use crate::{differential, params, Runner};
+
+    #[rustfmt::skip]
+    const EXPECTED: &str = "\
+        ##..##..##..##..##..##..##..##..##..##..\n\
+        ###...###...###...###...###...###...###.\n\
+        ####....####....####....####....####....\n\
+        #####.....#####.....#####.....#####.....\n\
+        ######......######......######......####\n\
+        #######.......#######.......#######.....\n";
+
+    #[test_case(include_str!("../input/day10test") => matches Ok((13140, s)) if s == EXPECTED)]
+    #[test_case(include_str!("../input/day10test2") => matches Ok((_, s)) if &s[..21] == "##..##..##..##..##..#")]
+    fn default_tests(input: &str) -> Result<(i64, String), Error<Day10Error>> {
+        crate::solve::<Day10>(input)
+    }
+
+    #[test]
+    fn signal_overflow() {
+        let input = "addx 2147483647\n".repeat(1 << 17);
+        assert_eq!(
+            crate::solve::<Day10>(&input),
+            Err(Error::Puzzle(Day10Error::SignalTooStrong))
+        );
+    }
+
+    #[test]
+    fn large_additions_are_malformed() {
+        assert!(matches!(
+            Day10::parse("addx 9223372036854775807\n"),
+            Err(Error::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn draws_on_a_smaller_screen() {
+        let solution = Day10
+            .configure(&params::overrides(&["rows=2", "columns=8"]))
+            .unwrap();
+        let answers = solution.run(&"noop\n".repeat(16)).unwrap();
+        assert_eq!(answers.part2, "###.....\n###.....\n");
+    }
+
+    #[test_case("columns=65" ; "too wide")]
+    #[test_case("columns=0" ; "no columns")]
+    #[test_case("rows=0" ; "no rows")]
+    #[test_case("rows=1025" ; "too tall")]
+    fn rejects_impossible_screens(size: &str) {
+        assert!(Day10.configure(&params::overrides(&[size])).is_err());
+    }
+
+    #[test_case(0, 40 ; "no rows")]
+    #[test_case(6, 0 ; "no columns")]
+    #[test_case(6, 65 ; "too wide")]
+    #[test_case(usize::MAX, 40 ; "too tall")]
+    fn computers_need_a_drawable_screen(rows: usize, columns: usize) {
+        assert_eq!(
+            Computer::new(rows, columns).err(),
+            Some(Day10Error::ScreenSize { rows, columns })
+        );
+    }
+
+    /// Lists the value of `X` during every cycle, then samples and draws from the list.
+    fn reference(input: &str) -> (i64, String) {
+        let mut x = 1;
+        let mut during = Vec::new();
+        for line in input.lines() {
+            during.push(x);
+            if let Some(n) = line.strip_prefix("addx ") {
+                during.push(x);
+                x += n.parse::<i64>().unwrap();
+            }
+        }
+        // The signal is also sampled in the cycle after the program ends.
+        during.push(x);
+
+        let signal = (20..=during.len())
+            .step_by(40)
+            .map(|cycle| cycle as i64 * during[cycle - 1])
+            .sum();
+
+        // Programs longer than the screen wrap round to the top, leaving lit pixels lit.
+        let mut lit = [false; 240];
+        for (cycle, &x) in during[..during.len() - 1].iter().enumerate() {
+            if (x - 1..=x + 1).contains(&((cycle % 40) as i64)) {
+                lit[cycle % 240] = true;
+            }
+        }
+        let mut screen = String::new();
+        for row in lit.chunks(40) {
+            screen.extend(row.iter().map(|&on| if on { '#' } else { '.' }));
+            screen.push('\n');
+        }
+        (signal, screen)
+    }
+
+    proptest! {
+        #[test]
+        fn agrees_with_reference(input in differential::inputs(30)) {
+            differential::check::<Day10>(input, reference)?;
+        }
+    }
+}
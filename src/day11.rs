@@ -0,0 +1,711 @@
+//! Day 11: Monkey in the Middle
+
+use std::cell::{Cell, RefCell};
+
+use color_eyre::Result;
+// use itertools::Itertools;
+use nom::{
+    branch::alt,
+    character::complete::{digit1, line_ending},
+    combinator::{map, map_res, value},
+    error::context,
+    multi::separated_list0,
+    sequence::{delimited, preceded, tuple},
+};
+use num::Integer;
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, debug_span, trace, trace_span};
+
+use crate::{
+    params::Params,
+    parsing::{self, lines, number, tag, IResult},
+    Error, PuzzleError, Solution,
+};
+
+const NO_CALM: i64 = 1;
+
+pub struct Day11;
+
+/// Why the monkeys can't play keep away.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum Day11Error {
+    #[error("monkey {monkey} throws to monkey {target}, but there are only {monkeys} monkeys")]
+    NoSuchMonkey {
+        monkey: usize,
+        target: usize,
+        monkeys: usize,
+    },
+    #[error("the monkeys' divisors are too large to keep worry levels bounded by")]
+    ModulusTooLarge,
+    #[error("a worry level grows too large while monkey {monkey} inspects it")]
+    WorryOverflow { monkey: usize },
+    #[error("there is too much monkey business to count")]
+    TooMuchMonkeyBusiness,
+}
+
+impl PuzzleError for Day11Error {}
+
+/// How long the monkeys play for, and how much relief calms worry in the first part.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Day11Params {
+    pub part1_rounds: usize,
+    pub part2_rounds: usize,
+    /// What worry levels are divided by after each inspection in the first part.
+    pub calming_factor: i64,
+}
+
+impl Default for Day11Params {
+    fn default() -> Self {
+        Self {
+            part1_rounds: 20,
+            part2_rounds: 10_000,
+            calming_factor: 3,
+        }
+    }
+}
+
+impl Params for Day11Params {
+    fn validate(&self) -> Result<(), String> {
+        if self.calming_factor < 1 {
+            return Err(format!(
+                "the calming factor must be at least 1, not {}",
+                self.calming_factor
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Solution for Day11 {
+    const DAY: u8 = 11;
+
+    type Parsed<'i> = Monkeys;
+    type Part1 = usize;
+    type Part2 = usize;
+    type Error = Day11Error;
+    type Params = Day11Params;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, Error<Day11Error>> {
+        Monkeys::parse_complete(input)
+    }
+
+    fn part1(monkeys: &Self::Parsed<'_>) -> Result<Self::Part1, Error<Day11Error>> {
+        Self::part1_with(monkeys, &Day11Params::default())
+    }
+
+    fn part2(monkeys: &Self::Parsed<'_>) -> Result<Self::Part2, Error<Day11Error>> {
+        Self::part2_with(monkeys, &Day11Params::default())
+    }
+
+    fn part1_with(
+        monkeys: &Self::Parsed<'_>,
+        params: &Day11Params,
+    ) -> Result<Self::Part1, Error<Day11Error>> {
+        Ok(simulate(
+            monkeys,
+            params.calming_factor,
+            params.part1_rounds,
+        )?)
+    }
+
+    fn part2_with(
+        monkeys: &Self::Parsed<'_>,
+        params: &Day11Params,
+    ) -> Result<Self::Part2, Error<Day11Error>> {
+        Ok(simulate(monkeys, NO_CALM, params.part2_rounds)?)
+    }
+
+    fn generate<R: Rng>(rng: &mut R, size: usize) -> String {
+        Self::generate_with(rng, size, &Day11Params::default())
+    }
+
+    /// Generates `size` items, spread between three to nine monkeys.
+    fn generate_with<R: Rng>(rng: &mut R, size: usize, params: &Day11Params) -> String {
+        // Worry levels can outgrow an `i64` while they are only divided by the calming factor, so
+        // retry until they don't, eventually falling back to monkeys which only add to them.
+        let mut attempts = 0;
+        let monkeys = loop {
+            attempts += 1;
+            let monkeys = GeneratedMonkey::generate_all(rng, size, attempts <= 20);
+            if GeneratedMonkey::stay_in_range(&monkeys, params) {
+                break monkeys;
+            }
+        };
+        monkeys
+            .iter()
+            .enumerate()
+            .map(|(idx, monkey)| monkey.describe(idx))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// A monkey for a generated input.
+struct GeneratedMonkey {
+    items: Vec<i64>,
+    worry_op: WorryOp,
+    test: Test,
+}
+
+impl GeneratedMonkey {
+    fn generate_all<R: Rng>(rng: &mut R, items: usize, multiply: bool) -> Vec<Self> {
+        let count = rng.gen_range(3..=9);
+        let mut divisors = [2, 3, 5, 7, 11, 13, 17, 19, 23];
+        divisors.shuffle(rng);
+        let mut monkeys = (0..count)
+            .map(|idx| {
+                let worry_op = match rng.gen_range(0..10) {
+                    _ if !multiply => WorryOp::Additive(rng.gen_range(1..=8)),
+                    0 => WorryOp::Squared,
+                    1..=4 => WorryOp::Multiplicative(rng.gen_range(2..=19)),
+                    _ => WorryOp::Additive(rng.gen_range(1..=8)),
+                };
+                let mut others = (0..count).filter(|&other| other != idx).collect::<Vec<_>>();
+                others.shuffle(rng);
+                Self {
+                    items: Vec::new(),
+                    worry_op,
+                    test: Test {
+                        divisor: divisors[idx],
+                        true_monkey: others[0],
+                        false_monkey: others[1],
+                    },
+                }
+            })
+            .collect::<Vec<_>>();
+        for _ in 0..items {
+            let idx = rng.gen_range(0..count);
+            monkeys[idx].items.push(rng.gen_range(50..=99));
+        }
+        monkeys
+    }
+
+    /// Whether every worry level fits in an `i64` over the rounds of the first part.
+    fn stay_in_range(monkeys: &[Self], params: &Day11Params) -> bool {
+        let mut items = monkeys
+            .iter()
+            .map(|monkey| {
+                monkey
+                    .items
+                    .iter()
+                    .map(|&worry| i128::from(worry))
+                    .collect()
+            })
+            .collect::<Vec<Vec<_>>>();
+        for _ in 0..params.part1_rounds {
+            for (idx, monkey) in monkeys.iter().enumerate() {
+                for worry in std::mem::take(&mut items[idx]) {
+                    let worry = match monkey.worry_op {
+                        WorryOp::Additive(n) => worry + i128::from(n),
+                        WorryOp::Multiplicative(n) => worry * i128::from(n),
+                        WorryOp::Squared => worry * worry,
+                    };
+                    if worry > i128::from(i64::MAX) {
+                        return false;
+                    }
+                    let worry = worry / i128::from(params.calming_factor);
+                    let target = if worry % i128::from(monkey.test.divisor) == 0 {
+                        monkey.test.true_monkey
+                    } else {
+                        monkey.test.false_monkey
+                    };
+                    items[target].push(worry);
+                }
+            }
+        }
+        true
+    }
+
+    /// The monkey's notes, as in the puzzle input.
+    fn describe(&self, idx: usize) -> String {
+        let items = self.items.iter().map(i64::to_string).collect::<Vec<_>>();
+        let operation = match self.worry_op {
+            WorryOp::Additive(n) => format!("+ {n}"),
+            WorryOp::Multiplicative(n) => format!("* {n}"),
+            WorryOp::Squared => "* old".to_owned(),
+        };
+        format!(
+            "Monkey {idx}:\n  \
+             Starting items: {}\n  \
+             Operation: new = old {operation}\n  \
+             Test: divisible by {}\n    \
+             If true: throw to monkey {}\n    \
+             If false: throw to monkey {}\n",
+            items.join(", "),
+            self.test.divisor,
+            self.test.true_monkey,
+            self.test.false_monkey,
+        )
+    }
+}
+
+/// Plays the given number of rounds on a copy of the monkeys, returning the level of monkey
+/// business.
+#[inline(never)]
+pub fn simulate(
+    monkeys: &Monkeys,
+    calming_factor: i64,
+    rounds: usize,
+) -> Result<usize, Day11Error> {
+    let _simulation = debug_span!("simulate", calming_factor, rounds).entered();
+    let mut monkeys = monkeys.clone();
+    monkeys.set_calming_factor(calming_factor)?;
+
+    for round in 1..=rounds {
+        trace_span!("round", number = round).in_scope(|| monkeys.execute_round())?;
+    }
+
+    monkeys.monkey_business()
+}
+
+/// The monkeys playing keep away, and how worry levels are managed between inspections.
+#[derive(Clone, Debug)]
+pub struct Monkeys {
+    monkeys: Vec<Monkey>,
+    calming: Calming,
+}
+
+impl Monkeys {
+    #[inline(never)]
+    pub fn parse_complete(input: &str) -> Result<Self, Error<Day11Error>> {
+        let monkeys = parsing::parse_all(input, Self::parse)?;
+        let count = monkeys.monkeys.len();
+        for (idx, monkey) in monkeys.monkeys.iter().enumerate() {
+            for target in [monkey.test.true_monkey, monkey.test.false_monkey] {
+                if target >= count {
+                    return Err(Day11Error::NoSuchMonkey {
+                        monkey: idx,
+                        target,
+                        monkeys: count,
+                    }
+                    .into());
+                }
+            }
+        }
+        Ok(monkeys)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Calming {
+    Division(i64),
+    Modular {
+        modulus: i64,
+        // multiplicative_mod_inverse: i64,
+    },
+}
+
+impl Default for Calming {
+    fn default() -> Self {
+        Calming::Division(1)
+    }
+}
+
+impl Calming {
+    fn new(calming_factor: i64, modulus: i64) -> Self {
+        if calming_factor != 1 {
+            Self::Division(calming_factor)
+        } else {
+            Self::Modular {
+                modulus,
+                // multiplicative_mod_inverse,
+            }
+        }
+    }
+
+    fn calm(&self, worry: &mut i64) {
+        match self {
+            Self::Division(d) => *worry /= d,
+            Self::Modular {
+                modulus,
+                // multiplicative_mod_inverse,
+            } => {
+                *worry %= modulus;
+                // *worry *= multiplicative_mod_inverse;
+                // *worry %= modulus;
+            }
+        }
+    }
+}
+
+impl Monkeys {
+    fn parse(s: &str) -> IResult<'_, Self> {
+        map(lines(Monkey::parse), |monkeys| Monkeys {
+            monkeys,
+            calming: Calming::default(),
+        })(s)
+    }
+
+    pub fn monkeys(&self) -> &[Monkey] {
+        &self.monkeys
+    }
+
+    /// Sets how much worry levels are divided by after each inspection. A factor of `1` instead
+    /// keeps worry levels bounded by the product of the monkeys' divisors.
+    pub fn set_calming_factor(&mut self, calming_factor: i64) -> Result<(), Day11Error> {
+        self.calming = Calming::new(calming_factor, self.worry_modulus()?);
+        Ok(())
+    }
+
+    fn worry_modulus(&self) -> Result<i64, Day11Error> {
+        self.monkeys
+            .iter()
+            .map(|m| m.test.divisor)
+            .try_fold(1i64, |acc, d| (acc / acc.gcd(&d)).checked_mul(d))
+            .ok_or(Day11Error::ModulusTooLarge)
+    }
+
+    /// Lets each monkey take a turn, in order.
+    pub fn execute_round(&self) -> Result<(), Day11Error> {
+        for (idx, monkey) in self.monkeys.iter().enumerate() {
+            for thrown in monkey.take_turn(self.calming) {
+                let (item, target) = thrown.ok_or(Day11Error::WorryOverflow { monkey: idx })?;
+                trace!(monkey = idx, worry = item.worry, target, "threw item");
+                self.monkeys[target].catch(item);
+            }
+        }
+        if tracing::enabled!(tracing::Level::TRACE) {
+            for (idx, monkey) in self.monkeys.iter().enumerate() {
+                trace!(monkey = idx, items = ?monkey.items(), "holding items");
+            }
+        }
+        Ok(())
+    }
+
+    /// The product of the inspection counts of the two most active monkeys.
+    pub fn monkey_business(&self) -> Result<usize, Day11Error> {
+        for (idx, monkey) in self.monkeys.iter().enumerate() {
+            debug!(
+                monkey = idx,
+                inspected = monkey.inspected(),
+                "inspection count"
+            );
+        }
+        let val = self.monkeys.iter().fold((0, 0), |acc, m| {
+            let inspected = m.inspected.get();
+            if inspected > acc.0 {
+                (inspected, acc.0)
+            } else if inspected > acc.1 {
+                (acc.0, inspected)
+            } else {
+                acc
+            }
+        });
+        val.0
+            .checked_mul(val.1)
+            .ok_or(Day11Error::TooMuchMonkeyBusiness)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Monkey {
+    items: RefCell<Vec<Item>>,
+    worry_op: WorryOp,
+    test: Test,
+    inspected: Cell<usize>,
+}
+
+impl Monkey {
+    fn parse(s: &str) -> IResult<'_, Self> {
+        context(
+            "monkey",
+            preceded(
+                delimited(tag("Monkey "), digit1, tuple((tag(":"), line_ending))),
+                map(
+                    tuple((
+                        context(
+                            "starting items",
+                            delimited(
+                                tag("  Starting items: "),
+                                separated_list0(tag(", "), Item::parse),
+                                line_ending,
+                            ),
+                        ),
+                        WorryOp::parse,
+                        Test::parse,
+                    )),
+                    |(items, worry_op, test)| Monkey {
+                        items: RefCell::new(items),
+                        worry_op,
+                        test,
+                        inspected: Cell::new(0),
+                    },
+                ),
+            ),
+        )(s)
+    }
+
+    /// Inspects and throws every item, yielding each item with its target, or `None` if its worry
+    /// level overflows.
+    fn take_turn(&self, calming: Calming) -> impl Iterator<Item = Option<(Item, usize)>> {
+        let items = std::mem::take(&mut *self.items.borrow_mut());
+        self.inspected.replace(self.inspected.get() + items.len());
+        let worry_op = self.worry_op;
+        let test = self.test;
+        items.into_iter().map(move |mut item| {
+            item.worry = match worry_op {
+                WorryOp::Additive(n) => item.worry.checked_add(n),
+                WorryOp::Multiplicative(n) => item.worry.checked_mul(n),
+                WorryOp::Squared => item.worry.checked_mul(item.worry),
+            }?;
+            calming.calm(&mut item.worry);
+            let target = test.apply(&item);
+            Some((item, target))
+        })
+    }
+
+    fn catch(&self, item: Item) {
+        self.items.borrow_mut().push(item);
+    }
+
+    /// The worry levels of the items the monkey is holding.
+    pub fn items(&self) -> Vec<i64> {
+        self.items.borrow().iter().map(|i| i.worry).collect()
+    }
+
+    /// The number of items the monkey has inspected.
+    pub fn inspected(&self) -> usize {
+        self.inspected.get()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Item {
+    worry: i64,
+}
+
+impl Item {
+    fn parse(s: &str) -> IResult<'_, Self> {
+        map(number, |worry| Item { worry })(s)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum WorryOp {
+    Additive(i64),
+    Multiplicative(i64),
+    Squared,
+}
+
+impl WorryOp {
+    fn parse(s: &str) -> IResult<'_, Self> {
+        context(
+            "operation",
+            delimited(
+                tag("  Operation: new = old "),
+                alt((
+                    value(WorryOp::Squared, tag("* old")),
+                    map(preceded(tag("+ "), number), WorryOp::Additive),
+                    map(preceded(tag("* "), number), WorryOp::Multiplicative),
+                )),
+                line_ending,
+            ),
+        )(s)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Test {
+    divisor: i64,
+    true_monkey: usize,
+    false_monkey: usize,
+}
+
+impl Test {
+    fn parse(s: &str) -> IResult<'_, Self> {
+        context(
+            "test",
+            map(
+                tuple((
+                    delimited(
+                        tag("  Test: divisible by "),
+                        map_res(number, |divisor| {
+                            if divisor > 0 {
+                                Ok(divisor)
+                            } else {
+                                Err("monkeys can only test divisibility by positive numbers")
+                            }
+                        }),
+                        line_ending,
+                    ),
+                    delimited(
+                        tag("    If true: throw to monkey "),
+                        number::<u32>,
+                        line_ending,
+                    ),
+                    delimited(
+                        tag("    If false: throw to monkey "),
+                        number::<u32>,
+                        line_ending,
+                    ),
+                )),
+                |(divisor, true_monkey, false_monkey)| Test {
+                    divisor,
+                    true_monkey: true_monkey as usize,
+                    false_monkey: false_monkey as usize,
+                },
+            ),
+        )(s)
+    }
+
+    fn apply(&self, item: &Item) -> usize {
+        if item.worry % self.divisor == 0 {
+            self.true_monkey
+        } else {
+            self.false_monkey
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::proptest;
+    use test_case::test_case;
+
+    use super::*;
+    use crate::{differential, params, Runner};
+
+    #[test_case(include_str!("../input/day11test") => matches Ok((10605, 2713310158)))]
+    fn default_tests(input: &str) -> Result<(usize, usize), Error<Day11Error>> {
+        crate::solve::<Day11>(input)
+    }
+
+    /// With no relief in the first part either, both parts match the puzzle's second part after
+    /// 20 rounds.
+    #[test]
+    fn overridden_rounds() {
+        let solution = Day11
+            .configure(&params::overrides(&["calming_factor=1", "part2_rounds=20"]))
+            .unwrap();
+        let answers = solution.run(include_str!("../input/day11test")).unwrap();
+        assert_eq!(
+            (answers.part1.as_str(), answers.part2.as_str()),
+            ("10197", "10197")
+        );
+        assert!(Day11
+            .configure(&params::overrides(&["calming_factor=0"]))
+            .is_err());
+    }
+
+    /// The notes for a single monkey.
+    fn monkey(items: &str, operation: &str, divisor: i64, targets: (usize, usize)) -> String {
+        format!(
+            "Monkey 0:\n  \
+             Starting items: {items}\n  \
+             Operation: new = old {operation}\n  \
+             Test: divisible by {divisor}\n    \
+             If true: throw to monkey {}\n    \
+             If false: throw to monkey {}\n",
+            targets.0, targets.1
+        )
+    }
+
+    #[test_case(&monkey("1", "+ 1", 2, (0, 1)) => Err(Error::Puzzle(Day11Error::NoSuchMonkey { monkey: 0, target: 1, monkeys: 1 })) ; "no such monkey")]
+    #[test_case(&monkey("9223372036854775807", "+ 1", 2, (0, 0)) => Err(Error::Puzzle(Day11Error::WorryOverflow { monkey: 0 })) ; "worry overflow")]
+    #[test_case(&[monkey("1", "+ 1", 4_294_967_311, (0, 1)), monkey("1", "+ 1", 4_294_967_357, (0, 1)), monkey("1", "+ 1", 4_294_967_371, (0, 1))].join("\n") => Err(Error::Puzzle(Day11Error::ModulusTooLarge)) ; "modulus overflow")]
+    #[test_case(&monkey("1", "+ 1", 0, (0, 0)) => matches Err(Error::Parse(_)) ; "zero divisor")]
+    #[test_case(&monkey("1", "+ 1", -3, (0, 0)) => matches Err(Error::Parse(_)) ; "negative divisor")]
+    fn malformed_input(input: &str) -> Result<(usize, usize), Error<Day11Error>> {
+        crate::solve::<Day11>(input)
+    }
+
+    /// Plays the rounds with plain arithmetic, tracking worry levels in the second part by their
+    /// remainder for each monkey's divisor rather than modulo their product.
+    fn reference(input: &str) -> (usize, usize) {
+        struct Notes {
+            items: Vec<i64>,
+            operation: (char, Option<i64>),
+            divisor: i64,
+            targets: (usize, usize),
+        }
+
+        let last_number = |line: &str| line.rsplit(' ').next().unwrap().parse::<i64>().unwrap();
+        let monkeys = input
+            .split("\n\n")
+            .map(|notes| {
+                let lines = notes.lines().collect::<Vec<_>>();
+                let (_, items) = lines[1].split_once(": ").unwrap();
+                let (_, operation) = lines[2].split_once("old ").unwrap();
+                let (operator, operand) = operation.split_once(' ').unwrap();
+                Notes {
+                    items: items
+                        .split(", ")
+                        .filter(|item| !item.is_empty())
+                        .map(|item| item.parse().unwrap())
+                        .collect(),
+                    operation: (operator.chars().next().unwrap(), operand.parse().ok()),
+                    divisor: last_number(lines[3]),
+                    targets: (
+                        last_number(lines[4]) as usize,
+                        last_number(lines[5]) as usize,
+                    ),
+                }
+            })
+            .collect::<Vec<_>>();
+        let inspect = |monkey: &Notes, old: i64| match monkey.operation {
+            ('+', operand) => old + operand.unwrap_or(old),
+            (_, operand) => old * operand.unwrap_or(old),
+        };
+        let target = |monkey: &Notes, divisible: bool| {
+            if divisible {
+                monkey.targets.0
+            } else {
+                monkey.targets.1
+            }
+        };
+        let business = |mut inspected: Vec<usize>| {
+            inspected.sort_unstable_by(|a, b| b.cmp(a));
+            inspected[0] * inspected[1]
+        };
+
+        let mut items = monkeys.iter().map(|m| m.items.clone()).collect::<Vec<_>>();
+        let mut inspected = vec![0; monkeys.len()];
+        for _ in 0..20 {
+            for (idx, monkey) in monkeys.iter().enumerate() {
+                for worry in std::mem::take(&mut items[idx]) {
+                    inspected[idx] += 1;
+                    let worry = inspect(monkey, worry) / 3;
+                    items[target(monkey, worry % monkey.divisor == 0)].push(worry);
+                }
+            }
+        }
+        let part1 = business(inspected);
+
+        let divisors = monkeys.iter().map(|m| m.divisor).collect::<Vec<_>>();
+        let mut items = monkeys
+            .iter()
+            .map(|m| {
+                m.items
+                    .iter()
+                    .map(|&worry| divisors.iter().map(|d| worry % d).collect::<Vec<_>>())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let mut inspected = vec![0; monkeys.len()];
+        for _ in 0..10_000 {
+            for (idx, monkey) in monkeys.iter().enumerate() {
+                for remainders in std::mem::take(&mut items[idx]) {
+                    inspected[idx] += 1;
+                    let remainders = remainders
+                        .iter()
+                        .zip(&divisors)
+                        .map(|(&r, d)| inspect(monkey, r) % d)
+                        .collect::<Vec<_>>();
+                    items[target(monkey, remainders[idx] == 0)].push(remainders);
+                }
+            }
+        }
+        (part1, business(inspected))
+    }
+
+    proptest! {
+        #![proptest_config(proptest::prelude::ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn agrees_with_reference(input in differential::inputs(30)) {
+            differential::check::<Day11>(input, reference)?;
+        }
+    }
+}
@@ -0,0 +1,346 @@
+//! Day 12: Hill Climbing Algorithm
+
+use std::fmt;
+
+use color_eyre::Result;
+use fxhash::FxHashSet;
+use itertools::Itertools;
+use palette::{FromColor, Hsv, Srgb};
+use petgraph::prelude::*;
+use rand::Rng;
+
+use crate::{
+    geometry::Point,
+    grid::Grid,
+    params::NoParams,
+    render::{self, Colour, Render, Scene},
+    Error, PuzzleError, Solution,
+};
+
+pub struct Day12;
+
+/// Why a height map has no route.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum Day12Error {
+    #[error("the map has no start square")]
+    NoStart,
+    #[error("the map has no summit square")]
+    NoSummit,
+    #[error("no path from the start to the summit")]
+    NoClimb,
+    #[error("no path from the summit to the lowest ground")]
+    NoDescent,
+}
+
+impl PuzzleError for Day12Error {}
+
+impl Solution for Day12 {
+    const DAY: u8 = 12;
+
+    type Parsed<'i> = HeightMap;
+    type Part1 = usize;
+    type Part2 = usize;
+    type Error = Day12Error;
+    type Params = NoParams;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, Error<Day12Error>> {
+        HeightMap::parse(input)
+    }
+
+    fn part1(map: &Self::Parsed<'_>) -> Result<Self::Part1, Error<Day12Error>> {
+        let (steps_from_origin, _) = map.climb()?;
+        Ok(steps_from_origin)
+    }
+
+    fn part2(map: &Self::Parsed<'_>) -> Result<Self::Part2, Error<Day12Error>> {
+        let (steps_to_flat, _) = map.descend()?;
+        Ok(steps_to_flat)
+    }
+
+    fn visualise(map: &Self::Parsed<'_>) -> Result<Option<Scene>, Error<Day12Error>> {
+        Ok(Some(map.routes()?.scene()))
+    }
+
+    /// Generates a map `size` squares wide, with a gentle climb from the start to the summit.
+    fn generate<R: Rng>(rng: &mut R, size: usize) -> String {
+        // At least 26 steps are needed to climb from `a` to `z` one letter at a time.
+        let width = size.max(27);
+        let height = (width / 3).max(3);
+        let path_row = rng.gen_range(0..height);
+        let mut out = String::with_capacity((width + 1) * height);
+        for y in 0..height {
+            for x in 0..width {
+                let base = (x * 25 / (width - 1)) as u8;
+                out.push(match (x, y == path_row) {
+                    (0, true) => 'S',
+                    (x, true) if x == width - 1 => 'E',
+                    (_, false) if rng.gen_bool(0.3) => char::from(b'a' + rng.gen_range(0..=base)),
+                    _ => char::from(b'a' + base),
+                });
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// The hill's height map, and the graph of the steps that can be climbed between squares.
+#[derive(Clone, Debug)]
+pub struct HeightMap {
+    heights: Grid<u8>,
+    grid: DiGraph<Node, usize>,
+    start: NodeIndex,
+    end: NodeIndex,
+    end_pos: Position,
+}
+
+impl HeightMap {
+    /// Builds the map from rows of lowercase heights, with the start marked `S` and the summit
+    /// marked `E`.
+    pub fn parse(input: &str) -> Result<Self, Error<Day12Error>> {
+        let squares = Grid::parse(input, |c| match c {
+            'a'..='z' | 'S' | 'E' => Some(c as u8),
+            _ => None,
+        })?;
+        let start_pos = squares
+            .position(|&c| c == b'S')
+            .ok_or(Day12Error::NoStart)?;
+        let end_pos = squares
+            .position(|&c| c == b'E')
+            .ok_or(Day12Error::NoSummit)?;
+        let heights = squares.map(|&c| match c {
+            b'S' => b'a',
+            b'E' => b'z',
+            c => c,
+        });
+
+        let mut grid = DiGraph::with_capacity(heights.width() * heights.height(), 0);
+        for (position, &height) in heights.iter() {
+            grid.add_node(Node { position, height });
+        }
+
+        let node_idx = |p: Position| NodeIndex::new(p.x + p.y * heights.width());
+        for (from, &height) in heights.iter() {
+            for to in heights.neighbours4(from) {
+                if heights[to] <= height + 1 {
+                    grid.add_edge(node_idx(from), node_idx(to), 1);
+                }
+            }
+        }
+
+        Ok(Self {
+            start: node_idx(start_pos),
+            end: node_idx(end_pos),
+            end_pos,
+            heights,
+            grid,
+        })
+    }
+
+    /// The height of every square, from `b'a'` to `b'z'`.
+    pub fn heights(&self) -> &Grid<u8> {
+        &self.heights
+    }
+
+    pub fn graph(&self) -> &DiGraph<Node, usize> {
+        &self.grid
+    }
+
+    pub fn start(&self) -> NodeIndex {
+        self.start
+    }
+
+    pub fn end(&self) -> NodeIndex {
+        self.end
+    }
+
+    /// The fewest steps from the start to the summit, and the path taken.
+    pub fn climb(&self) -> Result<(usize, Vec<NodeIndex>), Day12Error> {
+        let grid = &self.grid;
+        let end = self.end;
+        let end_pos = self.end_pos;
+        let is_goal = |node_idx| node_idx == end;
+        let edge_cost = |_| 1;
+        let estimate_cost = |node_idx: NodeIndex| {
+            let cur = grid[node_idx];
+            let cur_pos = cur.position;
+            (end_pos.x.abs_diff(cur_pos.x))
+                .min(end_pos.y.abs_diff(cur_pos.y))
+                .max((b'z' - cur.height) as usize)
+        };
+
+        petgraph::algo::astar(grid, self.start, is_goal, edge_cost, estimate_cost)
+            .ok_or(Day12Error::NoClimb)
+    }
+
+    /// The fewest steps from any lowest square to the summit, and the path taken, walking down from
+    /// the summit.
+    pub fn descend(&self) -> Result<(usize, Vec<NodeIndex>), Day12Error> {
+        let mut new_grid = self.grid.clone();
+        new_grid.reverse();
+        let is_goal = |node_idx: NodeIndex| new_grid[node_idx].height == b'a';
+        let edge_cost = |_| 1;
+        let estimate_cost = |node_idx: NodeIndex| {
+            let cur = new_grid[node_idx];
+            (cur.height - b'a') as usize
+        };
+
+        petgraph::algo::astar(&new_grid, self.end, is_goal, edge_cost, estimate_cost)
+            .ok_or(Day12Error::NoDescent)
+    }
+
+    /// The fastest routes up and down the hill.
+    pub fn routes(&self) -> Result<Routes<'_>, Day12Error> {
+        let (_, climb) = self.climb()?;
+        let (_, descent) = self.descend()?;
+        Ok(Routes {
+            map: self,
+            climb,
+            descent,
+        })
+    }
+}
+
+/// The fastest routes up and down the hill, as squares of the map.
+#[derive(Clone, Debug)]
+pub struct Routes<'m> {
+    pub map: &'m HeightMap,
+    /// The route from the start to the summit.
+    pub climb: Vec<NodeIndex>,
+    /// The route from the summit down to the nearest lowest square.
+    pub descent: Vec<NodeIndex>,
+}
+
+impl Render for Routes<'_> {
+    /// The graph of steps, with each square coloured by its height and the routes highlighted.
+    fn scene(&self) -> Scene {
+        let steps = |route: &[NodeIndex]| {
+            route
+                .iter()
+                .copied()
+                .tuple_windows()
+                .collect::<FxHashSet<(_, _)>>()
+        };
+        let (climb, descent) = (steps(&self.climb), steps(&self.descent));
+
+        render::Graph::from_petgraph(
+            &self.map.grid,
+            |idx, node| {
+                let hue = f32::from(node.height - b'a') / 25.0 * 0.75 * 360.0;
+                let colour = Srgb::from_color(Hsv::new(hue, 0.75, 1.0)).into_format();
+                let drawn = render::Node::new(node.height as char)
+                    .colour(colour)
+                    .at(node.position);
+                if self.climb.contains(&idx) {
+                    drawn.fill(Colour::new(0x11, 0x22, 0x11))
+                } else {
+                    drawn
+                }
+            },
+            |edge| {
+                let drawn = render::Edge::between(edge);
+                let step = (edge.source(), edge.target());
+                if climb.contains(&step) {
+                    drawn.colour(Colour::new(0x00, 0x99, 0x00))
+                } else if descent.contains(&step) {
+                    drawn.colour(Colour::new(0xcc, 0x00, 0x99))
+                } else {
+                    drawn
+                }
+            },
+        )
+        .into()
+    }
+}
+
+/// A square of the map and its height, from `b'a'` to `b'z'`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Node {
+    pub position: Position,
+    pub height: u8,
+}
+
+impl fmt::Display for Node {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}\n{}", self.position, self.height as char)
+    }
+}
+
+/// The position of a square, from the top-left of the map.
+pub type Position = Point<usize>;
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, VecDeque};
+
+    use proptest::proptest;
+    use test_case::test_case;
+
+    use super::*;
+    use crate::differential;
+
+    #[test_case(include_str!("../input/day12test") => matches Ok((31, 29)))]
+    fn default_tests(input: &str) -> Result<(usize, usize), Error<Day12Error>> {
+        crate::solve::<Day12>(input)
+    }
+
+    #[test_case("abE\n" => Err(Error::Puzzle(Day12Error::NoStart)) ; "no start")]
+    #[test_case("Sbc\n" => Err(Error::Puzzle(Day12Error::NoSummit)) ; "no summit")]
+    #[test_case("SbdE\n" => Err(Error::Puzzle(Day12Error::NoClimb)) ; "too steep")]
+    #[test_case("Sb?E\n" => matches Err(Error::Parse(_)) ; "bad square")]
+    fn unsolvable_input(input: &str) -> Result<(usize, usize), Error<Day12Error>> {
+        crate::solve::<Day12>(input)
+    }
+
+    /// Breadth-first searches down from the summit, where each step may be at most one lower
+    /// than the last.
+    fn reference(input: &str) -> (usize, usize) {
+        let squares = input.lines().map(str::as_bytes).collect::<Vec<_>>();
+        let height = |(x, y): (usize, usize)| match squares[y][x] {
+            b'S' => b'a',
+            b'E' => b'z',
+            c => c,
+        };
+        let find = |target| {
+            squares
+                .iter()
+                .enumerate()
+                .find_map(|(y, row)| row.iter().position(|&c| c == target).map(|x| (x, y)))
+                .unwrap()
+        };
+        let (start, summit) = (find(b'S'), find(b'E'));
+
+        let mut distances = HashMap::from([(summit, 0)]);
+        let mut queue = VecDeque::from([summit]);
+        while let Some(square) = queue.pop_front() {
+            let (x, y) = square;
+            let neighbours = [
+                (x.wrapping_sub(1), y),
+                (x + 1, y),
+                (x, y.wrapping_sub(1)),
+                (x, y + 1),
+            ];
+            for next in neighbours {
+                let on_map = squares.get(next.1).is_some_and(|row| next.0 < row.len());
+                if on_map && height(next) + 1 >= height(square) && !distances.contains_key(&next) {
+                    distances.insert(next, distances[&square] + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        let lowest = distances
+            .iter()
+            .filter(|&(&square, _)| height(square) == b'a')
+            .map(|(_, &distance)| distance)
+            .min();
+        (distances[&start], lowest.unwrap())
+    }
+
+    proptest! {
+        #[test]
+        fn agrees_with_reference(input in differential::inputs(30)) {
+            differential::check::<Day12>(input, reference)?;
+        }
+    }
+}
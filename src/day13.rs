@@ -0,0 +1,357 @@
+//! Day 13: Distress Signal
+
+use std::{cmp, fmt, str};
+
+use color_eyre::Result;
+use itertools::Itertools;
+use nom::{
+    branch::alt,
+    character::complete::line_ending,
+    combinator::{cut, eof, map, peek, value},
+    error::context,
+    multi::separated_list0,
+    sequence::{preceded, terminated},
+};
+use rand::Rng;
+use tracing::trace;
+
+use crate::{
+    params::NoParams,
+    parsing::{self, lines, number, tag, IResult, ParseError},
+    Error, PuzzleError, Solution,
+};
+
+/// How deeply packets may be nested, so that parsing and comparing them can't overflow the stack.
+const MAX_DEPTH: usize = 100;
+
+pub struct Day13;
+
+/// Why a list of packets has no answer.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum Day13Error {
+    #[error("there are {packets} packets, which can't be split into pairs")]
+    UnpairedPacket { packets: usize },
+}
+
+impl PuzzleError for Day13Error {}
+
+impl Solution for Day13 {
+    const DAY: u8 = 13;
+
+    type Parsed<'i> = Vec<Packet>;
+    type Part1 = usize;
+    type Part2 = usize;
+    type Error = Day13Error;
+    type Params = NoParams;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, Error<Day13Error>> {
+        check_depth(input)?;
+        let packet_or_blank = alt((
+            map(context("packet", Packet::list_token), Some),
+            value(None, peek(alt((line_ending, eof)))),
+        ));
+        let lines = parsing::parse_all(input, lines(packet_or_blank))?;
+        Ok(lines.into_iter().flatten().collect())
+    }
+
+    fn part1(packets: &Self::Parsed<'_>) -> Result<Self::Part1, Error<Day13Error>> {
+        if packets.len() % 2 != 0 {
+            return Err(Day13Error::UnpairedPacket {
+                packets: packets.len(),
+            }
+            .into());
+        }
+        let mut in_order = 0;
+        for (idx, (l, r)) in packets.iter().tuples().enumerate() {
+            let ordered = l <= r;
+            trace!(pair = idx + 1, left = ?l, right = ?r, ordered, "compared packets");
+            if ordered {
+                in_order += idx + 1;
+            }
+        }
+
+        Ok(in_order)
+    }
+
+    fn part2(packets: &Self::Parsed<'_>) -> Result<Self::Part2, Error<Day13Error>> {
+        let divider_1: Packet = Packet::List(vec![Packet::List(vec![Packet::Integer(2)])]);
+        let divider_2: Packet = Packet::List(vec![Packet::List(vec![Packet::Integer(6)])]);
+        // Packets can compare equal to each other or to a divider, so they are sorted rather than
+        // collected into a set. The sort is stable, so each divider stays ahead of equal packets.
+        let mut ordered_packets = vec![divider_1.clone(), divider_2.clone()];
+        ordered_packets.extend(packets.iter().cloned());
+        ordered_packets.sort();
+
+        let index_of = |divider| ordered_packets.iter().position(|p| *p == divider).unwrap() + 1;
+        let index_1 = index_of(divider_1);
+        let index_2 = index_of(divider_2);
+
+        Ok(index_1 * index_2)
+    }
+
+    /// Generates `size` pairs of packets.
+    fn generate<R: Rng>(rng: &mut R, size: usize) -> String {
+        (0..size)
+            .map(|_| format!("{}\n{}\n", generate_list(rng, 4), generate_list(rng, 4)))
+            .join("\n")
+    }
+}
+
+/// Checks that no packet is nested more than [`MAX_DEPTH`] lists deep.
+fn check_depth(input: &str) -> Result<(), ParseError> {
+    let mut depth = 0usize;
+    for (idx, c) in input.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => depth = depth.saturating_sub(1),
+            '\n' => depth = 0,
+            _ => {}
+        }
+        if depth > MAX_DEPTH {
+            return Err(ParseError::new(
+                input,
+                &input[idx..],
+                format!("packets can be nested at most {MAX_DEPTH} lists deep"),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// A random list packet, nested at most `depth` lists deep.
+fn generate_list<R: Rng>(rng: &mut R, depth: usize) -> String {
+    let items = (0..rng.gen_range(0..=5))
+        .map(|_| {
+            if depth > 1 && rng.gen_bool(0.3) {
+                generate_list(rng, depth - 1)
+            } else {
+                rng.gen_range(0..=10).to_string()
+            }
+        })
+        .join(",");
+    format!("[{items}]")
+}
+
+/// A packet of distress signal data: an integer or a list of packets.
+#[derive(Clone)]
+pub enum Packet {
+    Integer(u64),
+    List(Vec<Packet>),
+}
+
+impl fmt::Debug for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Integer(i) => write!(f, "{}", i),
+            Self::List(l) => write!(f, "{:?}", l),
+        }
+    }
+}
+
+impl Packet {
+    fn token(s: &str) -> IResult<'_, Self> {
+        alt((map(number, Self::Integer), Self::list_token))(s)
+    }
+
+    fn list_token(s: &str) -> IResult<'_, Self> {
+        // Once a list is opened, report problems within it rather than backtracking out.
+        preceded(
+            tag("["),
+            cut(terminated(
+                map(separated_list0(tag(","), Self::token), Self::List),
+                tag("]"),
+            )),
+        )(s)
+    }
+}
+
+impl str::FromStr for Packet {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        check_depth(s)?;
+        parsing::parse_all(s, Packet::list_token)
+    }
+}
+
+impl cmp::Ord for Packet {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        match (self, other) {
+            (Self::Integer(l), Self::Integer(r)) => l.cmp(r),
+            (Self::List(l), Self::List(r)) => {
+                for (l, r) in l.iter().zip(r) {
+                    match l.cmp(r) {
+                        cmp::Ordering::Equal => {}
+                        result => return result,
+                    }
+                }
+                l.len().cmp(&r.len())
+            }
+            (Self::Integer(l), Self::List(_)) => Packet::List(vec![Packet::Integer(*l)]).cmp(other),
+            (Self::List(_), Self::Integer(r)) => self.cmp(&Packet::List(vec![Packet::Integer(*r)])),
+        }
+    }
+}
+
+impl cmp::PartialOrd for Packet {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl cmp::PartialEq for Packet {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == cmp::Ordering::Equal
+    }
+}
+
+impl cmp::Eq for Packet {}
+
+#[cfg(test)]
+mod tests {
+    use proptest::proptest;
+    use test_case::test_case;
+
+    use super::*;
+    use crate::differential;
+
+    #[test_case(include_str!("../input/day13test") => matches Ok((13, 140)))]
+    fn default_tests(input: &str) -> Result<(usize, usize), Error<Day13Error>> {
+        crate::solve::<Day13>(input)
+    }
+
+    #[test]
+    fn unpaired_packets() {
+        assert_eq!(
+            crate::solve::<Day13>("[1]\n[2]\n\n[3]\n"),
+            Err(Error::Puzzle(Day13Error::UnpairedPacket { packets: 3 }))
+        );
+    }
+
+    #[test]
+    fn deeply_nested_packets_are_malformed() {
+        let packet = format!("{}{}", "[".repeat(100_000), "]".repeat(100_000));
+        let Err(Error::Parse(e)) = Day13::parse(&format!("{packet}\n[]\n")) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((e.line(), e.column()), (1, MAX_DEPTH + 1));
+    }
+
+    #[test_case(
+        "[1,1,3,1,1]".parse().unwrap(),
+        "[1,1,5,1,1]".parse().unwrap()
+        => cmp::Ordering::Less
+    )]
+    #[test_case(
+        "[[1],[2,3,4]]".parse().unwrap(),
+        "[[1],4]".parse().unwrap()
+        => cmp::Ordering::Less
+    )]
+    #[test_case(
+        "[9]".parse().unwrap(),
+        "[[8,7,6]]".parse().unwrap()
+        => cmp::Ordering::Greater
+    )]
+    #[test_case(
+        "[[4,4],4,4]".parse().unwrap(),
+        "[[4,4],4,4,4]".parse().unwrap()
+        => cmp::Ordering::Less
+    )]
+    #[test_case(
+        "[7,7,7,7]".parse().unwrap(),
+        "[7,7,7]".parse().unwrap()
+        => cmp::Ordering::Greater
+    )]
+    #[test_case(
+        "[]".parse().unwrap(),
+        "[3]".parse().unwrap()
+        => cmp::Ordering::Less
+    )]
+    #[test_case(
+        "[[[]]]".parse().unwrap(),
+        "[[]]".parse().unwrap()
+        => cmp::Ordering::Greater
+    )]
+    #[test_case(
+        "[1,[2,[3,[4,[5,6,7]]]],8,9]".parse().unwrap(),
+        "[1,[2,[3,[4,[5,6,0]]]],8,9]".parse().unwrap()
+        => cmp::Ordering::Greater
+    )]
+    fn check_ordering(left: Packet, right: Packet) -> cmp::Ordering {
+        left.cmp(&right)
+    }
+
+    /// Compares packets character by character as the puzzle describes, and places the dividers
+    /// by counting the packets before them.
+    fn reference(input: &str) -> (usize, usize) {
+        enum Value {
+            Integer(u64),
+            List(Vec<Value>),
+        }
+
+        fn parse(s: &[u8], idx: &mut usize) -> Value {
+            if s[*idx] != b'[' {
+                let len = s[*idx..].iter().take_while(|c| c.is_ascii_digit()).count();
+                let n = std::str::from_utf8(&s[*idx..*idx + len]).unwrap();
+                *idx += len;
+                return Value::Integer(n.parse().unwrap());
+            }
+            *idx += 1;
+            let mut list = Vec::new();
+            while s[*idx] != b']' {
+                if s[*idx] == b',' {
+                    *idx += 1;
+                }
+                list.push(parse(s, idx));
+            }
+            *idx += 1;
+            Value::List(list)
+        }
+
+        fn compare(left: &Value, right: &Value) -> cmp::Ordering {
+            match (left, right) {
+                (Value::Integer(l), Value::Integer(r)) => l.cmp(r),
+                (Value::Integer(l), Value::List(_)) => {
+                    compare(&Value::List(vec![Value::Integer(*l)]), right)
+                }
+                (Value::List(_), Value::Integer(r)) => {
+                    compare(left, &Value::List(vec![Value::Integer(*r)]))
+                }
+                (Value::List(l), Value::List(r)) => l
+                    .iter()
+                    .zip(r)
+                    .map(|(l, r)| compare(l, r))
+                    .find(|&order| order != cmp::Ordering::Equal)
+                    .unwrap_or_else(|| l.len().cmp(&r.len())),
+            }
+        }
+
+        let packets = input
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| parse(line.as_bytes(), &mut 0))
+            .collect::<Vec<_>>();
+        let part1 = packets
+            .chunks(2)
+            .enumerate()
+            .filter(|(_, pair)| compare(&pair[0], &pair[1]) != cmp::Ordering::Greater)
+            .map(|(idx, _)| idx + 1)
+            .sum();
+
+        let before = |divider: u64| {
+            let divider = parse(format!("[[{divider}]]").as_bytes(), &mut 0);
+            packets
+                .iter()
+                .filter(|packet| compare(packet, &divider) == cmp::Ordering::Less)
+                .count()
+        };
+        (part1, (before(2) + 1) * (before(6) + 2))
+    }
+
+    proptest! {
+        #[test]
+        fn agrees_with_reference(input in differential::inputs(30)) {
+            differential::check::<Day13>(input, reference)?;
+        }
+    }
+}
@@ -0,0 +1,596 @@
+//! Day 14: Regolith Reservoir
+
+use std::{fmt, ops::RangeInclusive};
+
+use color_eyre::Result;
+use itertools::Itertools;
+use nom::{
+    combinator::{map, map_res},
+    error::context,
+    multi::separated_list1,
+    sequence::separated_pair,
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    geometry::{Direction8, Point},
+    grid::Grid,
+    params::Params,
+    parsing::{self, lines, number, tag, IResult},
+    render::{Colour, Pixel, Render, Scene},
+    Error, PuzzleError, Solution,
+};
+
+/// The most squares a sand pit may cover, to bound the memory it needs.
+const MAX_CELLS: usize = 1 << 28;
+
+pub struct Day14;
+
+/// Why sand can't be poured into a cave.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum Day14Error {
+    #[error("the cave is too large to simulate, at {width} by {depth} squares")]
+    CaveTooLarge { width: usize, depth: usize },
+    #[error("there is rock at the source of the sand")]
+    RockAtSource,
+    #[error("the source of the sand is below the floor")]
+    SourceBelowFloor,
+    #[error("sand piles up to the source without falling into the abyss")]
+    SourceBlocked,
+}
+
+impl PuzzleError for Day14Error {}
+
+/// Where sand pours into the cave from.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Day14Params {
+    pub source_x: usize,
+    pub source_y: usize,
+}
+
+impl Default for Day14Params {
+    fn default() -> Self {
+        Self {
+            source_x: 500,
+            source_y: 0,
+        }
+    }
+}
+
+impl Day14Params {
+    fn source(&self) -> Point<usize> {
+        Point::new(self.source_x, self.source_y)
+    }
+}
+
+impl Params for Day14Params {}
+
+impl Solution for Day14 {
+    const DAY: u8 = 14;
+
+    type Parsed<'i> = SandPit;
+    type Part1 = usize;
+    type Part2 = usize;
+    type Error = Day14Error;
+    type Params = Day14Params;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, Error<Day14Error>> {
+        Self::parse_with(input, &Day14Params::default())
+    }
+
+    fn parse_with<'i>(
+        input: &'i str,
+        params: &Day14Params,
+    ) -> Result<Self::Parsed<'i>, Error<Day14Error>> {
+        let source = params.source();
+        let lines = parsing::parse_all(input, lines(rock_path))?;
+        let max_y = lines.iter().flatten().map(|&(_, y)| y).max().unwrap_or(0);
+
+        let (min_x, max_x) = lines
+            .iter()
+            .flatten()
+            .map(|&(x, _)| x)
+            .minmax()
+            .into_option()
+            .unwrap_or((source.x, source.x));
+        let mut sand_pit = SandPit::with_bounds(source, min_x, max_x, max_y)?;
+
+        for line in lines {
+            for (prior, next) in line.into_iter().tuple_windows() {
+                let x_range = prior.0.min(next.0)..=prior.0.max(next.0);
+                let y_range = prior.1.min(next.1)..=prior.1.max(next.1);
+                for (x, y) in x_range.cartesian_product(y_range) {
+                    sand_pit.add_rock(x, y);
+                }
+            }
+        }
+        if sand_pit.safe_to_stand() {
+            return Err(Day14Error::RockAtSource.into());
+        }
+
+        Ok(sand_pit)
+    }
+
+    fn part1(sand_pit: &Self::Parsed<'_>) -> Result<Self::Part1, Error<Day14Error>> {
+        Ok(sand_pit.clone().fill()?)
+    }
+
+    fn part2(sand_pit: &Self::Parsed<'_>) -> Result<Self::Part2, Error<Day14Error>> {
+        let mut sand_pit = sand_pit.clone();
+        sand_pit.add_floor();
+        Ok(sand_pit.fill_to_source())
+    }
+
+    fn visualise(sand_pit: &Self::Parsed<'_>) -> Result<Option<Scene>, Error<Day14Error>> {
+        let mut sand_pit = sand_pit.clone();
+        sand_pit.add_floor();
+        sand_pit.fill_to_source();
+        Ok(Some(sand_pit.scene()))
+    }
+
+    fn generate<R: Rng>(rng: &mut R, size: usize) -> String {
+        Self::generate_with(rng, size, &Day14Params::default())
+    }
+
+    /// Generates `size` rock paths, in a cave which gets deeper as more are added.
+    fn generate_with<R: Rng>(rng: &mut R, size: usize, params: &Day14Params) -> String {
+        let (source, depth) = (params.source(), 20 + size / 5);
+        (0..size)
+            .map(|_| {
+                let path = loop {
+                    let path = generate_rock_path(rng, source, depth);
+                    if avoids_source_diagonals(&path, source) {
+                        break path;
+                    }
+                };
+                let path = path.iter().map(|(x, y)| format!("{x},{y}")).join(" -> ");
+                format!("{path}\n")
+            })
+            .collect()
+    }
+}
+
+/// A random rock path no more than `depth` below the source, alternating between horizontal and
+/// vertical segments.
+fn generate_rock_path<R: Rng>(
+    rng: &mut R,
+    source: Point<usize>,
+    depth: usize,
+) -> Vec<(usize, usize)> {
+    let (left, right) = (
+        source.x.saturating_sub(depth / 2).max(1),
+        source.x + depth / 2,
+    );
+    let (top, bottom) = (source.y + 2, source.y + depth);
+    let mut vertex = (rng.gen_range(left..=right), rng.gen_range(top..=bottom));
+    let mut path = vec![vertex];
+    let mut horizontal = rng.gen_bool(0.5);
+    for _ in 1..rng.gen_range(2..=5) {
+        let step = rng.gen_range(1..=10);
+        let forwards = rng.gen_bool(0.5);
+        let shift = |c: usize, min, max| {
+            if forwards {
+                c + step
+            } else {
+                c.saturating_sub(step)
+            }
+            .clamp(min, max)
+        };
+        if horizontal {
+            vertex.0 = shift(vertex.0, left, right);
+        } else {
+            vertex.1 = shift(vertex.1, top, bottom);
+        }
+        path.push(vertex);
+        horizontal = !horizontal;
+    }
+    path
+}
+
+/// Whether the path stays clear of the diagonals running down from the source.
+///
+/// Sand can only pile up to the source if rocks on both diagonals hold up its edges, so without
+/// them it always ends up falling into the abyss. The diagonals split the cave into three convex
+/// regions, so a path avoids them if all of its vertices are in the same region.
+fn avoids_source_diagonals(path: &[(usize, usize)], source: Point<usize>) -> bool {
+    let region = |&(x, y): &(usize, usize)| {
+        let (dx, dy) = (
+            x as isize - source.x as isize,
+            y as isize - source.y as isize,
+        );
+        ((dx - dy).signum(), (dx + dy).signum())
+    };
+    let first = region(&path[0]);
+    first.0 != 0 && first.1 != 0 && path.iter().all(|vertex| region(vertex) == first)
+}
+
+/// A path of rock, as the `(x, y)` vertices of its straight segments.
+fn rock_path(s: &str) -> IResult<'_, Vec<(usize, usize)>> {
+    let coordinate = |s| map(number::<u32>, |c| c as usize)(s);
+    context(
+        "rock path",
+        map_res(
+            separated_list1(
+                tag(" -> "),
+                separated_pair(coordinate, tag(","), coordinate),
+            ),
+            |path: Vec<(usize, usize)>| {
+                if path
+                    .iter()
+                    .tuple_windows()
+                    .all(|(a, b)| a.0 == b.0 || a.1 == b.1)
+                {
+                    Ok(path)
+                } else {
+                    Err("rock paths can only run horizontally or vertically")
+                }
+            },
+        ),
+    )(s)
+}
+
+/// A cave slice of rock and sand, with sand pouring in from a source, normally `(500, 0)`.
+///
+/// The slice is wide enough to hold all of the sand that can pour in once the floor is added.
+#[derive(Debug, Clone)]
+pub struct SandPit {
+    cells: Grid<Cell>,
+    source: Point<usize>,
+    left: usize,
+    lowest_rock: usize,
+    has_floor: bool,
+    min_x: usize,
+    max_x: usize,
+}
+
+impl SandPit {
+    /// Creates an empty pit for rocks no deeper than `max_y`.
+    pub fn with_depth(source: Point<usize>, max_y: usize) -> Result<Self, Day14Error> {
+        Self::with_bounds(source, source.x, source.x, max_y)
+    }
+
+    /// Creates an empty pit for rocks between columns `min_x` and `max_x`, no deeper than `max_y`.
+    ///
+    /// Fails if the pit would cover more than [`MAX_CELLS`] squares, or the source would be below
+    /// the floor.
+    pub fn with_bounds(
+        source: Point<usize>,
+        min_x: usize,
+        max_x: usize,
+        max_y: usize,
+    ) -> Result<Self, Day14Error> {
+        if source.y > max_y.saturating_add(1) {
+            return Err(Day14Error::SourceBelowFloor);
+        }
+        // Sand spreads at most one column per row, so it can't get further than the floor's depth
+        // from the source.
+        let spread = max_y.saturating_add(3);
+        let left = min_x.min(source.x.saturating_sub(spread));
+        let right = max_x.max(source.x.saturating_add(spread));
+        let (width, depth) = ((right - left).saturating_add(1), spread);
+        if width
+            .checked_mul(depth)
+            .is_none_or(|cells| cells > MAX_CELLS)
+        {
+            return Err(Day14Error::CaveTooLarge { width, depth });
+        }
+        Ok(Self {
+            cells: Grid::new(width, depth, Cell::Empty),
+            source,
+            left,
+            lowest_rock: max_y,
+            has_floor: false,
+            min_x: source.x,
+            max_x: source.x,
+        })
+    }
+
+    pub fn add_rock(&mut self, x: usize, y: usize) {
+        self.cells[Point::new(x - self.left, y)] = Cell::Rock;
+        self.min_x = self.min_x.min(x);
+        self.max_x = self.max_x.max(x);
+    }
+
+    /// Adds an infinite floor two below the lowest rock.
+    pub fn add_floor(&mut self) {
+        let floor = self.lowest_rock + 2;
+        for x in 0..self.cells.width() {
+            self.cells[Point::new(x, floor)] = Cell::Rock;
+        }
+        self.has_floor = true;
+    }
+
+    fn is_in_bounds(&self, y: usize) -> bool {
+        self.has_floor || y < self.lowest_rock
+    }
+
+    /// Drops one unit of sand, returning whether it came to rest.
+    pub fn drop_sand(&mut self) -> bool {
+        let mut position = self.source();
+        while let Some(next) = self.next_position(position) {
+            if !self.is_in_bounds(next.y) {
+                return false;
+            }
+            position = next;
+        }
+        self.cells[position] = Cell::Sand;
+        self.max_x = self.max_x.max(position.x + self.left);
+        self.min_x = self.min_x.min(position.x + self.left);
+        true
+    }
+
+    /// Drops sand until it starts to fall out of the bottom of the pit, returning how many units
+    /// came to rest.
+    ///
+    /// Fails if the sand instead piles up to the source, as it would then never stop.
+    pub fn fill(&mut self) -> Result<usize, Day14Error> {
+        let mut count = 0;
+        while self.drop_sand() {
+            count += 1;
+            if self.safe_to_stand() {
+                return Err(Day14Error::SourceBlocked);
+            }
+        }
+        Ok(count)
+    }
+
+    /// Drops sand until it blocks the source, returning how many units were dropped.
+    pub fn fill_to_source(&mut self) -> usize {
+        let mut count = 0;
+        while !self.safe_to_stand() {
+            self.drop_sand();
+            count += 1;
+        }
+        count
+    }
+
+    /// The cell at the given position, if it is within the pit.
+    pub fn get(&self, x: usize, y: usize) -> Option<Cell> {
+        self.cells
+            .get(Point::new(x.checked_sub(self.left)?, y))
+            .copied()
+    }
+
+    /// The number of rows in the pit, including the floor.
+    pub fn depth(&self) -> usize {
+        self.cells.height()
+    }
+
+    /// The cells of the pit. Column `0` of the grid is column [`SandPit::left`] of the cave.
+    pub fn cells(&self) -> &Grid<Cell> {
+        &self.cells
+    }
+
+    /// The leftmost column of the cave covered by the pit.
+    pub fn left(&self) -> usize {
+        self.left
+    }
+
+    /// The source's position within the grid.
+    fn source(&self) -> Point<usize> {
+        Point::new(self.source.x - self.left, self.source.y)
+    }
+
+    fn next_position(&self, position: Point<usize>) -> Option<Point<usize>> {
+        [
+            Direction8::Down,
+            Direction8::DownLeft,
+            Direction8::DownRight,
+        ]
+        .into_iter()
+        .filter_map(|direction| position.checked_step(direction))
+        .find(|&p| self.cells.get(p).is_some_and(Cell::is_empty))
+    }
+
+    /// Whether sand has blocked the source.
+    pub fn safe_to_stand(&self) -> bool {
+        !self.cells[self.source()].is_empty()
+    }
+
+    /// The grid columns worth drawing, one either side of any rock or sand, and the number of rows.
+    fn drawn_area(&self) -> (RangeInclusive<usize>, usize) {
+        let columns = (self.min_x - self.left).saturating_sub(1)
+            ..=(self.max_x + 1 - self.left).min(self.cells.width() - 1);
+        let rows = if self.has_floor {
+            self.cells.height()
+        } else {
+            self.lowest_rock + 1
+        };
+        (columns, rows)
+    }
+}
+
+impl fmt::Display for SandPit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (range, rows) = self.drawn_area();
+        for i in range.clone() {
+            let marker = if i + self.left == self.source.x {
+                '*'
+            } else {
+                ' '
+            };
+            write!(f, "{marker}")?;
+        }
+        writeln!(f)?;
+        for row in self.cells.rows().take(rows) {
+            for cell in &row[range.clone()] {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl Render for SandPit {
+    fn scene(&self) -> Scene {
+        let (columns, rows) = self.drawn_area();
+        let source = self.source();
+        let raster = Grid::from_vec(
+            columns.clone().count(),
+            self.cells
+                .iter()
+                .filter(|(p, _)| p.y < rows && columns.contains(&p.x))
+                .map(|(p, cell)| match cell {
+                    Cell::Empty if p == source => Pixel::new('+', Colour::new(0xff, 0x33, 0x33)),
+                    Cell::Empty => Pixel::blank('.'),
+                    Cell::Sand => Pixel::new('o', Colour::new(0xe0, 0xb0, 0x50)),
+                    Cell::Rock => Pixel::new('#', Colour::new(0x55, 0x55, 0x55)),
+                })
+                .collect(),
+        );
+        raster
+            .expect("every row is cropped to the same columns")
+            .into()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Cell {
+    #[default]
+    Empty,
+    Sand,
+    Rock,
+}
+
+impl Cell {
+    pub fn is_empty(&self) -> bool {
+        matches!(self, Cell::Empty)
+    }
+}
+
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let c = match self {
+            Cell::Empty => '.',
+            Cell::Sand => 'o',
+            Cell::Rock => '#',
+        };
+        write!(f, "{c}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use proptest::proptest;
+    use test_case::test_case;
+
+    use super::*;
+    use crate::{differential, params, Runner};
+
+    #[test_case(include_str!("../input/day14test") => matches Ok((24, 93)))]
+    fn default_tests(input: &str) -> Result<(usize, usize), Error<Day14Error>> {
+        crate::solve::<Day14>(input)
+    }
+
+    #[test_case("490,0 -> 510,0\n" => Err(Error::Puzzle(Day14Error::RockAtSource)) ; "rock at source")]
+    #[test_case("498,5 -> 498,2\n502,2 -> 502,5 -> 498,5\n" => Err(Error::Puzzle(Day14Error::SourceBlocked)) ; "basin")]
+    #[test_case("500,4294967295 -> 501,4294967295\n" => matches Err(Error::Puzzle(Day14Error::CaveTooLarge { .. })) ; "too large")]
+    #[test_case("498,4 -> 502,8\n" => matches Err(Error::Parse(_)) ; "diagonal")]
+    fn unsolvable_input(input: &str) -> Result<(usize, usize), Error<Day14Error>> {
+        crate::solve::<Day14>(input)
+    }
+
+    /// Moving the rock and the source together leaves the same amount of sand.
+    #[test]
+    fn moved_source() {
+        let moved = include_str!("../input/day14test")
+            .lines()
+            .map(|line| {
+                line.split(" -> ")
+                    .map(|vertex| {
+                        let (x, y) = vertex.split_once(',').unwrap();
+                        let (x, y) = (x.parse::<usize>().unwrap(), y.parse::<usize>().unwrap());
+                        format!("{},{}", x + 10, y + 3)
+                    })
+                    .join(" -> ")
+                    + "\n"
+            })
+            .collect::<String>();
+        let solution = Day14
+            .configure(&params::overrides(&["source_x=510", "source_y=3"]))
+            .unwrap();
+        let answers = solution.run(&moved).unwrap();
+        assert_eq!(
+            (answers.part1.as_str(), answers.part2.as_str()),
+            ("24", "93")
+        );
+    }
+
+    #[test]
+    fn source_below_the_floor() {
+        let solution = Day14
+            .configure(&params::overrides(&["source_y=10"]))
+            .unwrap();
+        let error = solution.run("498,4 -> 498,6\n").unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<Day14Error>(),
+            Some(&Day14Error::SourceBelowFloor)
+        );
+    }
+
+    #[test]
+    fn draws_rock_at_the_edge() {
+        let sand_pit = Day14::parse("0,2 -> 1,2\n").unwrap();
+        assert!(sand_pit.to_string().contains('#'));
+    }
+
+    /// Drops each unit of sand one square at a time, into a set of the blocked squares.
+    fn reference(input: &str) -> (usize, usize) {
+        let mut rock = HashSet::new();
+        for line in input.lines() {
+            let vertices = line
+                .split(" -> ")
+                .map(|vertex| {
+                    let (x, y) = vertex.split_once(',').unwrap();
+                    (x.parse::<i32>().unwrap(), y.parse::<i32>().unwrap())
+                })
+                .collect::<Vec<_>>();
+            for segment in vertices.windows(2) {
+                let ((x1, y1), (x2, y2)) = (segment[0], segment[1]);
+                for x in x1.min(x2)..=x1.max(x2) {
+                    for y in y1.min(y2)..=y1.max(y2) {
+                        rock.insert((x, y));
+                    }
+                }
+            }
+        }
+        let lowest = rock.iter().map(|&(_, y)| y).max().unwrap_or(0);
+
+        let pour = |floor: bool| {
+            let mut blocked = rock.clone();
+            let mut rested = 0;
+            while !blocked.contains(&(500, 0)) {
+                let mut sand = (500, 0);
+                loop {
+                    if !floor && sand.1 > lowest {
+                        return rested;
+                    }
+                    let (x, y) = sand;
+                    let next = [(x, y + 1), (x - 1, y + 1), (x + 1, y + 1)]
+                        .into_iter()
+                        .find(|&(x, y)| !blocked.contains(&(x, y)) && (!floor || y < lowest + 2));
+                    match next {
+                        Some(next) => sand = next,
+                        None => break,
+                    }
+                }
+                blocked.insert(sand);
+                rested += 1;
+            }
+            rested
+        };
+        (pour(false), pour(true))
+    }
+
+    proptest! {
+        #[test]
+        fn agrees_with_reference(input in differential::inputs(30)) {
+            differential::check::<Day14>(input, reference)?;
+        }
+    }
+}
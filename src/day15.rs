@@ -0,0 +1,475 @@
+//! Day 15: Beacon Exclusion Zone
+
+use std::io::BufRead;
+
+use color_eyre::Result;
+use fxhash::FxHashSet;
+use nom::{
+    combinator::{map, map_res},
+    error::context,
+    sequence::{preceded, separated_pair, tuple},
+};
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, trace};
+
+use crate::{
+    geometry::Point,
+    interval::IntervalSet,
+    params::Params,
+    parsing::{self, lines, number, tag, IResult},
+    stream::Input,
+    Error, PuzzleError, Solution, StreamResult,
+};
+
+/// The furthest a sensor or beacon may be from the origin along each axis, so that distances
+/// between them fit in an `i32`.
+const MAX_COORDINATE: i32 = 100_000_000;
+
+/// Solves day 15, by default checking coverage along row `ROW` and searching for the distress
+/// beacon in `0..=2 * ROW`.
+pub struct Day15<const ROW: i32 = 2_000_000>;
+
+/// Why the sensor readings don't locate the distress beacon.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum Day15Error {
+    #[error("every position is within range of a sensor, so there is nowhere for the beacon")]
+    NoGap,
+    #[error("more than one position is out of range of every sensor")]
+    SeveralGaps,
+}
+
+impl PuzzleError for Day15Error {}
+
+/// Where to check coverage and search for the distress beacon, defaulting to those of
+/// [`Day15<ROW>`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Day15Params<const ROW: i32 = 2_000_000> {
+    /// The row along which to count the positions where there can't be a beacon.
+    pub row: i32,
+    /// The largest coordinate of the distress beacon along each axis. The search takes time in
+    /// proportion to the area this bounds, but no memory beyond the readings.
+    pub search_limit: i32,
+    /// What the distress beacon's `x` coordinate is multiplied by in its tuning frequency.
+    pub tuning_multiplier: i64,
+}
+
+impl<const ROW: i32> Default for Day15Params<ROW> {
+    fn default() -> Self {
+        Self {
+            row: ROW,
+            search_limit: ROW * 2,
+            tuning_multiplier: 4_000_000,
+        }
+    }
+}
+
+impl<const ROW: i32> Params for Day15Params<ROW> {
+    fn validate(&self) -> Result<(), String> {
+        if !(-MAX_COORDINATE..=MAX_COORDINATE).contains(&self.row) {
+            return Err(format!("the row must be within ±{MAX_COORDINATE}"));
+        }
+        if !(0..=MAX_COORDINATE).contains(&self.search_limit) {
+            return Err(format!(
+                "the search limit must be between 0 and {MAX_COORDINATE}"
+            ));
+        }
+        if !(1..=i64::from(i32::MAX)).contains(&self.tuning_multiplier) {
+            return Err(format!(
+                "the tuning multiplier must be between 1 and {}",
+                i32::MAX
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl<const ROW: i32> Solution for Day15<ROW> {
+    const DAY: u8 = 15;
+
+    type Parsed<'i> = Vec<Reading>;
+    type Part1 = usize;
+    type Part2 = i64;
+    type Error = Day15Error;
+    type Params = Day15Params<ROW>;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, Error<Day15Error>> {
+        Ok(parsing::parse_all(input, lines(reading))?)
+    }
+
+    fn part1(readings: &Self::Parsed<'_>) -> Result<Self::Part1, Error<Day15Error>> {
+        Self::part1_with(readings, &Day15Params::default())
+    }
+
+    fn part2(readings: &Self::Parsed<'_>) -> Result<Self::Part2, Error<Day15Error>> {
+        Self::part2_with(readings, &Day15Params::default())
+    }
+
+    fn part1_with(
+        readings: &Self::Parsed<'_>,
+        params: &Day15Params<ROW>,
+    ) -> Result<Self::Part1, Error<Day15Error>> {
+        let mut row = RowCoverage::new(params.row);
+        for &reading in readings {
+            row.add(reading);
+        }
+        Ok(row.beacon_free())
+    }
+
+    fn part2_with(
+        readings: &Self::Parsed<'_>,
+        params: &Day15Params<ROW>,
+    ) -> Result<Self::Part2, Error<Day15Error>> {
+        Ok(distress_beacon(
+            readings,
+            params.search_limit,
+            params.tuning_multiplier,
+        )?)
+    }
+
+    fn solve_streaming<R: BufRead>(input: &mut Input<R>) -> StreamResult<Self> {
+        Self::solve_streaming_with(input, &Day15Params::default())
+    }
+
+    /// Adds each reading to the coverage of part 1 as it is read, but keeps every reading for
+    /// part 2's search, so memory grows with the number of readings rather than the search area.
+    fn solve_streaming_with<R: BufRead>(
+        input: &mut Input<R>,
+        params: &Day15Params<ROW>,
+    ) -> StreamResult<Self> {
+        let mut row = RowCoverage::new(params.row);
+        let mut readings = Vec::new();
+        for line in input.lines() {
+            let reading = line.parse(reading)?;
+            row.add(reading);
+            readings.push(reading);
+        }
+        Ok(Some((
+            row.beacon_free(),
+            distress_beacon(&readings, params.search_limit, params.tuning_multiplier)?,
+        )))
+    }
+
+    fn generate<R: Rng>(rng: &mut R, size: usize) -> String {
+        Self::generate_with(rng, size, &Day15Params::default())
+    }
+
+    /// Generates `size` sensors, or at least the few needed to leave only one place for the
+    /// distress beacon, all within the largest coordinate.
+    fn generate_with<R: Rng>(rng: &mut R, size: usize, params: &Day15Params<ROW>) -> String {
+        let bound = params.search_limit;
+        let distress = Position::new(rng.gen_range(0..=bound), rng.gen_range(0..=bound));
+
+        // Sensors diagonally around the distress beacon, each just out of its reach, cover every
+        // other position within the bounds. Each reaches past its quadrant of the search area by
+        // a little, but no further than the largest coordinate.
+        let mut readings = [(1, 1), (1, -1), (-1, 1), (-1, -1)]
+            .into_iter()
+            .filter_map(|(dx, dy)| {
+                let mut reach = |d: i32, along: i32| {
+                    let extent = if d > 0 { bound - along } else { along };
+                    extent + rng.gen_range(1..=1000).min(MAX_COORDINATE - bound)
+                };
+                let (rx, ry) = (reach(dx, distress.x), reach(dy, distress.y));
+                // With no room past a corner of the search area, there is nothing for its sensor
+                // to cover.
+                if rx + ry == 0 {
+                    return None;
+                }
+                let radius = rx + ry - 1;
+                let sensor = Position::new(distress.x + dx * rx, distress.y + dy * ry);
+                // The beacon lies on the way back to the distress beacon, so within the bounds.
+                let back = rx.min(radius);
+                let beacon = Position::new(sensor.x - dx * back, sensor.y - dy * (radius - back));
+                Some(Reading { sensor, beacon })
+            })
+            .collect::<Vec<_>>();
+        while readings.len() < size {
+            let sensor = Position::new(rng.gen_range(0..=bound), rng.gen_range(0..=bound));
+            let distance = sensor.manhattan_distance(distress);
+            if distance < 2 {
+                continue;
+            }
+            let radius = rng.gen_range(1..distance);
+            let dx = rng.gen_range(-radius..=radius);
+            let dy = (radius - dx.abs()) * if rng.gen_bool(0.5) { 1 } else { -1 };
+            let beacon = Position::new(sensor.x + dx, sensor.y + dy);
+            if beacon.x.abs() > MAX_COORDINATE || beacon.y.abs() > MAX_COORDINATE {
+                continue;
+            }
+            readings.push(Reading { sensor, beacon });
+        }
+        readings.shuffle(rng);
+
+        readings
+            .iter()
+            .map(|Reading { sensor, beacon }| {
+                format!(
+                    "Sensor at x={}, y={}: closest beacon is at x={}, y={}\n",
+                    sensor.x, sensor.y, beacon.x, beacon.y
+                )
+            })
+            .collect()
+    }
+}
+
+/// A sensor and the closest beacon it detected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reading {
+    pub sensor: Position,
+    pub beacon: Position,
+}
+
+fn coordinate(s: &str) -> IResult<'_, i32> {
+    map_res(number, |c: i32| {
+        if (-MAX_COORDINATE..=MAX_COORDINATE).contains(&c) {
+            Ok(c)
+        } else {
+            Err(format!("coordinates must be within ±{MAX_COORDINATE}"))
+        }
+    })(s)
+}
+
+fn reading(s: &str) -> IResult<'_, Reading> {
+    let position = |s| {
+        map(
+            separated_pair(
+                preceded(tag("x="), coordinate),
+                tag(", "),
+                preceded(tag("y="), coordinate),
+            ),
+            |(x, y)| Position::new(x, y),
+        )(s)
+    };
+    context(
+        "sensor report",
+        map(
+            tuple((
+                preceded(tag("Sensor at "), position),
+                preceded(tag(": closest beacon is at "), position),
+            )),
+            |(sensor, beacon)| Reading { sensor, beacon },
+        ),
+    )(s)
+}
+
+/// The positions along one row which are within range of a sensor, and the beacons on it.
+#[derive(Clone, Debug)]
+pub struct RowCoverage {
+    row: i32,
+    covered: IntervalSet<i32>,
+    beacons: FxHashSet<Position>,
+}
+
+impl RowCoverage {
+    pub fn new(row: i32) -> Self {
+        Self {
+            row,
+            covered: IntervalSet::new(),
+            beacons: FxHashSet::default(),
+        }
+    }
+
+    pub fn add(&mut self, Reading { sensor, beacon }: Reading) {
+        if beacon.y == self.row {
+            self.beacons.insert(beacon);
+        }
+
+        let distance = sensor.manhattan_distance(beacon);
+        let vertical_difference = sensor.y.abs_diff(self.row) as i32;
+        let remaining = distance - vertical_difference;
+        self.covered
+            .insert(sensor.x - remaining..=sensor.x + remaining);
+    }
+
+    /// The number of positions along the row where there can't be a beacon.
+    pub fn beacon_free(&self) -> usize {
+        self.covered.covered_len() as usize - self.beacons.len()
+    }
+}
+
+/// The tuning frequency of the only position in `0..=bound` in both axes which no sensor covers.
+///
+/// Each row is checked against every sensor in turn, holding only the ranges covered on that row,
+/// so the search takes time in proportion to the size of the search area, but memory only in
+/// proportion to the number of sensors.
+pub fn distress_beacon(
+    readings: &[Reading],
+    bound: i32,
+    multiplier: i64,
+) -> Result<i64, Day15Error> {
+    let sensors = readings
+        .iter()
+        .map(|&Reading { sensor, beacon }| {
+            // Counted in `i64`s, as `i32`s anywhere in their range can be too far apart.
+            let distance =
+                i64::from(sensor.x.abs_diff(beacon.x)) + i64::from(sensor.y.abs_diff(beacon.y));
+            debug!(?sensor, ?beacon, distance, "sensor range");
+            ((i64::from(sensor.x), i64::from(sensor.y)), distance)
+        })
+        .collect::<Vec<_>>();
+
+    let bound = i64::from(bound);
+    let mut covered = Vec::with_capacity(sensors.len());
+    let mut gaps = Vec::new();
+    for y in 0..=bound {
+        covered.clear();
+        covered.extend(sensors.iter().filter_map(|&((x, sensor_y), distance)| {
+            let remaining = distance - (sensor_y - y).abs();
+            (remaining >= 0).then_some((x - remaining, x + remaining))
+        }));
+        covered.sort_unstable();
+        trace!(row = y, ?covered, "row coverage");
+
+        // The first column not known to be covered.
+        let mut next = 0;
+        for &(start, end) in covered.iter().chain([&(bound + 1, bound + 1)]) {
+            let gap = next..start.min(bound + 1);
+            gaps.extend(gap.take(2 - gaps.len()).map(|x| (x, y)));
+            next = next.max(end + 1);
+            if next > bound {
+                break;
+            }
+        }
+        if gaps.len() > 1 {
+            return Err(Day15Error::SeveralGaps);
+        }
+    }
+    match gaps.first() {
+        // Within `0..=bound`, so a position.
+        Some(&(x, y)) => Ok(tuning_frequency(
+            Position::new(x as i32, y as i32),
+            multiplier,
+        )),
+        None => Err(Day15Error::NoGap),
+    }
+}
+
+pub type Position = Point<i32>;
+
+/// The tuning frequency of a distress beacon at the given position.
+pub fn tuning_frequency(position: Position, multiplier: i64) -> i64 {
+    position.x as i64 * multiplier + position.y as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::proptest;
+    use test_case::test_case;
+
+    use super::*;
+    use crate::{differential, params, Runner};
+
+    #[test_case(include_str!("../input/day15test") => matches Ok((26, 56000011)))]
+    fn default_tests(input: &str) -> Result<(usize, i64), Error<Day15Error>> {
+        crate::solve::<Day15<10>>(input)
+    }
+
+    #[test_case(&["row=10", "search_limit=20"] => ("26".to_owned(), "56000011".to_owned()) ; "example area")]
+    #[test_case(&["row=11", "search_limit=20", "tuning_multiplier=10"] => ("28".to_owned(), "151".to_owned()) ; "other row and multiplier")]
+    fn overridden_search(overrides: &[&str]) -> (String, String) {
+        let solution = Day15::<2_000_000>
+            .configure(&params::overrides(overrides))
+            .unwrap();
+        let answers = solution.run(include_str!("../input/day15test")).unwrap();
+        (answers.part1, answers.part2)
+    }
+
+    #[test_case("Sensor at x=10, y=10: closest beacon is at x=30, y=30\n" => Err(Error::Puzzle(Day15Error::NoGap)) ; "no gap")]
+    #[test_case("Sensor at x=0, y=0: closest beacon is at x=1, y=0\n" => Err(Error::Puzzle(Day15Error::SeveralGaps)) ; "several gaps")]
+    #[test_case("Sensor at x=2147483647, y=0: closest beacon is at x=-2147483648, y=0\n" => matches Err(Error::Parse(_)) ; "huge coordinates")]
+    fn unsolvable_input(input: &str) -> Result<(usize, i64), Error<Day15Error>> {
+        differential::solve::<Day15<10>>(input)
+    }
+
+    #[test_case(0)]
+    #[test_case(1)]
+    #[test_case(2)]
+    fn generated_inputs_leave_one_gap(seed: u64) {
+        let input = Day15::<10>.generate(seed, 20);
+        assert_eq!(input.lines().count(), 20);
+        assert!(crate::solve::<Day15<10>>(&input).is_ok(), "{input}");
+    }
+
+    #[test]
+    fn generates_parsable_inputs_at_the_largest_search_limit() {
+        let limit = format!("search_limit={MAX_COORDINATE}");
+        let solution = Day15::<10>
+            .configure(&params::overrides(&[&limit]))
+            .unwrap();
+        for seed in 0..20 {
+            let input = solution.generate(seed, 20);
+            assert_eq!(input.lines().count(), 20);
+            let parsed = Day15::<10>::parse(&input);
+            assert!(parsed.is_ok(), "{input}");
+        }
+    }
+
+    #[test]
+    fn searches_the_largest_area_without_holding_it() {
+        let params = Day15Params::<10> {
+            search_limit: MAX_COORDINATE,
+            ..Day15Params::default()
+        };
+        assert!(params.validate().is_ok());
+        let readings =
+            Day15::<10>::parse("Sensor at x=0, y=0: closest beacon is at x=1, y=0\n").unwrap();
+        // The first row has gaps all along it, so the search stops there.
+        assert_eq!(
+            Day15::<10>::part2_with(&readings, &params),
+            Err(Error::Puzzle(Day15Error::SeveralGaps))
+        );
+    }
+
+    /// Checks every position along the row, and every position in the search area, against each
+    /// sensor in turn.
+    fn reference(input: &str) -> (usize, i64) {
+        const ROW: i64 = 10;
+        let readings = input
+            .lines()
+            .map(|line| {
+                let numbers = line
+                    .split(|c: char| !(c.is_ascii_digit() || c == '-'))
+                    .filter(|s| !s.is_empty())
+                    .map(|s| s.parse::<i64>().unwrap())
+                    .collect::<Vec<_>>();
+                ((numbers[0], numbers[1]), (numbers[2], numbers[3]))
+            })
+            .collect::<Vec<_>>();
+        let distance =
+            |(x1, y1): (i64, i64), (x2, y2): (i64, i64)| (x1 - x2).abs() + (y1 - y2).abs();
+        let covered = |position| {
+            readings
+                .iter()
+                .any(|&(sensor, beacon)| distance(sensor, position) <= distance(sensor, beacon))
+        };
+
+        let reach = readings
+            .iter()
+            .map(|&(sensor, beacon)| distance(sensor, beacon))
+            .max()
+            .unwrap();
+        let (left, right) = readings
+            .iter()
+            .fold((i64::MAX, i64::MIN), |(l, r), &(sensor, _)| {
+                (l.min(sensor.0 - reach), r.max(sensor.0 + reach))
+            });
+        let part1 = (left..=right)
+            .filter(|&x| covered((x, ROW)) && !readings.iter().any(|&(_, b)| b == (x, ROW)))
+            .count();
+
+        let distress = (0..=2 * ROW)
+            .flat_map(|y| (0..=2 * ROW).map(move |x| (x, y)))
+            .find(|&position| !covered(position))
+            .unwrap();
+        (part1, distress.0 * 4_000_000 + distress.1)
+    }
+
+    proptest! {
+        #[test]
+        fn agrees_with_reference(input in differential::inputs(30)) {
+            differential::check::<Day15<10>>(input, reference)?;
+        }
+    }
+}
@@ -0,0 +1,60 @@
+//! Differential testing of the solutions against deliberately simple reference solvers.
+//!
+//! Each day's tests pair its solution with a reference that favours obviousness over speed, and
+//! check that the two agree on generated inputs. Inputs are described by the seed and size given
+//! to [`Solution::generate`], so proptest shrinks a disagreement towards the smallest input that
+//! still shows it. Days which can stream their input are also checked to give the same answers
+//! when streamed.
+
+use std::fmt::Debug;
+
+use proptest::{prelude::*, test_runner::TestCaseError};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::{stream::Input, Solution, SolveResult};
+
+/// The seed and size of a generated input, with the size between `1` and `max_size`.
+pub fn inputs(max_size: usize) -> impl Strategy<Value = (u64, usize)> {
+    (any::<u64>(), 1..=max_size)
+}
+
+/// Checks that the solution and the reference give the same answers for a generated input.
+pub fn check<S>(
+    (seed, size): (u64, usize),
+    reference: fn(&str) -> (S::Part1, S::Part2),
+) -> Result<(), TestCaseError>
+where
+    S: Solution,
+    S::Part1: PartialEq + Debug,
+    S::Part2: PartialEq + Debug,
+{
+    let input = S::generate(&mut ChaCha8Rng::seed_from_u64(seed), size);
+    let answers = crate::solve::<S>(&input)
+        .map_err(|e| TestCaseError::fail(format!("{e:?}\ninput:\n{input}")))?;
+    prop_assert_eq!(&answers, &reference(&input), "input:\n{}", input);
+    if let Some(streamed) = S::solve_streaming(&mut Input::new(input.as_bytes()))
+        .map_err(|e| TestCaseError::fail(format!("streamed: {e:?}\ninput:\n{input}")))?
+    {
+        prop_assert_eq!(streamed, answers, "streamed input:\n{}", input);
+    }
+    Ok(())
+}
+
+/// Solves the input, checking that streaming it gives the same answers or failure for days which
+/// can be streamed.
+pub fn solve<S>(input: &str) -> SolveResult<S>
+where
+    S: Solution,
+    S::Part1: PartialEq + Debug,
+    S::Part2: PartialEq + Debug,
+    S::Error: PartialEq + Debug,
+{
+    let solved = crate::solve::<S>(input);
+    match S::solve_streaming(&mut Input::new(input.as_bytes())) {
+        Ok(None) => {}
+        Ok(Some(streamed)) => assert_eq!(solved.as_ref().ok(), Some(&streamed), "streamed"),
+        Err(e) => assert_eq!(solved.as_ref().err(), Some(&e), "streamed"),
+    }
+    solved
+}
@@ -0,0 +1,366 @@
+//! Points on a 2D lattice, the directions between them, and distance metrics.
+//!
+//! `x` increases to the right and `y` increases downwards, matching [`Grid`](crate::grid::Grid),
+//! so [`Direction4::Up`] is a step towards `y = 0`.
+
+use std::{
+    fmt,
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+};
+
+use num::{Num, Signed, ToPrimitive, Zero};
+
+/// A point, or an offset between two points.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Point<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T: Zero> Point<T> {
+    pub fn origin() -> Self {
+        Self::new(T::zero(), T::zero())
+    }
+}
+
+impl<T: Copy + Num + PartialOrd> Point<T> {
+    /// The point one step away in the given direction.
+    pub fn step(self, direction: impl Into<Self>) -> Self {
+        self + direction.into()
+    }
+
+    /// The sum of the distances along each axis.
+    pub fn manhattan_distance(self, other: Self) -> T {
+        abs_diff(self.x, other.x) + abs_diff(self.y, other.y)
+    }
+
+    /// The larger of the distances along each axis; the number of king's moves between the points.
+    pub fn chebyshev_distance(self, other: Self) -> T {
+        let dx = abs_diff(self.x, other.x);
+        let dy = abs_diff(self.y, other.y);
+        if dx > dy {
+            dx
+        } else {
+            dy
+        }
+    }
+}
+
+impl<T: Copy + ToPrimitive> Point<T> {
+    /// The straight line distance between the points.
+    ///
+    /// # Panics
+    ///
+    /// If either coordinate can't be represented as an `f64`.
+    pub fn euclidean_distance(self, other: Self) -> f64 {
+        let as_f64 = |v: T| v.to_f64().expect("coordinate is representable as an f64");
+        let dx = as_f64(self.x) - as_f64(other.x);
+        let dy = as_f64(self.y) - as_f64(other.y);
+        dx.hypot(dy)
+    }
+}
+
+impl<T: Copy + Signed> Point<T> {
+    /// The sign of each coordinate, giving a single king's move in the direction of this offset.
+    pub fn signum(self) -> Self {
+        Self::new(self.x.signum(), self.y.signum())
+    }
+
+    /// Rotates a quarter turn clockwise about the origin.
+    pub fn rotate_cw(self) -> Self {
+        Self::new(-self.y, self.x)
+    }
+
+    /// Rotates a quarter turn anticlockwise about the origin.
+    pub fn rotate_ccw(self) -> Self {
+        Self::new(self.y, -self.x)
+    }
+}
+
+impl Point<usize> {
+    /// Adds a signed offset, returning `None` if either coordinate would go below zero or overflow.
+    pub fn checked_add_signed(self, offset: Point<isize>) -> Option<Self> {
+        Some(Self::new(
+            self.x.checked_add_signed(offset.x)?,
+            self.y.checked_add_signed(offset.y)?,
+        ))
+    }
+
+    /// The point one step away in the given direction, if it has no negative coordinates.
+    pub fn checked_step(self, direction: impl Into<Point<isize>>) -> Option<Self> {
+        self.checked_add_signed(direction.into())
+    }
+}
+
+fn abs_diff<T: Copy + Num + PartialOrd>(a: T, b: T) -> T {
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl<T: AddAssign> AddAssign for Point<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl<T: SubAssign> SubAssign for Point<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+    }
+}
+
+impl<T: Copy + Mul<Output = T>> Mul<T> for Point<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Self::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+impl<T> From<(T, T)> for Point<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Self::new(x, y)
+    }
+}
+
+impl<T> From<Point<T>> for (T, T) {
+    fn from(point: Point<T>) -> Self {
+        (point.x, point.y)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Point<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+/// One of the four orthogonal directions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction4 {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction4 {
+    /// Every direction, clockwise from [`Direction4::Up`].
+    pub const ALL: [Self; 4] = [Self::Up, Self::Right, Self::Down, Self::Left];
+
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 4]
+    }
+
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 2) % 4]
+    }
+
+    /// The offset of a single step in this direction.
+    pub fn offset<T: Signed>(self) -> Point<T> {
+        let (x, y) = match self {
+            Self::Up => (T::zero(), -T::one()),
+            Self::Right => (T::one(), T::zero()),
+            Self::Down => (T::zero(), T::one()),
+            Self::Left => (-T::one(), T::zero()),
+        };
+        Point::new(x, y)
+    }
+}
+
+impl<T: Signed> From<Direction4> for Point<T> {
+    fn from(direction: Direction4) -> Self {
+        direction.offset()
+    }
+}
+
+/// One of the four orthogonal or four diagonal directions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction8 {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction8 {
+    /// Every direction, clockwise from [`Direction8::Up`].
+    pub const ALL: [Self; 8] = [
+        Self::Up,
+        Self::UpRight,
+        Self::Right,
+        Self::DownRight,
+        Self::Down,
+        Self::DownLeft,
+        Self::Left,
+        Self::UpLeft,
+    ];
+
+    /// Turns an eighth of a turn clockwise.
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 8]
+    }
+
+    /// Turns an eighth of a turn anticlockwise.
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 7) % 8]
+    }
+
+    pub fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 4) % 8]
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self as usize % 2 == 1
+    }
+
+    /// The offset of a single step in this direction.
+    pub fn offset<T: Signed>(self) -> Point<T> {
+        let (x, y) = match self {
+            Self::Up => (T::zero(), -T::one()),
+            Self::UpRight => (T::one(), -T::one()),
+            Self::Right => (T::one(), T::zero()),
+            Self::DownRight => (T::one(), T::one()),
+            Self::Down => (T::zero(), T::one()),
+            Self::DownLeft => (-T::one(), T::one()),
+            Self::Left => (-T::one(), T::zero()),
+            Self::UpLeft => (-T::one(), -T::one()),
+        };
+        Point::new(x, y)
+    }
+}
+
+impl From<Direction4> for Direction8 {
+    fn from(direction: Direction4) -> Self {
+        Self::ALL[direction as usize * 2]
+    }
+}
+
+impl<T: Signed> From<Direction8> for Point<T> {
+    fn from(direction: Direction8) -> Self {
+        direction.offset()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test_case(Point::new(0, 0), Point::new(3, -4) => (7, 4, 5.0))]
+    #[test_case(Point::new(-2, 5), Point::new(-2, 5) => (0, 0, 0.0))]
+    #[test_case(Point::new(1, 1), Point::new(2, 2) => (2, 1, 2f64.sqrt()))]
+    fn distances(a: Point<i32>, b: Point<i32>) -> (i32, i32, f64) {
+        (
+            a.manhattan_distance(b),
+            a.chebyshev_distance(b),
+            a.euclidean_distance(b),
+        )
+    }
+
+    #[test]
+    fn unsigned_distances_do_not_underflow() {
+        let (a, b) = (Point::new(1usize, 5), Point::new(4usize, 2));
+        assert_eq!(a.manhattan_distance(b), 6);
+        assert_eq!(b.manhattan_distance(a), 6);
+        assert_eq!(a.chebyshev_distance(b), 3);
+    }
+
+    #[test]
+    fn rotations_follow_directions() {
+        for direction in Direction4::ALL {
+            let offset = direction.offset::<i32>();
+            assert_eq!(offset.rotate_cw(), direction.turn_right().offset());
+            assert_eq!(offset.rotate_ccw(), direction.turn_left().offset());
+            assert_eq!(-offset, direction.opposite().offset());
+            assert_eq!(direction.turn_right().turn_left(), direction);
+        }
+    }
+
+    #[test]
+    fn direction8_steps_around_the_compass() {
+        let mut direction = Direction8::Up;
+        let mut visited = Vec::new();
+        for _ in 0..8 {
+            visited.push(Point::<i32>::origin().step(direction));
+            direction = direction.turn_right();
+        }
+        assert_eq!(direction, Direction8::Up);
+        assert_eq!(
+            visited
+                .iter()
+                .copied()
+                .map(<(i32, i32)>::from)
+                .collect::<Vec<_>>(),
+            [
+                (0, -1),
+                (1, -1),
+                (1, 0),
+                (1, 1),
+                (0, 1),
+                (-1, 1),
+                (-1, 0),
+                (-1, -1)
+            ]
+        );
+        assert_eq!(Direction8::from(Direction4::Left), Direction8::Left);
+        assert!(Direction8::DownLeft.is_diagonal());
+    }
+
+    #[test_case(Point::new(0, 0), Direction4::Up => None)]
+    #[test_case(Point::new(0, 0), Direction4::Right => Some(Point::new(1, 0)))]
+    #[test_case(Point::new(3, 1), Direction4::Up => Some(Point::new(3, 0)))]
+    fn checked_step(from: Point<usize>, direction: Direction4) -> Option<Point<usize>> {
+        from.checked_step(direction)
+    }
+
+    #[test_case(Point::new(3, -7) => Point::new(1, -1))]
+    #[test_case(Point::new(0, 2) => Point::new(0, 1))]
+    fn signum(offset: Point<i32>) -> Point<i32> {
+        offset.signum()
+    }
+}
@@ -0,0 +1,332 @@
+//! A dense, rectangular 2D grid.
+//!
+//! Positions are [`Point`]s, with `x` increasing to the right and `y` increasing downwards from the
+//! top-left corner.
+
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+use crate::{
+    geometry::{Direction4, Direction8, Point},
+    parsing::ParseError,
+};
+
+/// A dense, rectangular grid of cells, stored row by row.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T: Clone> Grid<T> {
+    /// Creates a grid with every cell set to `fill`.
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Self {
+            cells: vec![fill; width * height],
+            width,
+            height,
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid from cells listed row by row.
+    ///
+    /// Returns `None` if the number of cells is not a multiple of `width`.
+    pub fn from_vec(width: usize, cells: Vec<T>) -> Option<Self> {
+        if width == 0 {
+            return cells.is_empty().then_some(Self {
+                cells,
+                width,
+                height: 0,
+            });
+        }
+        if !cells.len().is_multiple_of(width) {
+            return None;
+        }
+        let height = cells.len() / width;
+        Some(Self {
+            cells,
+            width,
+            height,
+        })
+    }
+
+    /// Parses a character map, with one row of the grid per line.
+    ///
+    /// Lines may end with either `\n` or `\r\n`. Every row must be the same width, and `cell` must
+    /// accept every character.
+    pub fn parse(input: &str, mut cell: impl FnMut(char) -> Option<T>) -> Result<Self, ParseError> {
+        let mut cells = Vec::with_capacity(input.len());
+        let mut width = None;
+        let mut height = 0;
+        for line in input.lines() {
+            let mut line_width = 0;
+            for (idx, c) in line.char_indices() {
+                let value = cell(c).ok_or_else(|| {
+                    ParseError::new(input, &line[idx..], format!("unexpected character {c:?}"))
+                })?;
+                cells.push(value);
+                line_width += 1;
+            }
+            match width {
+                None => width = Some(line_width),
+                Some(width) if width != line_width => {
+                    return Err(ParseError::new(
+                        input,
+                        &line[line.len()..],
+                        format!("line is {line_width} wide, but the first line is {width} wide"),
+                    ))
+                }
+                Some(_) => {}
+            }
+            height += 1;
+        }
+
+        Ok(Self {
+            cells,
+            width: width.unwrap_or_default(),
+            height,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Whether the position lies within the grid.
+    pub fn contains(&self, position: Point<usize>) -> bool {
+        position.x < self.width && position.y < self.height
+    }
+
+    pub fn get(&self, position: Point<usize>) -> Option<&T> {
+        self.index_of(position).map(|idx| &self.cells[idx])
+    }
+
+    pub fn get_mut(&mut self, position: Point<usize>) -> Option<&mut T> {
+        self.index_of(position).map(|idx| &mut self.cells[idx])
+    }
+
+    fn index_of(&self, position: Point<usize>) -> Option<usize> {
+        self.contains(position)
+            .then_some(position.x + position.y * self.width)
+    }
+
+    /// Every position in the grid, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Point<usize>> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Point::new(x, y)))
+    }
+
+    /// Every cell in the grid with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point<usize>, &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    /// The position of the first cell, row by row, matching the predicate.
+    pub fn position(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<Point<usize>> {
+        self.iter().find(|(_, c)| predicate(c)).map(|(p, _)| p)
+    }
+
+    /// A grid of the same shape with `f` applied to every cell.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        (y < self.height).then(|| &self.cells[y * self.width..(y + 1) * self.width])
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(|y| &self.cells[y * self.width..(y + 1) * self.width])
+    }
+
+    /// The cells in column `x`, from top to bottom. Empty if `x` is out of bounds.
+    pub fn column(&self, x: usize) -> impl DoubleEndedIterator<Item = &T> {
+        let cells = if x < self.width {
+            &self.cells[x..]
+        } else {
+            &[]
+        };
+        cells.iter().step_by(self.width.max(1))
+    }
+
+    /// The positions reached by repeatedly stepping in a direction from `from`, excluding `from`
+    /// itself, until leaving the grid.
+    pub fn ray(
+        &self,
+        from: Point<usize>,
+        direction: impl Into<Point<isize>>,
+    ) -> impl Iterator<Item = Point<usize>> + '_ {
+        let step = direction.into();
+        let mut current = from;
+        std::iter::from_fn(move || {
+            if step == Point::origin() {
+                return None;
+            }
+            let next = current.checked_add_signed(step)?;
+            if !self.contains(next) {
+                return None;
+            }
+            current = next;
+            Some(next)
+        })
+    }
+
+    /// The orthogonally adjacent positions which lie within the grid.
+    pub fn neighbours4(&self, position: Point<usize>) -> impl Iterator<Item = Point<usize>> + '_ {
+        self.neighbours(position, Direction4::ALL.map(Point::from))
+    }
+
+    /// The orthogonally and diagonally adjacent positions which lie within the grid.
+    pub fn neighbours8(&self, position: Point<usize>) -> impl Iterator<Item = Point<usize>> + '_ {
+        self.neighbours(position, Direction8::ALL.map(Point::from))
+    }
+
+    fn neighbours<const N: usize>(
+        &self,
+        position: Point<usize>,
+        steps: [Point<isize>; N],
+    ) -> impl Iterator<Item = Point<usize>> + '_ {
+        steps.into_iter().filter_map(move |step| {
+            let next = position.checked_add_signed(step)?;
+            self.contains(next).then_some(next)
+        })
+    }
+}
+
+impl<T> Index<Point<usize>> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: Point<usize>) -> &Self::Output {
+        self.get(position).unwrap_or_else(|| {
+            panic!(
+                "position {position:?} is outside of the {}x{} grid",
+                self.width, self.height
+            )
+        })
+    }
+}
+
+impl<T> IndexMut<Point<usize>> for Grid<T> {
+    fn index_mut(&mut self, position: Point<usize>) -> &mut Self::Output {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(position).unwrap_or_else(|| {
+            panic!("position {position:?} is outside of the {width}x{height} grid")
+        })
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Grid")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .field("rows", &self.rows().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use test_case::test_case;
+
+    use super::*;
+
+    fn digits(input: &str) -> Grid<u32> {
+        Grid::parse(input, |c| c.to_digit(10)).unwrap()
+    }
+
+    #[test_case("123\n456\n" ; "lf")]
+    #[test_case("123\r\n456\r\n" ; "crlf")]
+    #[test_case("123\n456" ; "no trailing newline")]
+    fn parse_line_endings(input: &str) {
+        let grid = digits(input);
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.to_string(), "123\n456\n");
+    }
+
+    #[test_case("123\n45\n" => matches Err(_) ; "ragged")]
+    #[test_case("123\n4x6\n" => matches Err(_) ; "bad character")]
+    #[test_case("" => matches Ok((0, 0)) ; "empty")]
+    fn parse_errors(input: &str) -> Result<(usize, usize), ParseError> {
+        Grid::parse(input, |c| c.to_digit(10)).map(|g| (g.width(), g.height()))
+    }
+
+    #[test]
+    fn parse_errors_are_located() {
+        let error = Grid::parse("123\n4x6\n", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!((error.line(), error.column()), (2, 2));
+        let error = Grid::parse("123\n45\n", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!((error.line(), error.column()), (2, 3));
+    }
+
+    #[test]
+    fn get_is_bounds_checked_on_both_axes() {
+        let grid = digits("12\n34\n56\n");
+        assert_eq!(grid.get(Point::new(1, 2)), Some(&6));
+        assert_eq!(grid.get(Point::new(2, 0)), None);
+        assert_eq!(grid.get(Point::new(0, 3)), None);
+    }
+
+    #[test_case((0, 0) => vec![(1, 0), (0, 1)])]
+    #[test_case((1, 1) => vec![(1, 0), (2, 1), (1, 2), (0, 1)])]
+    #[test_case((2, 2) => vec![(2, 1), (1, 2)])]
+    fn neighbours4(position: (usize, usize)) -> Vec<(usize, usize)> {
+        digits("123\n456\n789\n")
+            .neighbours4(position.into())
+            .map(Into::into)
+            .collect()
+    }
+
+    #[test_case((0, 0) => 3)]
+    #[test_case((1, 1) => 8)]
+    #[test_case((2, 1) => 5)]
+    fn neighbours8(position: (usize, usize)) -> usize {
+        digits("123\n456\n789\n")
+            .neighbours8(position.into())
+            .count()
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let grid = digits("123\n456\n");
+        assert_eq!(grid.row(1), Some(&[4, 5, 6][..]));
+        assert_eq!(grid.row(2), None);
+        assert_eq!(grid.column(1).copied().collect_vec(), [2, 5]);
+        assert_eq!(grid.column(1).rev().copied().collect_vec(), [5, 2]);
+        assert_eq!(grid.column(3).count(), 0);
+    }
+
+    #[test_case((1, 1), (1, 0) => vec![(2, 1), (3, 1)])]
+    #[test_case((1, 1), (-1, -1) => vec![(0, 0)])]
+    #[test_case((0, 0), (0, -1) => Vec::<(usize, usize)>::new())]
+    fn ray(from: (usize, usize), step: (isize, isize)) -> Vec<(usize, usize)> {
+        let grid = Grid::new(4, 3, ());
+        grid.ray(from.into(), step).map(Into::into).collect()
+    }
+}
@@ -0,0 +1,419 @@
+//! Sets of integers stored as disjoint inclusive ranges.
+
+use std::{
+    collections::BTreeMap,
+    fmt, iter,
+    ops::{Bound, RangeInclusive},
+};
+
+use num::PrimInt;
+
+/// A set of integers, stored as the disjoint inclusive ranges it covers.
+///
+/// Ranges that overlap or abut are merged as they are inserted, so the stored ranges are always
+/// separated by at least one uncovered value. Point queries and single-range updates take
+/// logarithmic time in the number of stored ranges, plus the number of ranges merged or split.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    /// Ranges keyed by start, with the inclusive end as the value.
+    ranges: BTreeMap<T, T>,
+    /// The number of values in the set, saturating at `u128::MAX`.
+    covered: u128,
+}
+
+impl<T: PrimInt> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self {
+            ranges: BTreeMap::new(),
+            covered: 0,
+        }
+    }
+
+    /// The number of disjoint ranges in the set.
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// The disjoint ranges in the set, in ascending order.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = RangeInclusive<T>> + '_ {
+        self.ranges.iter().map(|(&start, &end)| start..=end)
+    }
+
+    /// The number of values in the set.
+    ///
+    /// Only a set of 128-bit integers can hold more values than a `u128` counts, in which case
+    /// this is `u128::MAX`.
+    pub fn covered_len(&self) -> u128 {
+        self.covered
+    }
+
+    /// The number of values in the set which also lie within `bounds`, saturating like
+    /// [`covered_len`](Self::covered_len).
+    pub fn covered_len_within(&self, bounds: RangeInclusive<T>) -> u128 {
+        self.overlapping(bounds)
+            .map(|r| width(*r.start(), *r.end()))
+            .fold(0, u128::saturating_add)
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.ranges
+            .range(..=value)
+            .next_back()
+            .is_some_and(|(_, &end)| end >= value)
+    }
+
+    /// Whether every value in `range` is in the set. Always true for an empty range.
+    pub fn contains_range(&self, range: &RangeInclusive<T>) -> bool {
+        range.is_empty()
+            || self
+                .ranges
+                .range(..=*range.start())
+                .next_back()
+                .is_some_and(|(&s, &e)| covers(&(s..=e), range))
+    }
+
+    /// Whether any value in `range` is in the set.
+    pub fn overlaps(&self, range: &RangeInclusive<T>) -> bool {
+        self.ranges
+            .range(..=*range.end())
+            .next_back()
+            .is_some_and(|(&s, &e)| overlap(&(s..=e), range))
+    }
+
+    /// Adds every value in `range` to the set. Empty ranges are ignored.
+    pub fn insert(&mut self, range: RangeInclusive<T>) {
+        let (mut start, mut end) = (*range.start(), *range.end());
+        if start > end {
+            return;
+        }
+
+        if let Some((&s, &e)) = self.ranges.range(..=start).next_back() {
+            if e >= start || abuts(e, start) {
+                start = s;
+                end = end.max(e);
+                self.remove_stored(s);
+            }
+        }
+        while let Some((&s, &e)) = self.ranges.range(start..).next() {
+            if s > end && !abuts(end, s) {
+                break;
+            }
+            end = end.max(e);
+            self.remove_stored(s);
+        }
+
+        self.insert_stored(start, end);
+    }
+
+    /// Removes every value in `range` from the set, splitting any range that straddles its ends.
+    pub fn remove(&mut self, range: RangeInclusive<T>) {
+        let (start, end) = (*range.start(), *range.end());
+        if start > end {
+            return;
+        }
+
+        if let Some((&s, &e)) = self.ranges.range(..start).next_back() {
+            if e >= start {
+                self.remove_stored(s);
+                self.insert_stored(s, start - T::one());
+                if e > end {
+                    self.insert_stored(end + T::one(), e);
+                }
+            }
+        }
+        while let Some((&s, &e)) = self.ranges.range(start..=end).next() {
+            self.remove_stored(s);
+            if e > end {
+                self.insert_stored(end + T::one(), e);
+            }
+        }
+    }
+
+    /// The parts of the set which lie within `bounds`, in ascending order.
+    pub fn overlapping(
+        &self,
+        bounds: RangeInclusive<T>,
+    ) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        let (start, end) = (*bounds.start(), *bounds.end());
+        // The range containing `start`, if any, begins before it.
+        let first = self
+            .ranges
+            .range(..start)
+            .next_back()
+            .filter(|&(_, &e)| e >= start && start <= end)
+            .map(|(_, &e)| start..=e.min(end));
+        let rest = (start <= end)
+            .then(|| {
+                self.ranges
+                    .range((Bound::Included(start), Bound::Included(end)))
+                    .map(move |(&s, &e)| s..=e.min(end))
+            })
+            .into_iter()
+            .flatten();
+        first.into_iter().chain(rest)
+    }
+
+    /// The maximal ranges within `bounds` which are not in the set, in ascending order.
+    pub fn gaps(&self, bounds: RangeInclusive<T>) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        let (start, end) = (*bounds.start(), *bounds.end());
+        let mut cursor = (start <= end).then_some(start);
+        self.overlapping(bounds)
+            .map(Some)
+            .chain(iter::once(None))
+            .filter_map(move |covered| {
+                let next = cursor?;
+                match covered {
+                    Some(covered) => {
+                        let gap =
+                            (next < *covered.start()).then(|| next..=*covered.start() - T::one());
+                        cursor = (*covered.end() < end).then(|| *covered.end() + T::one());
+                        gap
+                    }
+                    None => {
+                        cursor = None;
+                        Some(next..=end)
+                    }
+                }
+            })
+    }
+
+    /// The values within `bounds` which are not in the set.
+    pub fn complement(&self, bounds: RangeInclusive<T>) -> Self {
+        self.gaps(bounds).collect()
+    }
+
+    /// The values in either set.
+    pub fn union(&self, other: &Self) -> Self {
+        let (mut larger, smaller) = if self.len() >= other.len() {
+            (self.clone(), other)
+        } else {
+            (other.clone(), self)
+        };
+        larger.extend(smaller.iter());
+        larger
+    }
+
+    /// The values in both sets.
+    pub fn intersection(&self, other: &Self) -> Self {
+        let (larger, smaller) = if self.len() >= other.len() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        smaller
+            .iter()
+            .flat_map(|range| larger.overlapping(range))
+            .collect()
+    }
+
+    /// The values in this set but not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        for range in other.iter() {
+            result.remove(range);
+        }
+        result
+    }
+
+    fn insert_stored(&mut self, start: T, end: T) {
+        self.ranges.insert(start, end);
+        self.covered = self.covered.saturating_add(width(start, end));
+    }
+
+    fn remove_stored(&mut self, start: T) {
+        if let Some(end) = self.ranges.remove(&start) {
+            self.covered = if self.covered == u128::MAX {
+                // The count may have saturated, so count again from what is left.
+                self.covered_len_within(T::min_value()..=T::max_value())
+            } else {
+                self.covered - width(start, end)
+            };
+        }
+    }
+}
+
+/// Whether every value in `inner` is also in `outer`. Always true for an empty `inner`.
+pub fn covers<T: PartialOrd>(outer: &RangeInclusive<T>, inner: &RangeInclusive<T>) -> bool {
+    inner.is_empty() || (outer.start() <= inner.start() && inner.end() <= outer.end())
+}
+
+/// Whether any value is in both ranges. Always false if either is empty.
+pub fn overlap<T: PartialOrd>(a: &RangeInclusive<T>, b: &RangeInclusive<T>) -> bool {
+    !a.is_empty() && !b.is_empty() && a.start() <= b.end() && b.start() <= a.end()
+}
+
+/// Whether the range ending at `end` is immediately followed by the one starting at `start`.
+fn abuts<T: PrimInt>(end: T, start: T) -> bool {
+    end < start && end + T::one() == start
+}
+
+/// The number of values in `start..=end`, where `start <= end`, or `u128::MAX` if there are more.
+fn width<T: PrimInt>(start: T, end: T) -> u128 {
+    let span = match (start.to_i128(), end.to_i128()) {
+        (Some(start), Some(end)) => end.abs_diff(start),
+        // Only `u128`s beyond `i128::MAX` get here, so `start` is unsigned.
+        _ => end.to_u128().unwrap_or(u128::MAX) - start.to_u128().unwrap_or_default(),
+    };
+    span.saturating_add(1)
+}
+
+impl<T: PrimInt> From<RangeInclusive<T>> for IntervalSet<T> {
+    fn from(range: RangeInclusive<T>) -> Self {
+        let mut set = Self::new();
+        set.insert(range);
+        set
+    }
+}
+
+impl<T: PrimInt> FromIterator<RangeInclusive<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T: PrimInt> Extend<RangeInclusive<T>> for IntervalSet<T> {
+    fn extend<I: IntoIterator<Item = RangeInclusive<T>>>(&mut self, iter: I) {
+        for range in iter {
+            self.insert(range);
+        }
+    }
+}
+
+impl<T: PrimInt + fmt::Debug> fmt::Debug for IntervalSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use test_case::test_case;
+
+    use super::*;
+
+    fn set(ranges: &[RangeInclusive<i32>]) -> IntervalSet<i32> {
+        ranges.iter().cloned().collect()
+    }
+
+    #[test_case(&[1..=3, 5..=7] => vec![1..=3, 5..=7] ; "disjoint")]
+    #[test_case(&[1..=3, 4..=7] => vec![1..=7] ; "abutting")]
+    #[test_case(&[5..=7, 1..=5] => vec![1..=7] ; "overlapping")]
+    #[test_case(&[1..=9, 3..=4] => vec![1..=9] ; "nested")]
+    #[test_case(&[1..=2, 4..=5, 7..=8, 0..=7] => vec![0..=8] ; "spanning several")]
+    #[test_case(&[RangeInclusive::new(3, 1)] => Vec::<RangeInclusive<i32>>::new() ; "empty range")]
+    fn insert(ranges: &[RangeInclusive<i32>]) -> Vec<RangeInclusive<i32>> {
+        set(ranges).iter().collect()
+    }
+
+    #[test_case(&[1..=9], 3..=5 => vec![1..=2, 6..=9] ; "split")]
+    #[test_case(&[1..=3, 5..=7], 2..=6 => vec![1..=1, 7..=7] ; "trim both")]
+    #[test_case(&[1..=3, 5..=7], 0..=9 => Vec::<RangeInclusive<i32>>::new() ; "everything")]
+    #[test_case(&[1..=3], 4..=9 => vec![1..=3] ; "nothing")]
+    fn remove(
+        ranges: &[RangeInclusive<i32>],
+        removed: RangeInclusive<i32>,
+    ) -> Vec<RangeInclusive<i32>> {
+        let mut set = set(ranges);
+        set.remove(removed);
+        set.iter().collect()
+    }
+
+    #[test]
+    fn queries() {
+        let set = set(&[1..=3, 6..=8]);
+        assert!(set.contains(1) && set.contains(7));
+        assert!(!set.contains(0) && !set.contains(4) && !set.contains(9));
+        assert!(set.contains_range(&(6..=8)));
+        assert!(!set.contains_range(&(3..=6)));
+        assert!(set.overlaps(&(3..=6)));
+        assert!(!set.overlaps(&(4..=5)));
+        assert_eq!(set.covered_len(), 6);
+        assert_eq!(set.covered_len_within(2..=6), 3);
+    }
+
+    #[test_case(1..=5, 2..=4 => (true, true) ; "nested")]
+    #[test_case(1..=5, 1..=5 => (true, true) ; "equal")]
+    #[test_case(1..=5, 5..=7 => (false, true) ; "sharing an end")]
+    #[test_case(1..=5, 6..=7 => (false, false) ; "abutting")]
+    #[test_case(1..=5, RangeInclusive::new(3, 2) => (true, false) ; "empty inner")]
+    #[test_case(RangeInclusive::new(9, 1), 3..=4 => (false, false) ; "empty outer")]
+    fn compares_ranges(outer: RangeInclusive<i32>, inner: RangeInclusive<i32>) -> (bool, bool) {
+        assert_eq!(overlap(&outer, &inner), overlap(&inner, &outer));
+        let set = IntervalSet::from(outer.clone());
+        assert_eq!(set.contains_range(&inner), covers(&outer, &inner));
+        assert_eq!(set.overlaps(&inner), overlap(&outer, &inner));
+        (covers(&outer, &inner), overlap(&outer, &inner))
+    }
+
+    #[test]
+    fn algebra() {
+        let a = set(&[0..=4, 10..=14]);
+        let b = set(&[3..=11]);
+        assert_eq!(a.union(&b).iter().collect_vec(), [0..=14]);
+        assert_eq!(a.intersection(&b).iter().collect_vec(), [3..=4, 10..=11]);
+        assert_eq!(a.difference(&b).iter().collect_vec(), [0..=2, 12..=14]);
+        assert_eq!(b.difference(&a).iter().collect_vec(), [5..=9]);
+    }
+
+    #[test_case(&[2..=3, 6..=6], 0..=9 => vec![0..=1, 4..=5, 7..=9])]
+    #[test_case(&[0..=3], 0..=3 => Vec::<RangeInclusive<i32>>::new())]
+    #[test_case(&[], 5..=7 => vec![5..=7])]
+    #[test_case(&[0..=9], RangeInclusive::new(3, 1) => Vec::<RangeInclusive<i32>>::new() ; "empty bounds")]
+    fn gaps(
+        ranges: &[RangeInclusive<i32>],
+        bounds: RangeInclusive<i32>,
+    ) -> Vec<RangeInclusive<i32>> {
+        let set = set(ranges);
+        assert_eq!(
+            set.complement(bounds.clone()).iter().collect_vec(),
+            set.gaps(bounds.clone()).collect_vec()
+        );
+        set.gaps(bounds).collect()
+    }
+
+    #[test]
+    fn extremes_do_not_overflow() {
+        let mut set: IntervalSet<u8> = [0..=100, 101..=255].into_iter().collect();
+        assert_eq!(set.iter().collect_vec(), [0..=255]);
+        assert_eq!(set.covered_len(), 256);
+        set.remove(255..=255);
+        set.remove(0..=0);
+        assert_eq!(set.iter().collect_vec(), [1..=254]);
+        assert_eq!(
+            IntervalSet::from(i64::MIN..=i64::MAX).covered_len(),
+            1 << 64
+        );
+    }
+
+    #[test]
+    fn counts_of_128_bit_sets_saturate() {
+        let mut set = IntervalSet::from(0..=u128::MAX);
+        assert_eq!(set.covered_len(), u128::MAX);
+        assert_eq!(set.covered_len_within(1..=u128::MAX), u128::MAX);
+        set.remove(7..=7);
+        assert_eq!(set.covered_len(), u128::MAX);
+        set.remove(u128::MAX..=u128::MAX);
+        assert_eq!(set.covered_len(), u128::MAX - 1);
+        set.insert(7..=7);
+        assert_eq!(set.covered_len(), u128::MAX);
+
+        let mut set = IntervalSet::from(i128::MIN..=i128::MAX);
+        assert_eq!(set.covered_len(), u128::MAX);
+        set.remove(-1..=0);
+        assert_eq!(set.covered_len(), u128::MAX - 1);
+        assert_eq!(set.iter().collect_vec(), [i128::MIN..=-2, 1..=i128::MAX]);
+    }
+}
@@ -1 +1,689 @@
+//! Solutions to the [Advent of Code 2022](https://adventofcode.com/2022) puzzles.
+//!
+//! Each day lives in its own module and implements [`Solution`]. The [`SOLUTIONS`] table erases
+//! the per-day types so that a single runner can dispatch on the day number.
 
+use std::{
+    convert::Infallible,
+    env, fmt, fs,
+    hint::black_box,
+    io::{self, BufRead, Read},
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use color_eyre::{eyre::WrapErr, Report, Result};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::Serialize;
+use tracing::{debug_span, info_span};
+
+use crate::{
+    params::{Overrides, Params},
+    parsing::ParseError,
+    render::Scene,
+    stream::Input,
+};
+
+pub mod batch;
+pub mod bench;
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+pub mod day09;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+#[cfg(test)]
+mod differential;
+pub mod geometry;
+pub mod grid;
+pub mod interval;
+pub mod params;
+pub mod parsing;
+pub mod regression;
+pub mod render;
+pub mod stream;
+pub mod trace;
+pub mod watch;
+
+/// A solver for a single day's puzzle.
+///
+/// The input is parsed once and the result shared between both parts.
+pub trait Solution {
+    /// The day of December on which the puzzle was released.
+    const DAY: u8;
+
+    /// The parsed puzzle input, which may borrow from the raw input.
+    type Parsed<'i>;
+
+    /// The answer to the first part of the puzzle.
+    type Part1: fmt::Display;
+
+    /// The answer to the second part of the puzzle.
+    type Part2: fmt::Display;
+
+    /// Why an input which parses can still have no answer.
+    type Error: PuzzleError;
+
+    /// The puzzle's constants which can be overridden, or [`NoParams`](params::NoParams).
+    type Params: Params;
+
+    /// Parses the raw puzzle input.
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, Error<Self::Error>>;
+
+    /// Parses the raw puzzle input for a puzzle with the given parameters.
+    ///
+    /// Days with parameters parse with the default parameters in [`parse`](Self::parse), and are
+    /// given them again to solve each part, so the parsed input holds only what was read.
+    fn parse_with<'i>(
+        input: &'i str,
+        _params: &Self::Params,
+    ) -> Result<Self::Parsed<'i>, Error<Self::Error>> {
+        Self::parse(input)
+    }
+
+    /// Solves the first part of the puzzle.
+    fn part1(parsed: &Self::Parsed<'_>) -> Result<Self::Part1, Error<Self::Error>>;
+
+    /// Solves the second part of the puzzle.
+    fn part2(parsed: &Self::Parsed<'_>) -> Result<Self::Part2, Error<Self::Error>>;
+
+    /// Solves the first part of a puzzle with the given parameters.
+    fn part1_with(
+        parsed: &Self::Parsed<'_>,
+        _params: &Self::Params,
+    ) -> Result<Self::Part1, Error<Self::Error>> {
+        Self::part1(parsed)
+    }
+
+    /// Solves the second part of a puzzle with the given parameters.
+    fn part2_with(
+        parsed: &Self::Parsed<'_>,
+        _params: &Self::Params,
+    ) -> Result<Self::Part2, Error<Self::Error>> {
+        Self::part2(parsed)
+    }
+
+    /// Solves both parts of the puzzle while reading the input, holding only as much of it as
+    /// needed, for the days whose input can be read a line or a byte at a time.
+    ///
+    /// Returns `None` for the days which need their whole input at once.
+    fn solve_streaming<R: BufRead>(_input: &mut Input<R>) -> StreamResult<Self> {
+        Ok(None)
+    }
+
+    /// Solves both parts of a puzzle with the given parameters while reading the input.
+    fn solve_streaming_with<R: BufRead>(
+        input: &mut Input<R>,
+        _params: &Self::Params,
+    ) -> StreamResult<Self> {
+        Self::solve_streaming(input)
+    }
+
+    /// Draws the state of the puzzle once solved, for the days which have something to show.
+    fn visualise(_parsed: &Self::Parsed<'_>) -> Result<Option<Scene>, Error<Self::Error>> {
+        Ok(None)
+    }
+
+    /// Draws the state of a puzzle with the given parameters once solved.
+    fn visualise_with(
+        parsed: &Self::Parsed<'_>,
+        _params: &Self::Params,
+    ) -> Result<Option<Scene>, Error<Self::Error>> {
+        Self::visualise(parsed)
+    }
+
+    /// Generates a random, valid puzzle input which grows with `size`.
+    ///
+    /// What `size` counts depends on the day, such as the number of elves or the width of a map.
+    fn generate<R: Rng>(rng: &mut R, size: usize) -> String;
+
+    /// Generates a random, valid puzzle input for a puzzle with the given parameters.
+    fn generate_with<R: Rng>(rng: &mut R, size: usize, _params: &Self::Params) -> String {
+        Self::generate(rng, size)
+    }
+}
+
+/// The errors specific to one day's puzzle.
+///
+/// Each day describes how its input can be well-formed yet unsolvable, such as a monkey throwing
+/// to one that doesn't exist. Days with no such inputs use [`Infallible`].
+pub trait PuzzleError: std::error::Error + Send + Sync + 'static {}
+
+impl PuzzleError for Infallible {}
+
+/// Why a puzzle input could not be solved.
+///
+/// Malformed input is reported the same way by every day, while the puzzle errors are specific to
+/// each day.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum Error<E> {
+    /// The input doesn't match the puzzle's format.
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    /// The input parses, but the puzzle has no answer for it.
+    #[error(transparent)]
+    Puzzle(E),
+}
+
+impl<E: PuzzleError> From<E> for Error<E> {
+    fn from(e: E) -> Self {
+        Self::Puzzle(e)
+    }
+}
+
+impl<E: PuzzleError> Error<E> {
+    /// Converts to a report of the underlying error, so that it can be downcast to either a
+    /// [`ParseError`] or the day's own error type.
+    pub fn into_report(self) -> Report {
+        match self {
+            Self::Parse(e) => Report::new(e),
+            Self::Puzzle(e) => Report::new(e),
+        }
+    }
+}
+
+/// The answers to both parts of a puzzle, or why it could not be solved.
+pub type SolveResult<S> =
+    Result<(<S as Solution>::Part1, <S as Solution>::Part2), Error<<S as Solution>::Error>>;
+
+/// The answers to both parts of a puzzle solved while reading its input, `None` if the day can't be
+/// solved that way, or why it could not be solved.
+pub type StreamResult<S> =
+    Result<Option<(<S as Solution>::Part1, <S as Solution>::Part2)>, Error<<S as Solution>::Error>>;
+
+/// Parses the input and solves both parts of the puzzle.
+pub fn solve<S: Solution>(input: &str) -> SolveResult<S> {
+    let parsed = S::parse(input)?;
+    let part1 = S::part1(&parsed)?;
+    let part2 = S::part2(&parsed)?;
+    Ok((part1, part2))
+}
+
+/// The rendered answers to both parts of a puzzle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Answers {
+    pub part1: String,
+    pub part2: String,
+}
+
+impl fmt::Display for Answers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Part 1: \n{}\n\nPart 2: \n{}", self.part1, self.part2)
+    }
+}
+
+/// Which parts of a puzzle to solve.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Parts {
+    One,
+    Two,
+    #[default]
+    Both,
+}
+
+impl Parts {
+    /// Whether part 1 is to be solved.
+    pub fn part1(self) -> bool {
+        self != Self::Two
+    }
+
+    /// Whether part 2 is to be solved.
+    pub fn part2(self) -> bool {
+        self != Self::One
+    }
+}
+
+impl FromStr for Parts {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "1" => Ok(Self::One),
+            "2" => Ok(Self::Two),
+            "both" => Ok(Self::Both),
+            _ => Err(format!("expected `1`, `2` or `both`, found `{s}`")),
+        }
+    }
+}
+
+impl fmt::Display for Parts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::One => "1",
+            Self::Two => "2",
+            Self::Both => "both",
+        })
+    }
+}
+
+/// The rendered answers to the parts of a puzzle which were solved.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PartAnswers {
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+impl From<Answers> for PartAnswers {
+    fn from(answers: Answers) -> Self {
+        Self {
+            part1: Some(answers.part1),
+            part2: Some(answers.part2),
+        }
+    }
+}
+
+impl fmt::Display for PartAnswers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts = [(1, &self.part1), (2, &self.part2)];
+        let solved = parts
+            .iter()
+            .filter_map(|(part, answer)| Some((part, answer.as_ref()?)));
+        for (idx, (part, answer)) in solved.enumerate() {
+            if idx > 0 {
+                write!(f, "\n\n")?;
+            }
+            write!(f, "Part {part}: \n{answer}")?;
+        }
+        Ok(())
+    }
+}
+
+/// How long each phase of solving a puzzle took.
+///
+/// Serialised as whole nanoseconds.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Timings {
+    #[serde(with = "bench::nanos")]
+    pub parse: Duration,
+    #[serde(with = "bench::nanos")]
+    pub part1: Duration,
+    #[serde(with = "bench::nanos")]
+    pub part2: Duration,
+}
+
+impl Timings {
+    /// The time taken by all three phases.
+    pub fn total(&self) -> Duration {
+        self.parse + self.part1 + self.part2
+    }
+}
+
+/// A type-erased [`Solution`], allowing days to be selected at runtime.
+pub trait Runner: Sync {
+    /// The day of December on which the puzzle was released.
+    fn day(&self) -> u8;
+
+    /// Parses the input and solves both parts of the puzzle, timing each phase.
+    ///
+    /// The answers are rendered after timing, so that formatting isn't counted.
+    fn run_timed(&self, input: &str) -> Result<(Answers, Timings)> {
+        let (answers, timings) = self.run_parts_timed(input, Parts::Both)?;
+        let solved = |answer: Option<String>| answer.expect("both parts were solved");
+        let answers = Answers {
+            part1: solved(answers.part1),
+            part2: solved(answers.part2),
+        };
+        Ok((answers, timings))
+    }
+
+    /// Parses the input once and solves only the selected parts, timing each phase.
+    ///
+    /// A part which isn't solved takes no time.
+    fn run_parts_timed(&self, input: &str, parts: Parts) -> Result<(PartAnswers, Timings)>;
+
+    /// Parses the input and solves both parts of the puzzle.
+    fn run(&self, input: &str) -> Result<Answers> {
+        self.run_timed(input).map(|(answers, _)| answers)
+    }
+
+    /// Solves both parts of the puzzle while reading the input, if the day supports it.
+    ///
+    /// An I/O error which cut the input short is reported in preference to any failure it caused.
+    fn run_streaming(&self, input: &mut dyn BufRead) -> Result<Option<Answers>>;
+
+    /// Parses the input and draws the state of the puzzle once solved, if the day supports it.
+    fn visualise(&self, input: &str) -> Result<Option<Scene>>;
+
+    /// Generates a random, valid puzzle input which grows with `size`.
+    ///
+    /// The same seed and size always give the same input.
+    fn generate(&self, seed: u64, size: usize) -> String;
+
+    /// A runner for the same day with some of its parameters overridden.
+    ///
+    /// Fails if the day has no such parameters, or the values don't suit them.
+    fn configure(&self, overrides: &Overrides) -> Result<Box<dyn Runner>>;
+
+    /// The parameters overridden so far, by name.
+    fn overrides(&self) -> Overrides;
+}
+
+impl<S: Solution + Sync + 'static> Runner for S {
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn run_parts_timed(&self, input: &str, parts: Parts) -> Result<(PartAnswers, Timings)> {
+        Configured::<S>::default().run_parts_timed(input, parts)
+    }
+
+    fn run_streaming(&self, input: &mut dyn BufRead) -> Result<Option<Answers>> {
+        Configured::<S>::default().run_streaming(input)
+    }
+
+    fn visualise(&self, input: &str) -> Result<Option<Scene>> {
+        Configured::<S>::default().visualise(input)
+    }
+
+    fn generate(&self, seed: u64, size: usize) -> String {
+        Configured::<S>::default().generate(seed, size)
+    }
+
+    fn configure(&self, overrides: &Overrides) -> Result<Box<dyn Runner>> {
+        Configured::<S>::default().configure(overrides)
+    }
+
+    fn overrides(&self) -> Overrides {
+        Overrides::new()
+    }
+}
+
+/// A [`Solution`] with some of its parameters overridden.
+struct Configured<S: Solution> {
+    params: S::Params,
+    overrides: Overrides,
+    solution: PhantomData<fn() -> S>,
+}
+
+impl<S: Solution> Default for Configured<S> {
+    fn default() -> Self {
+        Self {
+            params: S::Params::default(),
+            overrides: Overrides::new(),
+            solution: PhantomData,
+        }
+    }
+}
+
+impl<S: Solution + 'static> Runner for Configured<S> {
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn run_parts_timed(&self, input: &str, parts: Parts) -> Result<(PartAnswers, Timings)> {
+        let _solve = info_span!("solve", day = S::DAY).entered();
+
+        let start = Instant::now();
+        let parsed = debug_span!("parse").in_scope(|| {
+            black_box(S::parse_with(black_box(input), &self.params).map_err(Error::into_report))
+        })?;
+        let parse = start.elapsed();
+
+        let mut part1_time = Duration::ZERO;
+        let part1 = if parts.part1() {
+            let start = Instant::now();
+            let part1 = debug_span!("part1").in_scope(|| {
+                black_box(S::part1_with(&parsed, &self.params).map_err(Error::into_report))
+            })?;
+            part1_time = start.elapsed();
+            Some(part1)
+        } else {
+            None
+        };
+
+        let mut part2_time = Duration::ZERO;
+        let part2 = if parts.part2() {
+            let start = Instant::now();
+            let part2 = debug_span!("part2").in_scope(|| {
+                black_box(S::part2_with(&parsed, &self.params).map_err(Error::into_report))
+            })?;
+            part2_time = start.elapsed();
+            Some(part2)
+        } else {
+            None
+        };
+
+        let answers = PartAnswers {
+            part1: part1.map(|answer| answer.to_string()),
+            part2: part2.map(|answer| answer.to_string()),
+        };
+        let timings = Timings {
+            parse,
+            part1: part1_time,
+            part2: part2_time,
+        };
+        Ok((answers, timings))
+    }
+
+    fn run_streaming(&self, input: &mut dyn BufRead) -> Result<Option<Answers>> {
+        let _solve = info_span!("solve_streaming", day = S::DAY).entered();
+        let mut input = Input::new(input);
+        let solved = S::solve_streaming_with(&mut input, &self.params);
+        input.finish()?;
+        let answers = solved.map_err(Error::into_report)?;
+        Ok(answers.map(|(part1, part2)| Answers {
+            part1: part1.to_string(),
+            part2: part2.to_string(),
+        }))
+    }
+
+    fn visualise(&self, input: &str) -> Result<Option<Scene>> {
+        let _visualise = info_span!("visualise", day = S::DAY).entered();
+        let parsed = S::parse_with(input, &self.params).map_err(Error::into_report)?;
+        S::visualise_with(&parsed, &self.params).map_err(Error::into_report)
+    }
+
+    fn generate(&self, seed: u64, size: usize) -> String {
+        S::generate_with(&mut ChaCha8Rng::seed_from_u64(seed), size, &self.params)
+    }
+
+    fn configure(&self, overrides: &Overrides) -> Result<Box<dyn Runner>> {
+        let params = params::apply(&self.params, overrides)
+            .wrap_err_with(|| format!("configuring day {}", S::DAY))?;
+        let mut all_overrides = self.overrides.clone();
+        all_overrides.extend(overrides.clone());
+        Ok(Box::new(Self {
+            params,
+            overrides: all_overrides,
+            solution: PhantomData,
+        }))
+    }
+
+    fn overrides(&self) -> Overrides {
+        self.overrides.clone()
+    }
+}
+
+/// Every implemented day, in order.
+pub static SOLUTIONS: &[&dyn Runner] = &[
+    &day01::Day01,
+    &day02::Day02,
+    &day03::Day03,
+    &day04::Day04,
+    &day05::Day05,
+    &day06::Day06,
+    &day07::Day07,
+    &day08::Day08,
+    &day09::Day09,
+    &day10::Day10,
+    &day11::Day11,
+    &day12::Day12,
+    &day13::Day13,
+    &day14::Day14,
+    &day15::Day15::<2_000_000>,
+];
+
+/// Looks up the solution for the given day.
+pub fn solution(day: u8) -> Option<&'static dyn Runner> {
+    SOLUTIONS.iter().copied().find(|s| s.day() == day)
+}
+
+/// The path of the puzzle input for the given day, relative to the crate root.
+pub fn default_input_path(day: u8) -> PathBuf {
+    PathBuf::from(format!("input/day{day:02}"))
+}
+
+/// Reads the puzzle input from the given path, or from stdin if no path is given.
+pub fn read_input(path: Option<&Path>) -> Result<String> {
+    let input = if let Some(path) = path {
+        fs::read_to_string(path)?
+    } else {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        input
+    };
+    Ok(input)
+}
+
+/// Entry point shared by the single-day binaries.
+///
+/// Reads the input from the path given as the first argument, or from stdin, and prints both
+/// answers.
+pub fn run_day_binary<S: Solution + Sync + 'static>(solution: S) -> Result<()> {
+    color_eyre::install()?;
+
+    let path = env::args_os().nth(1).map(PathBuf::from);
+    let input = read_input(path.as_deref())?;
+    let answers = solution.run(&input).map_err(|e| match &path {
+        Some(path) => parsing::name_source(e, path.display()),
+        None => parsing::name_source(e, "<stdin>"),
+    })?;
+
+    println!("{answers}");
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test_case(0)]
+    #[test_case(1)]
+    #[test_case(2)]
+    fn generated_inputs_are_solvable(seed: u64) {
+        for solution in SOLUTIONS.iter().map(|&s| small(s)) {
+            let input = solution.generate(seed, 50);
+            if let Err(e) = solution.run(&input) {
+                panic!(
+                    "day {} failed on seed {seed}: {e:?}\n{input}",
+                    solution.day()
+                );
+            }
+        }
+    }
+
+    /// The solution, with day 15's search area shrunk as its real one is too slow to search in
+    /// unoptimised tests.
+    fn small(solution: &dyn Runner) -> Box<dyn Runner> {
+        let overrides = match solution.day() {
+            15 => params::overrides(&["row=10", "search_limit=20"]),
+            _ => Overrides::new(),
+        };
+        solution.configure(&overrides).unwrap()
+    }
+
+    #[test]
+    fn streamed_inputs_give_the_same_answers() {
+        let streamed = SOLUTIONS
+            .iter()
+            .map(|&s| small(s))
+            .filter_map(|solution| {
+                let input = solution.generate(3, 50);
+                let answers = solution.run_streaming(&mut input.as_bytes()).unwrap()?;
+                assert_eq!(
+                    answers,
+                    solution.run(&input).unwrap(),
+                    "day {}",
+                    solution.day()
+                );
+                Some(solution.day())
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(streamed, [1, 2, 3, 4, 5, 6, 9, 15]);
+    }
+
+    #[test]
+    fn parts_are_solved_independently() {
+        for solution in SOLUTIONS.iter().map(|&s| small(s)) {
+            let input = solution.generate(4, 30);
+            let both = PartAnswers::from(solution.run(&input).unwrap());
+            let (part1, timings) = solution.run_parts_timed(&input, Parts::One).unwrap();
+            assert_eq!((part1.part1, part1.part2), (both.part1, None));
+            assert_eq!(timings.part2, Duration::ZERO);
+            let (part2, timings) = solution.run_parts_timed(&input, Parts::Two).unwrap();
+            assert_eq!((part2.part1, part2.part2), (None, both.part2));
+            assert_eq!(timings.part1, Duration::ZERO);
+        }
+    }
+
+    #[test_case("1" => Ok(Parts::One))]
+    #[test_case("2" => Ok(Parts::Two))]
+    #[test_case("Both" => Ok(Parts::Both))]
+    #[test_case("3" => Err("expected `1`, `2` or `both`, found `3`".to_owned()))]
+    fn parses_parts(s: &str) -> Result<Parts, String> {
+        s.parse()
+    }
+
+    #[test]
+    fn shows_only_the_solved_parts() {
+        let answers = PartAnswers {
+            part1: None,
+            part2: Some("42".to_owned()),
+        };
+        assert_eq!(answers.to_string(), "Part 2: \n42");
+    }
+
+    #[test]
+    fn streaming_reports_io_errors_first() {
+        let mut input = &b"ab\ncd\n\xff\n"[..];
+        let error = day03::Day03.run_streaming(&mut input).unwrap_err();
+        assert!(error.downcast_ref::<io::Error>().is_some(), "{error:?}");
+    }
+
+    #[test]
+    fn configured_runners_keep_their_overrides() {
+        let solution = day11::Day11
+            .configure(&params::overrides(&["part1_rounds=5"]))
+            .unwrap()
+            .configure(&params::overrides(&["part2_rounds=6"]))
+            .unwrap();
+        assert_eq!(
+            params::describe(&solution.overrides()),
+            "part1_rounds = 5, part2_rounds = 6"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_parameters() {
+        let Err(error) = day03::Day03.configure(&params::overrides(&["rounds=3"])) else {
+            panic!("expected day 3 to have no parameters");
+        };
+        assert_eq!(
+            format!("{error:#}"),
+            "configuring day 3: there are no parameters to override, found `rounds`"
+        );
+    }
+
+    #[test]
+    fn generated_inputs_are_reproducible() {
+        for solution in SOLUTIONS {
+            assert_eq!(solution.generate(7, 20), solution.generate(7, 20));
+            assert_ne!(solution.generate(7, 20), solution.generate(8, 20));
+        }
+    }
+}
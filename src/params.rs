@@ -0,0 +1,232 @@
+//! Overrides for the constants baked into the puzzles, such as the size of day 7's disk.
+//!
+//! Each day lists what can be overridden in its [`Params`], and [`Runner::configure`] applies
+//! overrides given by name, as they are read from the command line or a config file. A config file
+//! has a table of overrides per day:
+//!
+//! ```toml
+//! [day15]
+//! row = 10
+//! search_limit = 20
+//! ```
+//!
+//! [`Runner::configure`]: crate::Runner::configure
+
+use std::{collections::BTreeMap, fmt::Debug, fs, path::Path};
+
+use color_eyre::{
+    eyre::{bail, eyre, WrapErr},
+    Result,
+};
+use itertools::Itertools;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// The constants of a day's puzzle which can be overridden, defaulting to the puzzle's own.
+///
+/// Each field can be overridden by its name, so the fields must serialise as a flat table.
+pub trait Params:
+    Clone + Debug + Default + Serialize + DeserializeOwned + Send + Sync + 'static
+{
+    /// Checks that the parameters make sense together, describing the problem if not.
+    fn validate(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// The parameters of a day which has nothing to override.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NoParams {}
+
+impl Params for NoParams {}
+
+/// Parameter values to override, by name.
+pub type Overrides = toml::value::Table;
+
+/// Applies the overrides to `params`, checking that every name is a parameter, every value has
+/// the right type, and that the result is valid.
+pub fn apply<P: Params>(params: &P, overrides: &Overrides) -> Result<P> {
+    let toml::Value::Table(mut table) = toml::Value::try_from(params)? else {
+        unreachable!("parameters are serialised as a table");
+    };
+    for (name, value) in overrides {
+        if !table.contains_key(name) {
+            if table.is_empty() {
+                bail!("there are no parameters to override, found `{name}`");
+            }
+            bail!(
+                "unknown parameter `{name}`, expected one of {}",
+                table.keys().map(|name| format!("`{name}`")).join(", ")
+            );
+        }
+        table.insert(name.clone(), value.clone());
+    }
+    let params = toml::Value::Table(table)
+        .try_into::<P>()
+        .wrap_err("invalid parameter")?;
+    params.validate().map_err(|e| eyre!(e))?;
+    Ok(params)
+}
+
+/// Parses an override given as `NAME=VALUE`.
+///
+/// The value is read as TOML, so `row=10` gives a number, falling back to a string for values
+/// which aren't valid TOML, such as `name=abc`.
+pub fn parse_override(s: &str) -> Result<(String, toml::Value), String> {
+    let (name, value) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected `NAME=VALUE`, found `{s}`"))?;
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(format!("expected a parameter name before `=` in `{s}`"));
+    }
+    let value = value.trim();
+    let value = toml::from_str::<Overrides>(&format!("value = {value}"))
+        .ok()
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| toml::Value::String(value.to_owned()));
+    Ok((name.to_owned(), value))
+}
+
+/// Parses overrides given as `NAME=VALUE`, for tests.
+#[cfg(test)]
+pub(crate) fn overrides(pairs: &[&str]) -> Overrides {
+    pairs
+        .iter()
+        .map(|pair| parse_override(pair).unwrap())
+        .collect()
+}
+
+/// Describes the overrides as `name = value` pairs, in name order.
+pub fn describe(overrides: &Overrides) -> String {
+    overrides
+        .iter()
+        .map(|(name, value)| match value {
+            toml::Value::String(s) => format!("{name} = {s:?}"),
+            value => format!("{name} = {value}"),
+        })
+        .join(", ")
+}
+
+/// Overrides for any of the days, as read from a config file.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Config {
+    days: BTreeMap<u8, Overrides>,
+}
+
+impl Config {
+    /// Reads a config file, with a `[dayNN]` table of overrides for each day.
+    pub fn read(path: &Path) -> Result<Self> {
+        let text =
+            fs::read_to_string(path).wrap_err_with(|| format!("reading {}", path.display()))?;
+        Self::parse(&text).wrap_err_with(|| format!("parsing {}", path.display()))
+    }
+
+    fn parse(text: &str) -> Result<Self> {
+        let tables = toml::from_str::<BTreeMap<String, Overrides>>(text)?;
+        let mut days = BTreeMap::new();
+        for (name, overrides) in tables {
+            let day = name
+                .strip_prefix("day")
+                .and_then(|day| day.parse::<u8>().ok())
+                .filter(|&day| crate::solution(day).is_some())
+                .ok_or_else(|| {
+                    eyre!("expected a table named after a solved day, found `{name}`")
+                })?;
+            if days.insert(day, overrides).is_some() {
+                bail!("day {day} is configured twice");
+            }
+        }
+        Ok(Self { days })
+    }
+
+    /// The overrides for the given day, with `extra` taking precedence over the file's.
+    pub fn overrides(&self, day: u8, extra: &Overrides) -> Overrides {
+        let mut overrides = self.days.get(&day).cloned().unwrap_or_default();
+        overrides.extend(extra.clone());
+        overrides
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct Example {
+        rounds: usize,
+        factor: i64,
+    }
+
+    impl Default for Example {
+        fn default() -> Self {
+            Self {
+                rounds: 20,
+                factor: 3,
+            }
+        }
+    }
+
+    impl Params for Example {
+        fn validate(&self) -> Result<(), String> {
+            if self.factor < 1 {
+                return Err("the factor must be at least 1".to_owned());
+            }
+            Ok(())
+        }
+    }
+
+    #[test_case(&[] => Ok(Example::default()) ; "none")]
+    #[test_case(&["rounds=5"] => Ok(Example { rounds: 5, factor: 3 }) ; "one")]
+    #[test_case(&["factor = 2", "rounds=1"] => Ok(Example { rounds: 1, factor: 2 }) ; "several")]
+    #[test_case(&["steps=5"] => Err("unknown parameter `steps`, expected one of `factor`, `rounds`".to_owned()) ; "unknown")]
+    #[test_case(&["rounds=many"] => matches Err(e) if e.starts_with("invalid parameter") ; "wrong type")]
+    #[test_case(&["factor=0"] => Err("the factor must be at least 1".to_owned()) ; "invalid")]
+    fn applies_overrides(pairs: &[&str]) -> Result<Example, String> {
+        apply(&Example::default(), &overrides(pairs)).map_err(|e| format!("{e:#}"))
+    }
+
+    #[test]
+    fn days_without_parameters_reject_overrides() {
+        let error = apply(&NoParams {}, &overrides(&["rounds=5"])).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "there are no parameters to override, found `rounds`"
+        );
+    }
+
+    #[test_case("row=10" => Ok(("row".to_owned(), toml::Value::Integer(10))) ; "number")]
+    #[test_case("name=abc" => Ok(("name".to_owned(), toml::Value::String("abc".to_owned()))) ; "bare string")]
+    #[test_case("flag=true" => Ok(("flag".to_owned(), toml::Value::Boolean(true))) ; "boolean")]
+    #[test_case("row" => Err("expected `NAME=VALUE`, found `row`".to_owned()) ; "no value")]
+    #[test_case("=10" => Err("expected a parameter name before `=` in `=10`".to_owned()) ; "no name")]
+    fn parses_overrides(s: &str) -> Result<(String, toml::Value), String> {
+        parse_override(s)
+    }
+
+    #[test]
+    fn describes_overrides_in_name_order() {
+        assert_eq!(
+            describe(&overrides(&["rounds=5", "name=abc"])),
+            "name = \"abc\", rounds = 5"
+        );
+    }
+
+    #[test]
+    fn command_line_overrides_take_precedence_over_the_config() {
+        let config = Config::parse("[day11]\npart1_rounds = 5\npart2_rounds = 6\n").unwrap();
+        let overrides = config.overrides(11, &overrides(&["part2_rounds=7"]));
+        assert_eq!(describe(&overrides), "part1_rounds = 5, part2_rounds = 7");
+        assert!(config.overrides(7, &Overrides::new()).is_empty());
+    }
+
+    #[test_case("[monkeys]\nrounds = 5\n" ; "not a day")]
+    #[test_case("[day99]\nrounds = 5\n" ; "unsolved day")]
+    #[test_case("rounds = 5\n" ; "not a table")]
+    fn rejects_bad_configs(text: &str) {
+        assert!(Config::parse(text).is_err());
+    }
+}
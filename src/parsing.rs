@@ -0,0 +1,557 @@
+//! Shared error reporting for the puzzle input parsers.
+//!
+//! The nom parsers use [`Error`], which records what was expected where and the grammar
+//! [`context`](nom::error::context) it was expected in. Use [`tag`] rather than nom's so that the
+//! expected text appears in the message, and [`number`] rather than nom's integer parsers so that
+//! a number too large for its type is reported as such. [`parse_all`] turns a failure into a
+//! [`ParseError`], which renders the line and column of the failure with a caret under the
+//! offending input:
+//!
+//! ```text
+//! in crate move, expected ` from `, found ` fro`
+//!  --> input/day05:12:7
+//!    |
+//! 12 | move 1 fro 2 to 3
+//!    |       ^
+//! ```
+
+use std::{any, error, fmt, str::FromStr};
+
+use color_eyre::Report;
+use nom::{
+    branch::alt,
+    character::complete::{digit1, line_ending},
+    combinator::{all_consuming, eof, map},
+    error::{ContextError, ErrorKind, FromExternalError},
+    multi::many_till,
+    sequence::terminated,
+    Finish,
+};
+use num::PrimInt;
+
+/// How many bytes of a line either side of a failure are shown in its report, so that a failure in
+/// a very long line doesn't print the whole line.
+pub const EXCERPT_LEN: usize = 32;
+
+/// The result of a parser using [`Error`].
+pub type IResult<'i, O> = nom::IResult<&'i str, O, Error<'i>>;
+
+/// A parse failure, borrowing the input it failed at.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Error<'i> {
+    input: &'i str,
+    expected: Vec<Expected>,
+    /// The grammar rules being parsed, innermost first.
+    context: Vec<&'static str>,
+}
+
+/// Something a parser expected to find.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Expected {
+    /// Literal text, from [`tag`].
+    Tag(&'static str),
+    /// One of the symbols given to [`symbol`].
+    Symbol(String),
+    Char(char),
+    /// Input matching one of nom's built-in parsers.
+    Kind(ErrorKind),
+    /// A value which failed to convert, with the reason.
+    Invalid(String),
+    /// A number too large or small for the named type, from [`number`].
+    OutOfRange(&'static str),
+}
+
+impl fmt::Display for Expected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tag(tag) => write!(f, "`{}`", tag.escape_debug()),
+            Self::Symbol(symbol) => write!(f, "`{}`", symbol.escape_debug()),
+            Self::Char(c) => write!(f, "`{}`", c.escape_debug()),
+            Self::Kind(kind) => f.write_str(describe_kind(kind)),
+            Self::Invalid(reason) => write!(f, "a valid value ({reason})"),
+            Self::OutOfRange(ty) => write!(f, "a number that fits in {ty}"),
+        }
+    }
+}
+
+fn describe_kind(kind: &ErrorKind) -> &str {
+    match kind {
+        ErrorKind::Digit => "a number",
+        ErrorKind::Alpha => "a letter",
+        ErrorKind::AlphaNumeric => "a letter or digit",
+        ErrorKind::Space | ErrorKind::MultiSpace => "whitespace",
+        ErrorKind::CrLf => "a line break",
+        ErrorKind::Eof => "the end of the input",
+        ErrorKind::Char => "a character",
+        _ => kind.description(),
+    }
+}
+
+impl<'i> Error<'i> {
+    fn new(input: &'i str, expected: Expected) -> Self {
+        Self {
+            input,
+            expected: vec![expected],
+            context: Vec::new(),
+        }
+    }
+}
+
+impl<'i> nom::error::ParseError<&'i str> for Error<'i> {
+    fn from_error_kind(input: &'i str, kind: ErrorKind) -> Self {
+        Self::new(input, Expected::Kind(kind))
+    }
+
+    fn append(_: &'i str, _: ErrorKind, other: Self) -> Self {
+        other
+    }
+
+    fn from_char(input: &'i str, c: char) -> Self {
+        Self::new(input, Expected::Char(c))
+    }
+
+    /// Keeps whichever alternative got furthest, merging the expectations of alternatives which
+    /// failed at the same place.
+    fn or(mut self, other: Self) -> Self {
+        match self.input.len().cmp(&other.input.len()) {
+            std::cmp::Ordering::Less => self,
+            std::cmp::Ordering::Greater => other,
+            std::cmp::Ordering::Equal => {
+                for expected in other.expected {
+                    if !self.expected.contains(&expected) {
+                        self.expected.push(expected);
+                    }
+                }
+                if self.context.is_empty() {
+                    self.context = other.context;
+                }
+                self
+            }
+        }
+    }
+}
+
+impl<'i> ContextError<&'i str> for Error<'i> {
+    fn add_context(_: &'i str, context: &'static str, mut other: Self) -> Self {
+        other.context.push(context);
+        other
+    }
+}
+
+impl<'i, E: fmt::Display> FromExternalError<&'i str, E> for Error<'i> {
+    fn from_external_error(input: &'i str, _: ErrorKind, e: E) -> Self {
+        Self::new(input, Expected::Invalid(e.to_string()))
+    }
+}
+
+/// Recognises the given text, reporting it as expected if it is missing.
+pub fn tag<'i>(tag: &'static str) -> impl Fn(&'i str) -> IResult<'i, &'i str> {
+    move |s| {
+        nom::bytes::complete::tag::<_, _, Error<'i>>(tag)(s)
+            .map_err(|e| e.map(|_| Error::new(s, Expected::Tag(tag))))
+    }
+}
+
+/// Recognises the longest of the given symbols, returning the value it stands for, and reporting
+/// them all as expected if none is there.
+pub fn symbol<'i, 's, T: Clone>(
+    symbols: &'s [(String, T)],
+) -> impl Fn(&'i str) -> IResult<'i, T> + 's {
+    move |s| {
+        let found = symbols
+            .iter()
+            .filter(|(symbol, _)| s.starts_with(symbol.as_str()))
+            .max_by_key(|(symbol, _)| symbol.len());
+        match found {
+            Some((symbol, value)) => Ok((&s[symbol.len()..], value.clone())),
+            None => Err(nom::Err::Error(Error {
+                input: s,
+                expected: symbols
+                    .iter()
+                    .map(|(symbol, _)| Expected::Symbol(symbol.clone()))
+                    .collect(),
+                context: Vec::new(),
+            })),
+        }
+    }
+}
+
+/// Recognises a decimal number of type `T`, which may have a sign if `T` is signed.
+///
+/// Digits which don't fit in `T` are reported as out of range, rather than as not being a number.
+pub fn number<'i, T: PrimInt + FromStr>(s: &'i str) -> IResult<'i, T> {
+    let sign = usize::from(T::min_value() < T::zero() && s.starts_with(['+', '-']));
+    let (rest, _) = digit1(&s[sign..])?;
+    match s[..s.len() - rest.len()].parse() {
+        Ok(n) => Ok((rest, n)),
+        Err(_) => Err(nom::Err::Error(Error::new(
+            s,
+            Expected::OutOfRange(any::type_name::<T>()),
+        ))),
+    }
+}
+
+/// Applies `line` to every line of the input, each ended by a line break or the end of the input.
+///
+/// Unlike `many0`, a line which fails to parse is reported, rather than ending the list early.
+pub fn lines<'i, O>(
+    line: impl FnMut(&'i str) -> IResult<'i, O>,
+) -> impl FnMut(&'i str) -> IResult<'i, Vec<O>> {
+    map(
+        many_till(terminated(line, alt((line_ending, eof))), eof),
+        |(lines, _)| lines,
+    )
+}
+
+/// Applies `parser` to the whole of `input`, failing if any input is left over.
+pub fn parse_all<'i, O>(
+    input: &'i str,
+    parser: impl FnMut(&'i str) -> IResult<'i, O>,
+) -> Result<O, ParseError> {
+    all_consuming(parser)(input)
+        .finish()
+        .map(|(_, output)| output)
+        .map_err(|e| ParseError::from_nom(input, e))
+}
+
+/// A parse failure located within the input, ready to be shown to the user.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    source_name: Option<String>,
+    line: usize,
+    column: usize,
+    line_text: String,
+    /// The column of `line_text` to draw the caret under, which differs from `column` when only an
+    /// excerpt of the line is shown.
+    caret: usize,
+    message: String,
+    context: Vec<&'static str>,
+}
+
+impl ParseError {
+    /// A failure at `at`, which must be a slice of `source`, described by `message`.
+    ///
+    /// Only a window of the line around the failure is shown, as [`in_excerpt`](Self::in_excerpt)
+    /// shows it.
+    pub fn new(source: &str, at: &str, message: impl Into<String>) -> Self {
+        let offset = offset_in(source, at);
+        let line_start = source[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = source[offset..]
+            .find('\n')
+            .map_or(source.len(), |i| offset + i);
+        let line_text = source[line_start..line_end].trim_end_matches('\r');
+        let at_in_line = (offset - line_start).min(line_text.len());
+        let mut before_start = at_in_line.saturating_sub(EXCERPT_LEN);
+        while !line_text.is_char_boundary(before_start) {
+            before_start += 1;
+        }
+        Self::in_excerpt(
+            source[..offset].matches('\n').count() + 1,
+            source[line_start..offset].chars().count() + 1,
+            &line_text[before_start..at_in_line],
+            &line_text[at_in_line..],
+            message,
+        )
+    }
+
+    /// A failure at the given line and column, described by `message`, showing only a window of
+    /// the line around it.
+    ///
+    /// `before` is the text of the line leading up to the failure, and is marked as cut short if it
+    /// doesn't reach back to the start of the line. `rest` is the text from the failure to the end
+    /// of the line, or at least [`EXCERPT_LEN`] bytes of it, and is cut short after that many.
+    pub fn in_excerpt(
+        line: usize,
+        column: usize,
+        before: &str,
+        rest: &str,
+        message: impl Into<String>,
+    ) -> Self {
+        let cut = if before.chars().count() + 1 < column {
+            "…"
+        } else {
+            ""
+        };
+        let (rest, rest_cut) = if rest.len() > EXCERPT_LEN {
+            let mut end = EXCERPT_LEN;
+            while !rest.is_char_boundary(end) {
+                end -= 1;
+            }
+            (&rest[..end], "…")
+        } else {
+            (rest, "")
+        };
+        Self {
+            source_name: None,
+            line,
+            column,
+            line_text: format!("{cut}{before}{rest}{rest_cut}"),
+            caret: cut.chars().count() + before.chars().count() + 1,
+            message: message.into(),
+            context: Vec::new(),
+        }
+    }
+
+    /// A failure at `at`, which must be a slice of `source`, where `expected` was expected.
+    pub fn expected(source: &str, at: &str, expected: impl fmt::Display) -> Self {
+        Self::new(
+            source,
+            at,
+            format!("expected {expected}, found {}", found(at)),
+        )
+    }
+
+    /// Locates a nom failure within `source`, the input the parser was first applied to.
+    pub fn from_nom(source: &str, error: Error<'_>) -> Self {
+        let Error {
+            input,
+            expected,
+            context,
+        } = error;
+        let (invalid, expected): (Vec<_>, Vec<_>) = expected
+            .into_iter()
+            .partition(|e| matches!(e, Expected::Invalid(_) | Expected::OutOfRange(_)));
+        let message = match invalid.first() {
+            Some(Expected::Invalid(reason)) => format!("{} is not valid: {reason}", found(input)),
+            Some(Expected::OutOfRange(ty)) => {
+                let len = input
+                    .char_indices()
+                    .find(|&(i, c)| !(c.is_ascii_digit() || i == 0 && matches!(c, '+' | '-')))
+                    .map_or(input.len(), |(i, _)| i);
+                format!("number out of range for {ty}, found `{}`", &input[..len])
+            }
+            _ => format!(
+                "expected {}, found {}",
+                join_alternatives(&expected),
+                found(input)
+            ),
+        };
+        Self {
+            context,
+            ..Self::new(source, input, message)
+        }
+    }
+
+    /// Moves the failure to the given line, for input parsed a line at a time.
+    pub fn on_line(mut self, line: usize) -> Self {
+        self.line = line;
+        self
+    }
+
+    /// Names the file the input was read from, for the location line.
+    pub fn with_source_name(mut self, name: impl fmt::Display) -> Self {
+        self.source_name = Some(name.to_string());
+        self
+    }
+
+    /// Adds an outer grammar rule which was being parsed.
+    pub fn in_context(mut self, context: &'static str) -> Self {
+        self.context.push(context);
+        self
+    }
+
+    /// The line of the failure, counting from 1.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// The column of the failure in characters, counting from 1.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The grammar rules being parsed, innermost first.
+    pub fn context(&self) -> &[&'static str] {
+        &self.context
+    }
+}
+
+/// Names the source of any [`ParseError`] in the report.
+pub fn name_source(mut report: Report, name: impl fmt::Display) -> Report {
+    if let Some(error) = report.downcast_mut::<ParseError>() {
+        error.source_name = Some(name.to_string());
+    }
+    report
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for context in &self.context {
+            write!(f, "in {context}, ")?;
+        }
+        writeln!(f, "{}", self.message)?;
+
+        let gutter = self.line.to_string().len();
+        let name = self.source_name.as_deref().unwrap_or("<input>");
+        writeln!(f, "{:gutter$}--> {name}:{}:{}", "", self.line, self.column)?;
+        writeln!(f, "{:gutter$} |", "")?;
+        writeln!(f, "{} | {}", self.line, self.line_text)?;
+        write!(f, "{:gutter$} | {:>caret$}", "", "^", caret = self.caret)
+    }
+}
+
+impl error::Error for ParseError {}
+
+/// The byte offset of `at` within `source`, falling back to treating `at` as a suffix.
+fn offset_in(source: &str, at: &str) -> usize {
+    let start = source.as_ptr() as usize;
+    let position = at.as_ptr() as usize;
+    if (start..=start + source.len()).contains(&position) {
+        position - start
+    } else {
+        source.len().saturating_sub(at.len())
+    }
+}
+
+/// Describes the start of the remaining input: the next token, with any whitespace on the line
+/// before it.
+pub(crate) fn found(rest: &str) -> String {
+    match rest.chars().next() {
+        None => "the end of the input".to_owned(),
+        Some('\n' | '\r') => "the end of the line".to_owned(),
+        Some(_) => {
+            let token =
+                rest.trim_start_matches(|c: char| c.is_whitespace() && !matches!(c, '\n' | '\r'));
+            let end = token
+                .find(char::is_whitespace)
+                .map_or(rest.len(), |i| rest.len() - token.len() + i);
+            let token: String = rest[..end]
+                .chars()
+                .take(16)
+                .map(|c| {
+                    if c.is_whitespace() {
+                        c.escape_debug().to_string()
+                    } else {
+                        c.to_string()
+                    }
+                })
+                .collect();
+            format!("`{token}`")
+        }
+    }
+}
+
+fn join_alternatives(expected: &[Expected]) -> String {
+    match expected {
+        [] => "something else".to_owned(),
+        [only] => only.to_string(),
+        [init @ .., last] => {
+            let init: Vec<_> = init.iter().map(ToString::to_string).collect();
+            format!("{} or {last}", init.join(", "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nom::{
+        character::complete::digit1,
+        combinator::map_res,
+        error::context,
+        sequence::{preceded, separated_pair},
+    };
+    use test_case::test_case;
+
+    use super::*;
+
+    fn crate_move(s: &str) -> IResult<'_, (u8, u8)> {
+        context(
+            "crate move",
+            preceded(
+                tag("move "),
+                separated_pair(map_res(digit1, str::parse), tag(" from "), number),
+            ),
+        )(s)
+    }
+
+    #[test]
+    fn renders_location_caret_and_context() {
+        let input = "move 1 from 2\nmove 1 fro 2\n";
+        let error = parse_all(input, lines(crate_move))
+            .unwrap_err()
+            .with_source_name("input/day05");
+        assert_eq!((error.line(), error.column()), (2, 7));
+        assert_eq!(
+            error.to_string(),
+            "in crate move, expected ` from `, found ` fro`\n\
+             \x20--> input/day05:2:7\n\
+             \x20 |\n\
+             2 | move 1 fro 2\n\
+             \x20 |       ^"
+        );
+    }
+
+    #[test_case("move 1 from 2\r\nmove x from 2" => (2, 6, "in crate move, expected a number, found `x`".to_owned()) ; "crlf")]
+    #[test_case("move 300 from 2" => (1, 6, "in crate move, `300` is not valid: number too large to fit in target type".to_owned()) ; "invalid")]
+    #[test_case("move 1 from 2000000000" => (1, 13, "in crate move, number out of range for u8, found `2000000000`".to_owned()) ; "out of range")]
+    #[test_case("move 1 from -2" => (1, 13, "in crate move, expected a number, found `-2`".to_owned()) ; "unsigned")]
+    #[test_case("move 1 from 2\nstop" => (2, 1, "in crate move, expected `move `, found `stop`".to_owned()) ; "bad line")]
+    #[test_case("move 1 from 2 " => (1, 14, "expected a line break or the end of the input, found ` `".to_owned()) ; "trailing")]
+    fn locates_failure(input: &str) -> (usize, usize, String) {
+        let error = parse_all(input, lines(crate_move)).unwrap_err();
+        let headline = error.to_string().lines().next().unwrap().to_owned();
+        (error.line(), error.column(), headline)
+    }
+
+    #[test_case("" => "the end of the input" ; "end of input")]
+    #[test_case("\r\nx" => "the end of the line" ; "end of line")]
+    #[test_case(" \tfro 2" => "` \\tfro`" ; "leading whitespace")]
+    #[test_case("  \n" => "`  `" ; "trailing whitespace")]
+    #[test_case("abcdefghijklmnopqrstuvwxyz" => "`abcdefghijklmnop`" ; "long token")]
+    fn describes_what_was_found(rest: &str) -> String {
+        found(rest)
+    }
+
+    #[test_case("-128" => Ok(-128) ; "smallest")]
+    #[test_case("+127" => Ok(127) ; "plus sign")]
+    #[test_case("-129" => Err("number out of range for i8, found `-129`".to_owned()) ; "too small")]
+    #[test_case("--1" => Err("expected a number, found `-1`".to_owned()) ; "two signs")]
+    fn reads_signed_numbers(input: &str) -> Result<i8, String> {
+        parse_all(input, number).map_err(|e| e.to_string().lines().next().unwrap().to_owned())
+    }
+
+    #[test_case("ab" => Ok(2) ; "longest")]
+    #[test_case("a" => Ok(1) ; "shorter")]
+    #[test_case("c" => Err("expected `a`, `ab` or `b`, found `c`".to_owned()) ; "missing")]
+    fn matches_the_longest_symbol(input: &str) -> Result<u8, String> {
+        let symbols = [("a", 1), ("ab", 2), ("b", 3)].map(|(s, v)| (s.to_owned(), v));
+        parse_all(input, symbol(&symbols))
+            .map_err(|e| e.to_string().lines().next().unwrap().to_owned())
+    }
+
+    #[test]
+    fn hand_rolled_errors_use_the_same_layout() {
+        let input = "12\n3a\n";
+        let at = &input[4..];
+        let error = ParseError::expected(input, at, "a digit").in_context("row");
+        assert_eq!((error.line(), error.column()), (2, 2));
+        assert!(error
+            .to_string()
+            .starts_with("in row, expected a digit, found `a`\n"));
+    }
+
+    #[test]
+    fn excerpts_mark_where_the_line_is_cut() {
+        let error = ParseError::in_excerpt(3, 1000, "abc", "X", "bad");
+        assert_eq!((error.line(), error.column()), (3, 1000));
+        assert_eq!(
+            error.to_string(),
+            "bad\n\
+             \x20--> <input>:3:1000\n\
+             \x20 |\n\
+             3 | …abcX\n\
+             \x20 |     ^"
+        );
+    }
+
+    #[test]
+    fn names_the_source_of_reports() {
+        let report = Report::new(ParseError::new("x", "x", "bad"));
+        let report = name_source(report, "input/day01");
+        assert!(report.to_string().contains("--> input/day01:1:1"));
+    }
+}
@@ -0,0 +1,336 @@
+//! Checks the solutions against known answers.
+//!
+//! The expected answers for each day live in `answers/dayNN.toml`, with an `[[input]]` table per
+//! input file:
+//!
+//! ```toml
+//! [[input]]
+//! path = "input/day01test"
+//! part1 = 24000
+//! part2 = 45000
+//! ```
+//!
+//! Either part may be left out if its answer isn't known. Inputs marked `slow = true` are skipped
+//! unless asked for, as they take too long to solve in unoptimised builds.
+
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::{eyre::WrapErr, Result};
+use serde::Deserialize;
+
+use crate::Runner;
+
+/// The expected answers for one day's inputs.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    #[serde(rename = "input", default)]
+    pub inputs: Vec<Case>,
+}
+
+/// An input file and its expected answers.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Case {
+    /// The input file, relative to the crate root.
+    pub path: PathBuf,
+    pub part1: Option<Answer>,
+    pub part2: Option<Answer>,
+    /// Whether the input takes too long to solve in unoptimised builds.
+    #[serde(default)]
+    pub slow: bool,
+}
+
+/// An expected answer, written in the manifest as either a number or a string.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum Answer {
+    Number(i64),
+    Text(String),
+}
+
+impl Answer {
+    /// Whether the rendered answer matches, ignoring trailing whitespace.
+    pub fn matches(&self, actual: &str) -> bool {
+        match self {
+            Self::Number(n) => actual.trim_end() == n.to_string(),
+            Self::Text(s) => actual.trim_end() == s.trim_end(),
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Number(n) => write!(f, "{n}"),
+            Self::Text(s) => f.write_str(s),
+        }
+    }
+}
+
+impl Manifest {
+    /// Reads a manifest from the given file.
+    pub fn read(path: &Path) -> Result<Self> {
+        let text =
+            fs::read_to_string(path).wrap_err_with(|| format!("reading {}", path.display()))?;
+        Self::parse(&text, path)
+    }
+
+    fn parse(text: &str, path: &Path) -> Result<Self> {
+        toml::from_str(text).wrap_err_with(|| format!("parsing {}", path.display()))
+    }
+}
+
+/// The path of the given day's manifest, relative to `root`.
+pub fn manifest_path(root: &Path, day: u8) -> PathBuf {
+    root.join(format!("answers/day{day:02}.toml"))
+}
+
+/// Reads the given day's manifest, if it has one.
+pub fn load_manifest(root: &Path, day: u8) -> Result<Option<Manifest>> {
+    let path = manifest_path(root, day);
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e).wrap_err_with(|| format!("reading {}", path.display())),
+    };
+    Manifest::parse(&text, &path).map(Some)
+}
+
+/// The result of checking one part of one input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Pass,
+    Mismatch {
+        expected: String,
+        actual: String,
+    },
+    /// The manifest doesn't list an answer for this part.
+    Unchecked,
+    /// The input couldn't be read or solved.
+    Failed(String),
+}
+
+impl Outcome {
+    pub(crate) fn check(expected: Option<&Answer>, actual: &str) -> Self {
+        match expected {
+            None => Self::Unchecked,
+            Some(expected) if expected.matches(actual) => Self::Pass,
+            Some(expected) => Self::Mismatch {
+                expected: expected.to_string(),
+                actual: actual.to_owned(),
+            },
+        }
+    }
+
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Self::Mismatch { .. } | Self::Failed(_))
+    }
+
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            Self::Pass => "ok",
+            Self::Mismatch { .. } => "MISMATCH",
+            Self::Unchecked => "-",
+            Self::Failed(_) => "FAILED",
+        }
+    }
+}
+
+/// The outcome of checking both parts of one input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CaseReport {
+    pub day: u8,
+    pub path: PathBuf,
+    pub part1: Outcome,
+    pub part2: Outcome,
+}
+
+/// The outcomes of checking every input listed in the manifests.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Report {
+    pub cases: Vec<CaseReport>,
+    /// Days with no manifest.
+    pub missing: Vec<u8>,
+}
+
+impl Report {
+    /// Whether every listed answer matched.
+    pub fn passed(&self) -> bool {
+        self.failures() == 0
+    }
+
+    /// The number of parts which didn't match or couldn't be solved.
+    pub fn failures(&self) -> usize {
+        self.outcomes().filter(|o| o.is_failure()).count()
+    }
+
+    fn outcomes(&self) -> impl Iterator<Item = &Outcome> {
+        self.cases.iter().flat_map(|c| [&c.part1, &c.part2])
+    }
+}
+
+/// Solves every input listed in the manifests under `root` and compares the answers.
+///
+/// Inputs marked as slow are only solved if `include_slow` is set.
+pub fn check<'s>(
+    root: &Path,
+    solutions: impl IntoIterator<Item = &'s dyn Runner>,
+    include_slow: bool,
+) -> Result<Report> {
+    let mut report = Report::default();
+    for solution in solutions {
+        let day = solution.day();
+        let Some(manifest) = load_manifest(root, day)? else {
+            report.missing.push(day);
+            continue;
+        };
+        for case in manifest.inputs {
+            if case.slow && !include_slow {
+                continue;
+            }
+            report.cases.push(check_case(root, solution, case));
+        }
+    }
+    Ok(report)
+}
+
+fn check_case(root: &Path, solution: &dyn Runner, case: Case) -> CaseReport {
+    let answers = fs::read_to_string(root.join(&case.path))
+        .wrap_err("reading the input")
+        .and_then(|input| solution.run(&input));
+    let (part1, part2) = match answers {
+        Ok(answers) => (
+            Outcome::check(case.part1.as_ref(), &answers.part1),
+            Outcome::check(case.part2.as_ref(), &answers.part2),
+        ),
+        Err(e) => {
+            let failed = Outcome::Failed(format!("{e:#}"));
+            (failed.clone(), failed)
+        }
+    };
+    CaseReport {
+        day: solution.day(),
+        path: case.path,
+        part1,
+        part2,
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let paths = self
+            .cases
+            .iter()
+            .map(|c| c.path.display().to_string())
+            .collect::<Vec<_>>();
+        let path_width = paths.iter().map(String::len).max().unwrap_or(0).max(5);
+
+        writeln!(f, "day  {:path_width$}  {:8}  part 2", "input", "part 1")?;
+        for (case, path) in self.cases.iter().zip(&paths) {
+            writeln!(
+                f,
+                "{:>3}  {path:path_width$}  {:8}  {}",
+                case.day,
+                case.part1.label(),
+                case.part2.label()
+            )?;
+        }
+
+        for case in &self.cases {
+            for (part, outcome) in [(1, &case.part1), (2, &case.part2)] {
+                match outcome {
+                    Outcome::Mismatch { expected, actual } => {
+                        writeln!(
+                            f,
+                            "\nday {} part {part}, {}:",
+                            case.day,
+                            case.path.display()
+                        )?;
+                        writeln!(f, "  expected: {}", indent(expected))?;
+                        writeln!(f, "  actual:   {}", indent(actual))?;
+                    }
+                    // Both parts fail together, so only report the error once.
+                    Outcome::Failed(error) if part == 1 => {
+                        writeln!(f, "\nday {}, {}:", case.day, case.path.display())?;
+                        writeln!(f, "  {}", indent(error))?;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        if !self.missing.is_empty() {
+            let days = self.missing.iter().map(u8::to_string).collect::<Vec<_>>();
+            writeln!(f, "\nno answers recorded for day {}", days.join(", "))?;
+        }
+
+        let total = self
+            .outcomes()
+            .filter(|o| **o != Outcome::Unchecked)
+            .count();
+        write!(
+            f,
+            "\n{} of {total} answers matched",
+            total - self.failures()
+        )
+    }
+}
+
+/// Indents continuation lines of a multi-line answer to line up under the first.
+pub(crate) fn indent(s: &str) -> String {
+    s.trim_end().replace('\n', "\n            ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SOLUTIONS;
+
+    fn root() -> &'static Path {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+    }
+
+    #[test]
+    fn answers_match_manifests() {
+        let report = check(root(), SOLUTIONS.iter().copied(), false).unwrap();
+        assert!(report.missing.is_empty(), "\n{report}");
+        assert!(report.passed(), "\n{report}");
+    }
+
+    #[test]
+    #[ignore = "too slow for unoptimised builds; run with `cargo test --release -- --ignored`"]
+    fn slow_answers_match_manifests() {
+        let report = check(root(), SOLUTIONS.iter().copied(), true).unwrap();
+        assert!(report.passed(), "\n{report}");
+    }
+
+    #[test]
+    fn mismatches_are_reported() {
+        let case = Case {
+            path: "input/day01test".into(),
+            part1: Some(Answer::Number(1)),
+            part2: None,
+            slow: false,
+        };
+        let report = Report {
+            cases: vec![check_case(root(), &crate::day01::Day01, case)],
+            missing: vec![],
+        };
+        assert_eq!(
+            report.cases[0].part1,
+            Outcome::Mismatch {
+                expected: "1".into(),
+                actual: "24000".into()
+            }
+        );
+        assert_eq!(report.cases[0].part2, Outcome::Unchecked);
+        assert_eq!(report.failures(), 1);
+        assert!(report
+            .to_string()
+            .contains("  1  input/day01test  MISMATCH  -"));
+    }
+}
@@ -0,0 +1,334 @@
+//! Drawing puzzle state as DOT graphs, plain text or SVG.
+//!
+//! Types with something to show implement [`Render`] by describing a [`Scene`]: either a
+//! [`Raster`] of coloured glyphs, or a [`Graph`] of labelled nodes and edges. Each backend then
+//! draws the scenes that it can, without needing any external tools.
+
+use std::{fmt, str::FromStr};
+
+use color_eyre::{eyre::bail, Result};
+use palette::Srgb;
+use petgraph::{
+    graph::{EdgeReference, NodeIndex},
+    visit::EdgeRef,
+};
+
+use crate::{geometry::Point, grid::Grid};
+
+mod dot;
+mod svg;
+mod text;
+
+/// An 8-bit sRGB colour.
+pub type Colour = Srgb<u8>;
+
+/// A way of drawing a [`Scene`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Backend {
+    /// A Graphviz DOT graph. Only graphs can be drawn this way.
+    Dot,
+    /// Plain text: the glyphs of a raster, or an outline of a graph.
+    Text,
+    /// A standalone SVG image.
+    Svg,
+}
+
+impl FromStr for Backend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "dot" => Ok(Self::Dot),
+            "text" => Ok(Self::Text),
+            "svg" => Ok(Self::Svg),
+            _ => Err(format!("expected `dot`, `text` or `svg`, found `{s}`")),
+        }
+    }
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Dot => "dot",
+            Self::Text => "text",
+            Self::Svg => "svg",
+        })
+    }
+}
+
+/// Something which can be drawn.
+pub trait Render {
+    /// Describes what to draw.
+    fn scene(&self) -> Scene;
+
+    /// Draws the scene with the given backend.
+    fn render(&self, backend: Backend) -> Result<String> {
+        self.scene().render(backend)
+    }
+}
+
+/// A description of what to draw, independent of the backend.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Scene {
+    Raster(Raster),
+    Graph(Graph),
+}
+
+impl Scene {
+    /// Draws the scene with the given backend.
+    pub fn render(&self, backend: Backend) -> Result<String> {
+        Ok(match (backend, self) {
+            (Backend::Dot, Self::Graph(graph)) => dot::graph(graph),
+            (Backend::Dot, Self::Raster(_)) => bail!("a raster can't be drawn as a DOT graph"),
+            (Backend::Text, Self::Raster(raster)) => text::raster(raster),
+            (Backend::Text, Self::Graph(graph)) => text::graph(graph),
+            (Backend::Svg, Self::Raster(raster)) => svg::raster(raster),
+            (Backend::Svg, Self::Graph(graph)) => svg::graph(graph),
+        })
+    }
+}
+
+impl From<Raster> for Scene {
+    fn from(raster: Raster) -> Self {
+        Self::Raster(raster)
+    }
+}
+
+impl From<Graph> for Scene {
+    fn from(graph: Graph) -> Self {
+        Self::Graph(graph)
+    }
+}
+
+/// A grid of glyphs, each optionally filled with a colour.
+pub type Raster = Grid<Pixel>;
+
+/// A single cell of a [`Raster`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Pixel {
+    /// How the cell is drawn as text.
+    pub glyph: char,
+    /// How the cell is filled in images. Cells without a colour are left as the background.
+    pub colour: Option<Colour>,
+}
+
+impl Pixel {
+    pub const fn new(glyph: char, colour: Colour) -> Self {
+        Self {
+            glyph,
+            colour: Some(colour),
+        }
+    }
+
+    /// A cell which is only visible as text.
+    pub const fn blank(glyph: char) -> Self {
+        Self {
+            glyph,
+            colour: None,
+        }
+    }
+}
+
+/// A directed graph with labelled nodes and edges.
+///
+/// Nodes are identified by their index in [`Graph::nodes`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Graph {
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+}
+
+impl Graph {
+    /// Converts a petgraph graph, styling each node and edge with the given functions.
+    pub fn from_petgraph<N, E>(
+        graph: &petgraph::Graph<N, E>,
+        mut node: impl FnMut(NodeIndex, &N) -> Node,
+        mut edge: impl FnMut(EdgeReference<'_, E>) -> Edge,
+    ) -> Self {
+        Self {
+            nodes: graph
+                .node_indices()
+                .map(|idx| node(idx, &graph[idx]))
+                .collect(),
+            edges: graph.edge_references().map(&mut edge).collect(),
+        }
+    }
+}
+
+/// A node of a [`Graph`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Node {
+    /// The text drawn on the node, which may span several lines.
+    pub label: String,
+    /// The colour of the node's outline and label.
+    pub colour: Option<Colour>,
+    /// The colour the node is filled with.
+    pub fill: Option<Colour>,
+    /// Where to draw the node, in grid cells. Nodes are laid out automatically unless every node
+    /// in the graph has a position.
+    pub position: Option<Point<usize>>,
+}
+
+impl Node {
+    pub fn new(label: impl fmt::Display) -> Self {
+        Self {
+            label: label.to_string(),
+            colour: None,
+            fill: None,
+            position: None,
+        }
+    }
+
+    pub fn colour(self, colour: Colour) -> Self {
+        Self {
+            colour: Some(colour),
+            ..self
+        }
+    }
+
+    pub fn fill(self, fill: Colour) -> Self {
+        Self {
+            fill: Some(fill),
+            ..self
+        }
+    }
+
+    pub fn at(self, position: Point<usize>) -> Self {
+        Self {
+            position: Some(position),
+            ..self
+        }
+    }
+}
+
+/// An edge of a [`Graph`], between the indices of two nodes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Edge {
+    pub from: usize,
+    pub to: usize,
+    pub label: Option<String>,
+    pub colour: Option<Colour>,
+}
+
+impl Edge {
+    pub fn new(from: usize, to: usize) -> Self {
+        Self {
+            from,
+            to,
+            label: None,
+            colour: None,
+        }
+    }
+
+    /// An edge between the same nodes as a petgraph edge.
+    pub fn between<E>(edge: EdgeReference<'_, E>) -> Self {
+        Self::new(edge.source().index(), edge.target().index())
+    }
+
+    pub fn label(self, label: impl fmt::Display) -> Self {
+        Self {
+            label: Some(label.to_string()),
+            ..self
+        }
+    }
+
+    pub fn colour(self, colour: Colour) -> Self {
+        Self {
+            colour: Some(colour),
+            ..self
+        }
+    }
+}
+
+/// Formats a colour as `#rrggbb`.
+fn hex(colour: Colour) -> String {
+    format!("#{:02x}{:02x}{:02x}", colour.red, colour.green, colour.blue)
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    fn tree() -> Graph {
+        Graph {
+            nodes: vec![
+                Node::new("root").fill(Colour::new(0xdd, 0xe6, 0xf5)),
+                Node::new("a"),
+                Node::new("b \"quoted\""),
+            ],
+            edges: vec![
+                Edge::new(0, 1).label("left"),
+                Edge::new(0, 2).colour(Colour::new(0, 0x99, 0)),
+            ],
+        }
+    }
+
+    fn raster() -> Raster {
+        Grid::from_vec(
+            2,
+            vec![
+                Pixel::new('#', Colour::new(255, 0, 0)),
+                Pixel::blank('.'),
+                Pixel::blank('.'),
+                Pixel::new('#', Colour::new(255, 0, 0)),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test_case("dot" => Ok(Backend::Dot))]
+    #[test_case("SVG" => Ok(Backend::Svg))]
+    #[test_case("png" => Err("expected `dot`, `text` or `svg`, found `png`".to_owned()))]
+    fn parse_backend(s: &str) -> Result<Backend, String> {
+        s.parse()
+    }
+
+    #[test]
+    fn graph_as_dot() {
+        let dot = Scene::from(tree()).render(Backend::Dot).unwrap();
+        assert_eq!(
+            dot,
+            "digraph {\n    \
+                0 [ label = \"root\" fillcolor = \"#dde6f5\" style = filled ]\n    \
+                1 [ label = \"a\" ]\n    \
+                2 [ label = \"b \\\"quoted\\\"\" ]\n    \
+                0 -> 1 [ label = \"left\" ]\n    \
+                0 -> 2 [ color = \"#009900\" ]\n\
+            }\n"
+        );
+    }
+
+    #[test]
+    fn graph_as_text() {
+        let text = Scene::from(tree()).render(Backend::Text).unwrap();
+        assert_eq!(text, "root\n  left: a\n  b \"quoted\"\n");
+    }
+
+    #[test]
+    fn raster_as_text() {
+        let text = Scene::from(raster()).render(Backend::Text).unwrap();
+        assert_eq!(text, "#.\n.#\n");
+    }
+
+    #[test]
+    fn raster_cannot_be_dot() {
+        assert!(Scene::from(raster()).render(Backend::Dot).is_err());
+    }
+
+    #[test]
+    fn svg_draws_coloured_cells() {
+        let svg = Scene::from(raster()).render(Backend::Svg).unwrap();
+        assert!(svg.starts_with("<svg "));
+        assert_eq!(svg.matches("fill=\"#ff0000\"").count(), 2);
+    }
+
+    #[test]
+    fn svg_escapes_labels() {
+        let svg = Scene::from(tree()).render(Backend::Svg).unwrap();
+        assert!(svg.contains("b &quot;quoted&quot;"));
+        assert_eq!(svg.matches("<ellipse").count(), 3);
+        assert_eq!(svg.matches("<line").count(), 2);
+    }
+}
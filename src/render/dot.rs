@@ -0,0 +1,54 @@
+//! The Graphviz DOT backend.
+
+use std::fmt::Write;
+
+use super::{hex, Graph};
+
+pub(super) fn graph(graph: &Graph) -> String {
+    let mut out = String::from("digraph {\n");
+    for (idx, node) in graph.nodes.iter().enumerate() {
+        let mut attrs = format!("label = {}", quote(&node.label));
+        if let Some(colour) = node.colour {
+            write!(attrs, " color = \"{0}\" fontcolor = \"{0}\"", hex(colour)).unwrap();
+        }
+        if let Some(fill) = node.fill {
+            write!(attrs, " fillcolor = \"{}\" style = filled", hex(fill)).unwrap();
+        }
+        if let Some(position) = node.position {
+            // Fixed positions are honoured by `neato` and `fdp`; `dot` lays nodes out itself.
+            write!(
+                attrs,
+                " pos = \"{},{}!\"",
+                position.x,
+                -(position.y as isize)
+            )
+            .unwrap();
+        }
+        writeln!(out, "    {idx} [ {attrs} ]").unwrap();
+    }
+    for edge in &graph.edges {
+        let mut attrs = Vec::new();
+        if let Some(label) = &edge.label {
+            attrs.push(format!("label = {}", quote(label)));
+        }
+        if let Some(colour) = edge.colour {
+            attrs.push(format!("color = \"{}\"", hex(colour)));
+        }
+        write!(out, "    {} -> {}", edge.from, edge.to).unwrap();
+        if !attrs.is_empty() {
+            write!(out, " [ {} ]", attrs.join(" ")).unwrap();
+        }
+        out.push('\n');
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// Quotes a string as a DOT ID.
+fn quote(s: &str) -> String {
+    let escaped = s
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("\"{escaped}\"")
+}
@@ -0,0 +1,216 @@
+//! The SVG backend.
+//!
+//! Graphs are drawn with each node at its given position, or otherwise with a simple layered
+//! layout: nodes are placed in rows by their depth from the roots, and each parent is centred over
+//! its children.
+
+use std::fmt::Write;
+
+use super::{hex, Colour, Graph, Raster};
+
+/// The size of a raster cell, in pixels.
+const CELL: usize = 10;
+/// The space around the edge of a graph, in pixels.
+const MARGIN: f64 = 20.0;
+/// The approximate width of a character of a label, in pixels.
+const CHAR_WIDTH: f64 = 7.5;
+/// The height of a line of a label, in pixels.
+const LINE_HEIGHT: f64 = 14.0;
+
+pub(super) fn raster(raster: &Raster) -> String {
+    let (width, height) = (raster.width() * CELL, raster.height() * CELL);
+    let mut out = header(width as f64, height as f64);
+    for (position, pixel) in raster.iter() {
+        if let Some(colour) = pixel.colour {
+            writeln!(
+                out,
+                r#"<rect x="{}" y="{}" width="{CELL}" height="{CELL}" fill="{}"/>"#,
+                position.x * CELL,
+                position.y * CELL,
+                hex(colour)
+            )
+            .unwrap();
+        }
+    }
+    out.push_str("</svg>\n");
+    out
+}
+
+pub(super) fn graph(graph: &Graph) -> String {
+    let radii = graph
+        .nodes
+        .iter()
+        .map(|node| {
+            let lines = node.label.lines().count().max(1);
+            let longest = node.label.lines().map(|l| l.chars().count()).max();
+            let rx = longest.unwrap_or(0) as f64 * CHAR_WIDTH / 2.0 + 10.0;
+            let ry = lines as f64 * LINE_HEIGHT / 2.0 + 6.0;
+            (rx, ry)
+        })
+        .collect::<Vec<_>>();
+    let max_rx = radii.iter().map(|r| r.0).fold(0.0, f64::max);
+    let max_ry = radii.iter().map(|r| r.1).fold(0.0, f64::max);
+
+    let fixed = graph
+        .nodes
+        .iter()
+        .map(|node| node.position)
+        .collect::<Option<Vec<_>>>();
+    let (slots, column_width, row_height) = match fixed {
+        Some(positions) => {
+            let slots = positions.iter().map(|p| (p.x as f64, p.y as f64)).collect();
+            let size = 2.0 * max_rx.max(max_ry) + 10.0;
+            (slots, size, size)
+        }
+        None => (layered(graph), 2.0 * max_rx + 10.0, 2.0 * max_ry + 50.0),
+    };
+    let centres = slots
+        .iter()
+        .map(|&(x, y)| {
+            (
+                MARGIN + max_rx + x * column_width,
+                MARGIN + max_ry + y * row_height,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let width = centres.iter().map(|c| c.0).fold(0.0, f64::max) + max_rx + MARGIN;
+    let height = centres.iter().map(|c| c.1).fold(0.0, f64::max) + max_ry + MARGIN;
+    let mut out = header(width, height);
+    out.push_str(concat!(
+        r#"<defs><marker id="arrow" viewBox="0 0 10 10" refX="10" refY="5" "#,
+        r#"markerWidth="8" markerHeight="8" orient="auto-start-reverse">"#,
+        r#"<path d="M 0 0 L 10 5 L 0 10 z"/></marker></defs>"#,
+        "\n",
+        r#"<rect width="100%" height="100%" fill="white"/>"#,
+        "\n",
+    ));
+
+    for edge in &graph.edges {
+        let (from, to) = (centres[edge.from], centres[edge.to]);
+        let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+        // Start and end the line on the outlines of the nodes rather than at their centres.
+        let start = boundary(from, (dx, dy), radii[edge.from]);
+        let end = boundary(to, (-dx, -dy), radii[edge.to]);
+        let colour = colour_or(edge.colour, "black");
+        writeln!(
+            out,
+            r#"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="{colour}" marker-end="url(#arrow)"/>"#,
+            start.0, start.1, end.0, end.1
+        )
+        .unwrap();
+        if let Some(label) = &edge.label {
+            let (x, y) = ((start.0 + end.0) / 2.0, (start.1 + end.1) / 2.0);
+            writeln!(
+                out,
+                r#"<text x="{x:.1}" y="{y:.1}" text-anchor="middle" fill="{colour}">{}</text>"#,
+                escape(label)
+            )
+            .unwrap();
+        }
+    }
+
+    for ((node, &(cx, cy)), &(rx, ry)) in graph.nodes.iter().zip(&centres).zip(&radii) {
+        let colour = colour_or(node.colour, "black");
+        let fill = colour_or(node.fill, "white");
+        writeln!(
+            out,
+            r#"<ellipse cx="{cx:.1}" cy="{cy:.1}" rx="{rx:.1}" ry="{ry:.1}" stroke="{colour}" fill="{fill}"/>"#,
+        )
+        .unwrap();
+        let lines = node.label.lines().collect::<Vec<_>>();
+        let top = cy - (lines.len() as f64 - 1.0) * LINE_HEIGHT / 2.0;
+        for (idx, line) in lines.into_iter().enumerate() {
+            writeln!(
+                out,
+                r#"<text x="{cx:.1}" y="{:.1}" text-anchor="middle" dominant-baseline="central" fill="{colour}">{}</text>"#,
+                top + idx as f64 * LINE_HEIGHT,
+                escape(line)
+            )
+            .unwrap();
+        }
+    }
+
+    out.push_str("</svg>\n");
+    out
+}
+
+fn header(width: f64, height: f64) -> String {
+    format!(
+        concat!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0:.0}" height="{1:.0}" "#,
+            r#"viewBox="0 0 {0:.0} {1:.0}" font-family="monospace" font-size="12">"#,
+            "\n"
+        ),
+        width.ceil(),
+        height.ceil()
+    )
+}
+
+/// The point where a ray from the centre of an ellipse, in the given direction, leaves it.
+fn boundary(centre: (f64, f64), direction: (f64, f64), (rx, ry): (f64, f64)) -> (f64, f64) {
+    let (dx, dy) = direction;
+    if dx == 0.0 && dy == 0.0 {
+        return centre;
+    }
+    let t = 1.0 / ((dx / rx).powi(2) + (dy / ry).powi(2)).sqrt();
+    (centre.0 + dx * t, centre.1 + dy * t)
+}
+
+/// Places each node in a column and row, with roots at the top and leaves spread out left to
+/// right.
+fn layered(graph: &Graph) -> Vec<(f64, f64)> {
+    let n = graph.nodes.len();
+    let mut children = vec![Vec::new(); n];
+    let mut has_parent = vec![false; n];
+    for edge in &graph.edges {
+        children[edge.from].push(edge.to);
+        has_parent[edge.to] = true;
+    }
+
+    let mut slots = vec![(0.0, 0.0); n];
+    let mut visited = vec![false; n];
+    let mut next_leaf = 0.0;
+    let roots = (0..n).filter(|&idx| !has_parent[idx]).chain(0..n);
+    for root in roots {
+        if visited[root] {
+            continue;
+        }
+        visited[root] = true;
+        // Each entry is a node, its depth, its next child to visit, and the nodes it reached first.
+        let mut stack = vec![(root, 0, 0, Vec::new())];
+        while let Some((idx, depth, next_child, owned)) = stack.last_mut() {
+            let (idx, depth) = (*idx, *depth);
+            if let Some(&child) = children[idx].get(*next_child) {
+                *next_child += 1;
+                if !visited[child] {
+                    visited[child] = true;
+                    owned.push(child);
+                    stack.push((child, depth + 1, 0, Vec::new()));
+                }
+                continue;
+            }
+            let x = match (owned.first(), owned.last()) {
+                (Some(&first), Some(&last)) => (slots[first].0 + slots[last].0) / 2.0,
+                _ => {
+                    next_leaf += 1.0;
+                    next_leaf - 1.0
+                }
+            };
+            slots[idx] = (x, depth as f64);
+            stack.pop();
+        }
+    }
+    slots
+}
+
+fn colour_or(colour: Option<Colour>, default: &str) -> String {
+    colour.map_or_else(|| default.to_owned(), hex)
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,59 @@
+//! The plain text backend.
+
+use super::{Graph, Raster};
+
+pub(super) fn raster(raster: &Raster) -> String {
+    let mut out = String::with_capacity((raster.width() + 1) * raster.height());
+    for row in raster.rows() {
+        out.extend(row.iter().map(|pixel| pixel.glyph));
+        out.push('\n');
+    }
+    out
+}
+
+/// Draws the graph as an indented outline, starting from the nodes with no incoming edges.
+///
+/// Each node is only listed once, under the first node found to lead to it.
+pub(super) fn graph(graph: &Graph) -> String {
+    let mut children = vec![Vec::new(); graph.nodes.len()];
+    let mut has_parent = vec![false; graph.nodes.len()];
+    for edge in &graph.edges {
+        children[edge.from].push(edge);
+        has_parent[edge.to] = true;
+    }
+
+    let mut out = String::new();
+    let mut visited = vec![false; graph.nodes.len()];
+    let roots = (0..graph.nodes.len())
+        .filter(|&idx| !has_parent[idx])
+        .chain(0..graph.nodes.len());
+    for root in roots {
+        if visited[root] {
+            continue;
+        }
+        visited[root] = true;
+        let mut stack = vec![(root, None, 0)];
+        while let Some((idx, edge_label, depth)) = stack.pop() {
+            out.extend(std::iter::repeat_n("  ", depth));
+            if let Some(edge_label) = edge_label {
+                out.push_str(&single_line(edge_label));
+                out.push_str(": ");
+            }
+            out.push_str(&single_line(&graph.nodes[idx].label));
+            out.push('\n');
+
+            // Pushed in reverse so that children are listed in the order of their edges.
+            for edge in children[idx].iter().rev() {
+                if !visited[edge.to] {
+                    visited[edge.to] = true;
+                    stack.push((edge.to, edge.label.as_deref(), depth + 1));
+                }
+            }
+        }
+    }
+    out
+}
+
+fn single_line(s: &str) -> String {
+    s.replace('\n', " ")
+}
@@ -0,0 +1,521 @@
+//! Puzzle input read as a stream, for inputs too large to hold in memory.
+//!
+//! [`Input`] reads from any [`BufRead`] a line or a byte at a time, holding no more than the
+//! current line, or a block of lines at a time to be parsed in parallel. Parse failures are located
+//! by line and column just as [`parse_all`] locates them within a whole input, so the same
+//! malformed input gives the same report either way.
+//!
+//! Reading stops at the first I/O error, which is kept until [`Input::finish`] so that the
+//! iterators can yield plain lines and bytes.
+
+use std::{
+    collections::VecDeque,
+    fmt,
+    io::{self, BufRead, BufReader, Read},
+};
+
+use nom::{branch::alt, character::complete::line_ending, combinator::eof, sequence::terminated};
+
+use crate::parsing::{self, parse_all, IResult, ParseError, EXCERPT_LEN};
+
+/// Puzzle input read from `R` as it is needed.
+#[derive(Debug)]
+pub struct Input<R> {
+    reader: R,
+    /// The line the next read starts on, counting from 1.
+    line: usize,
+    error: Option<io::Error>,
+}
+
+impl<R: Read> Input<BufReader<R>> {
+    /// Reads the input from an unbuffered reader, such as a file.
+    pub fn buffered(reader: R) -> Self {
+        Self::new(BufReader::new(reader))
+    }
+}
+
+impl<R: BufRead> Input<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            line: 1,
+            error: None,
+        }
+    }
+
+    /// The remaining input, a line at a time.
+    pub fn lines(&mut self) -> Lines<'_, R> {
+        Lines { input: self }
+    }
+
+    /// The remaining input, a byte at a time.
+    pub fn bytes(&mut self) -> Bytes<'_, R> {
+        Bytes {
+            input: self,
+            column: 0,
+            before: VecDeque::with_capacity(EXCERPT_LEN),
+            ahead: VecDeque::new(),
+            last: None,
+            ended: false,
+        }
+    }
+
+    /// The remaining input in blocks of whole lines, so that the blocks can be parsed in parallel.
+    ///
+    /// Each block holds at least `size` bytes unless the input ends first, and ends with a line,
+    /// including its line ending, for which `ends_block` holds.
+    pub fn blocks<F: FnMut(&[u8]) -> bool>(
+        &mut self,
+        size: usize,
+        ends_block: F,
+    ) -> Blocks<'_, R, F> {
+        Blocks {
+            input: self,
+            size,
+            ends_block,
+        }
+    }
+
+    /// Finishes reading, returning the I/O error which cut the input short, if there was one.
+    pub fn finish(self) -> io::Result<()> {
+        self.error.map_or(Ok(()), Err)
+    }
+
+    /// Records an I/O error, after which the input reads as ended.
+    fn fail(&mut self, error: io::Error) {
+        self.error.get_or_insert(error);
+    }
+}
+
+/// A line of the input, keeping its line ending.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Line {
+    number: usize,
+    text: String,
+}
+
+impl Line {
+    /// The line number, counting from 1.
+    pub fn number(&self) -> usize {
+        self.number
+    }
+
+    /// The text of the line, without its line ending.
+    pub fn text(&self) -> &str {
+        self.text
+            .strip_suffix('\n')
+            .map_or(&*self.text, |text| text.strip_suffix('\r').unwrap_or(text))
+    }
+
+    /// Applies `parser` to the whole line, as [`parsing::lines`] applies it to each line of an
+    /// input held in memory.
+    pub fn parse<'l, O>(
+        &'l self,
+        parser: impl FnMut(&'l str) -> IResult<'l, O>,
+    ) -> Result<O, ParseError> {
+        parse_all(&self.text, terminated(parser, alt((line_ending, eof))))
+            .map_err(|e| e.on_line(self.number))
+    }
+
+    /// A failure at `at`, which must be a slice of [`text`](Self::text), described by `message`.
+    pub fn error(&self, at: &str, message: impl Into<String>) -> ParseError {
+        ParseError::new(&self.text, at, message).on_line(self.number)
+    }
+
+    /// A failure at `at`, which must be a slice of [`text`](Self::text), where `expected` was
+    /// expected.
+    pub fn expected(&self, at: &str, expected: impl fmt::Display) -> ParseError {
+        ParseError::expected(&self.text, at, expected).on_line(self.number)
+    }
+}
+
+/// An iterator over the lines of an [`Input`].
+#[derive(Debug)]
+pub struct Lines<'a, R> {
+    input: &'a mut Input<R>,
+}
+
+impl<R: BufRead> Iterator for Lines<'_, R> {
+    type Item = Line;
+
+    fn next(&mut self) -> Option<Line> {
+        let input = &mut *self.input;
+        if input.error.is_some() {
+            return None;
+        }
+        let mut bytes = Vec::new();
+        match input.reader.read_until(b'\n', &mut bytes) {
+            Ok(0) => None,
+            Ok(_) => match String::from_utf8(bytes) {
+                Ok(text) => {
+                    let number = input.line;
+                    if text.ends_with('\n') {
+                        input.line += 1;
+                    }
+                    Some(Line { number, text })
+                }
+                Err(_) => {
+                    input.fail(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("line {} of the input is not valid UTF-8", input.line),
+                    ));
+                    None
+                }
+            },
+            Err(e) => {
+                input.fail(e);
+                None
+            }
+        }
+    }
+}
+
+/// A block of whole lines of the input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Block {
+    first_line: usize,
+    text: String,
+}
+
+impl Block {
+    /// The number of the block's first line, counting from 1.
+    pub fn first_line(&self) -> usize {
+        self.first_line
+    }
+
+    /// The text of the block's lines, keeping their line endings.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+/// An iterator over the blocks of an [`Input`].
+pub struct Blocks<'a, R, F> {
+    input: &'a mut Input<R>,
+    size: usize,
+    ends_block: F,
+}
+
+impl<R: BufRead, F: FnMut(&[u8]) -> bool> Iterator for Blocks<'_, R, F> {
+    type Item = Block;
+
+    fn next(&mut self) -> Option<Block> {
+        let input = &mut *self.input;
+        if input.error.is_some() {
+            return None;
+        }
+        let first_line = input.line;
+        let mut bytes = Vec::with_capacity(self.size);
+        loop {
+            let start = bytes.len();
+            match input.reader.read_until(b'\n', &mut bytes) {
+                Ok(0) => break,
+                Ok(_) if bytes.ends_with(b"\n") => {
+                    input.line += 1;
+                    if bytes.len() >= self.size && (self.ends_block)(&bytes[start..]) {
+                        break;
+                    }
+                }
+                Ok(_) => break,
+                Err(e) => {
+                    input.fail(e);
+                    return None;
+                }
+            }
+        }
+        if bytes.is_empty() {
+            return None;
+        }
+        match String::from_utf8(bytes) {
+            Ok(text) => Some(Block { first_line, text }),
+            Err(e) => {
+                let valid = &e.as_bytes()[..e.utf8_error().valid_up_to()];
+                let line = first_line + valid.iter().filter(|&&b| b == b'\n').count();
+                input.fail(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("line {line} of the input is not valid UTF-8"),
+                ));
+                None
+            }
+        }
+    }
+}
+
+/// An iterator over the bytes of an [`Input`], which keeps track of where the last byte was.
+#[derive(Debug)]
+pub struct Bytes<'a, R> {
+    input: &'a mut Input<R>,
+    /// The characters read so far on the current line.
+    column: usize,
+    /// The end of the current line before the last byte.
+    before: VecDeque<u8>,
+    /// Bytes read past the last byte to show in a failure's report, which are yielded next.
+    ahead: VecDeque<u8>,
+    last: Option<u8>,
+    ended: bool,
+}
+
+impl<R: BufRead> Bytes<'_, R> {
+    /// A failure at the last byte read, or at the end of the input once it has ended, where
+    /// `expected` was expected.
+    ///
+    /// The report shows the line on either side of the failure, so the rest of the line is read
+    /// ahead, as far as the report shows it.
+    pub fn expected(&mut self, expected: impl fmt::Display) -> ParseError {
+        let (line, column, mut before, at) = match (self.last, self.ended) {
+            (None, _) | (Some(b'\n'), true) => (self.input.line, 1, VecDeque::new(), None),
+            (Some(last), true) => {
+                let mut before = self.before.clone();
+                before.push_back(last);
+                (self.input.line, self.column + 1, before, None)
+            }
+            (Some(b'\n'), false) => (
+                self.input.line - 1,
+                self.column,
+                self.before.clone(),
+                Some(b'\n'),
+            ),
+            (Some(last), false) => (
+                self.input.line,
+                self.column,
+                self.before.clone(),
+                Some(last),
+            ),
+        };
+
+        // Start the excerpt on a whole character.
+        while before.front().copied().is_some_and(is_continuation) {
+            before.pop_front();
+        }
+        let before = String::from_utf8_lossy(before.make_contiguous());
+
+        let (rest, found) = match at {
+            None => (String::new(), parsing::found("")),
+            Some(b'\n') => (String::new(), parsing::found("\n")),
+            Some(b) => {
+                let (ahead, line_ended) = self.peek_line();
+                let mut rest = vec![b];
+                rest.extend(ahead);
+                let found = parsing::found(&String::from_utf8_lossy(&rest));
+                if line_ended {
+                    while rest.last() == Some(&b'\r') {
+                        rest.pop();
+                    }
+                }
+                (String::from_utf8_lossy(&rest).into_owned(), found)
+            }
+        };
+        ParseError::in_excerpt(
+            line,
+            column,
+            &before,
+            &rest,
+            format!("expected {expected}, found {found}"),
+        )
+    }
+
+    /// Reads ahead to the end of the current line, or far enough past [`EXCERPT_LEN`] bytes to
+    /// cut the line short on a whole character, returning the bytes read ahead on this line and
+    /// whether the line ends among them.
+    fn peek_line(&mut self) -> (Vec<u8>, bool) {
+        let limit = EXCERPT_LEN + 2;
+        let input = &mut *self.input;
+        while self.ahead.len() < limit && !self.ahead.contains(&b'\n') && input.error.is_none() {
+            match input.reader.fill_buf() {
+                Ok([]) => break,
+                Ok(&[byte, ..]) => {
+                    input.reader.consume(1);
+                    self.ahead.push_back(byte);
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => input.fail(e),
+            }
+        }
+        let line = self.ahead.iter().take_while(|&&b| b != b'\n').copied();
+        let line = line.take(limit).collect::<Vec<_>>();
+        let line_ended = line.len() < limit;
+        (line, line_ended)
+    }
+}
+
+impl<R: BufRead> Iterator for Bytes<'_, R> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        let input = &mut *self.input;
+        let byte = match self.ahead.pop_front() {
+            Some(byte) => Some(byte),
+            None => loop {
+                if input.error.is_some() {
+                    break None;
+                }
+                match input.reader.fill_buf() {
+                    Ok([]) => break None,
+                    Ok(&[byte, ..]) => {
+                        input.reader.consume(1);
+                        break Some(byte);
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                    Err(e) => input.fail(e),
+                }
+            },
+        };
+        let Some(byte) = byte else {
+            self.ended = true;
+            return None;
+        };
+
+        match self.last {
+            Some(b'\n') => {
+                self.before.clear();
+                self.column = 0;
+            }
+            Some(last) => {
+                if self.before.len() == EXCERPT_LEN {
+                    self.before.pop_front();
+                }
+                self.before.push_back(last);
+            }
+            None => {}
+        }
+        if !is_continuation(byte) {
+            self.column += 1;
+        }
+        if byte == b'\n' {
+            input.line += 1;
+        }
+        self.last = Some(byte);
+        Some(byte)
+    }
+}
+
+/// Whether the byte continues a UTF-8 character, rather than starting one.
+fn is_continuation(byte: u8) -> bool {
+    byte & 0b1100_0000 == 0b1000_0000
+}
+
+#[cfg(test)]
+mod tests {
+    use nom::character::complete::u32;
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test]
+    fn numbers_lines_and_strips_endings() {
+        let mut input = Input::new("one\r\ntwo\n\nfour".as_bytes());
+        let lines = input
+            .lines()
+            .map(|line| (line.number(), line.text().to_owned()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            [(1, "one"), (2, "two"), (3, ""), (4, "four")].map(|(n, t)| (n, t.to_owned()))
+        );
+        assert!(input.finish().is_ok());
+    }
+
+    #[test_case("12\n" => Ok(12) ; "number")]
+    #[test_case("12 \n" => Err((1, 3, "expected a line break or the end of the input, found ` `".to_owned())) ; "trailing")]
+    #[test_case("\n" => Err((1, 1, "expected a number, found the end of the line".to_owned())) ; "blank")]
+    fn parses_lines_like_parsing_lines(text: &str) -> Result<u32, (usize, usize, String)> {
+        let line = Line {
+            number: 1,
+            text: text.to_owned(),
+        };
+        line.parse(u32).map_err(|e| {
+            let headline = e.to_string().lines().next().unwrap().to_owned();
+            (e.line(), e.column(), headline)
+        })
+    }
+
+    #[test]
+    fn locates_line_failures_in_the_whole_input() {
+        let source = "1\n2\nx\n";
+        let line = Input::new(source.as_bytes()).lines().nth(2).unwrap();
+        assert_eq!(
+            line.parse(u32),
+            parsing::parse_all(source, parsing::lines(u32)).map(|_| 0)
+        );
+    }
+
+    #[test_case("ab\ncDe", 5 => (2, 2, "expected x, found `De`".to_owned()) ; "letter")]
+    #[test_case("ab\ncd", 3 => (1, 3, "expected x, found the end of the line".to_owned()) ; "line ending")]
+    #[test_case("ab", 3 => (1, 3, "expected x, found the end of the input".to_owned()) ; "end of input")]
+    #[test_case("", 1 => (1, 1, "expected x, found the end of the input".to_owned()) ; "empty")]
+    fn locates_byte_failures(source: &str, read: usize) -> (usize, usize, String) {
+        let mut input = Input::new(source.as_bytes());
+        let mut bytes = input.bytes();
+        for _ in 0..read {
+            bytes.next();
+        }
+        let error = bytes.expected("x");
+        let headline = error.to_string().lines().next().unwrap().to_owned();
+        (error.line(), error.column(), headline)
+    }
+
+    #[test]
+    fn cuts_long_lines_short_in_byte_failures() {
+        let source = format!("{}!", "a".repeat(100));
+        let mut input = Input::new(source.as_bytes());
+        let mut bytes = input.bytes();
+        bytes.by_ref().for_each(drop);
+        let error = bytes.expected("x");
+        assert_eq!(error.column(), 102);
+        assert!(error
+            .to_string()
+            .contains(&format!("1 | …{}!\n", "a".repeat(EXCERPT_LEN))));
+    }
+
+    #[test]
+    fn shows_the_same_window_of_long_lines_both_ways() {
+        let source = format!("{}é!{}\r\n", "a".repeat(3000), "b".repeat(3000));
+        let at = source.find('!').unwrap();
+        let in_memory = ParseError::expected(&source, &source[at..], "x");
+        let mut input = Input::new(source.as_bytes());
+        let mut bytes = input.bytes();
+        bytes.by_ref().take(at + 1).for_each(drop);
+        assert_eq!(bytes.expected("x"), in_memory);
+        assert_eq!(
+            bytes.next(),
+            Some(b'b'),
+            "bytes read ahead are still yielded"
+        );
+
+        let short = "ab!c\r\n";
+        let in_memory = ParseError::expected(short, &short[2..], "x");
+        let mut input = Input::new(short.as_bytes());
+        let mut bytes = input.bytes();
+        bytes.by_ref().take(3).for_each(drop);
+        assert_eq!(bytes.expected("x"), in_memory);
+        assert!(in_memory.to_string().contains("1 | ab!c\n"));
+    }
+
+    #[test]
+    fn splits_blocks_after_matching_lines() {
+        let mut input = Input::new("1\n2\n\n3\n\n\n4\n5".as_bytes());
+        let blocks = input
+            .blocks(3, |line| line == b"\n")
+            .map(|block| (block.first_line(), block.text().to_owned()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            blocks,
+            [(1, "1\n2\n\n"), (4, "3\n\n"), (6, "\n4\n5")].map(|(n, t)| (n, t.to_owned()))
+        );
+        assert!(input.finish().is_ok());
+
+        let mut input = Input::new(&b"1\n\n2\n\xff\n"[..]);
+        assert_eq!(input.blocks(1, |line| line == b"\n").count(), 1);
+        let error = input.finish().unwrap_err();
+        assert_eq!(error.to_string(), "line 4 of the input is not valid UTF-8");
+    }
+
+    #[test]
+    fn keeps_the_first_io_error() {
+        let mut input = Input::new(&[b'1', b'\n', 0xff, b'\n'][..]);
+        assert_eq!(input.lines().count(), 1);
+        let error = input.finish().unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(error.to_string(), "line 2 of the input is not valid UTF-8");
+    }
+}
@@ -0,0 +1,204 @@
+//! Structured tracing of the solvers' intermediate steps.
+//!
+//! The solvers emit [`tracing`] spans and events at the points worth watching, such as each
+//! instruction day 10's CPU starts or each item day 11's monkeys throw. Nothing is recorded unless
+//! a subscriber is installed with [`init`], which keeps the events matching a filter in the syntax
+//! of [`EnvFilter`], such as `aoc_2022::day10=trace` or `debug`.
+
+use std::{
+    fmt,
+    fs::File,
+    io::{self, IsTerminal},
+    path::Path,
+    str::FromStr,
+};
+
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+use tracing::Subscriber;
+use tracing_subscriber::{fmt::writer::BoxMakeWriter, EnvFilter};
+
+/// How trace events are written.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// One human-readable line per event, prefixed by the spans it is in.
+    #[default]
+    Text,
+    /// One JSON object per line, with the event's fields and the spans it is in.
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(format!("expected `text` or `json`, found `{s}`")),
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Text => "text",
+            Self::Json => "json",
+        })
+    }
+}
+
+/// A subscriber which writes the events matching `filter` to `writer`.
+///
+/// Text is coloured only if `ansi` is set, as it should be for a terminal.
+pub fn subscriber(
+    filter: &str,
+    format: Format,
+    writer: BoxMakeWriter,
+    ansi: bool,
+) -> Result<Box<dyn Subscriber + Send + Sync>> {
+    let filter = EnvFilter::try_new(filter)
+        .map_err(|e| eyre!(e))
+        .wrap_err_with(|| format!("invalid trace filter `{filter}`"))?;
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(writer);
+    Ok(match format {
+        Format::Text => Box::new(builder.with_ansi(ansi).finish()),
+        Format::Json => Box::new(
+            builder
+                .json()
+                .with_current_span(true)
+                .with_span_list(true)
+                .finish(),
+        ),
+    })
+}
+
+/// Writes the events matching `filter` to the given file, or to stderr, for the rest of the run.
+///
+/// Text is coloured only when written to a terminal.
+pub fn init(filter: &str, format: Format, out: Option<&Path>) -> Result<()> {
+    let subscriber = match out {
+        Some(path) => {
+            let file =
+                File::create(path).wrap_err_with(|| format!("creating {}", path.display()))?;
+            subscriber(filter, format, BoxMakeWriter::new(file), false)?
+        }
+        None => subscriber(
+            filter,
+            format,
+            BoxMakeWriter::new(io::stderr),
+            io::stderr().is_terminal(),
+        )?,
+    };
+    tracing::subscriber::set_global_default(subscriber)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::Runner;
+
+    /// A writer which keeps everything written to it, shared between clones.
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Solves the day's example input while tracing, returning what was written.
+    fn trace(solution: &dyn Runner, input: &str, filter: &str, format: Format) -> String {
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = subscriber(
+            filter,
+            format,
+            BoxMakeWriter::new(move || writer.clone()),
+            false,
+        )
+        .unwrap();
+        tracing::subscriber::with_default(subscriber, || solution.run(input).unwrap());
+        let written = buffer.0.lock().unwrap().clone();
+        String::from_utf8(written).unwrap()
+    }
+
+    #[test]
+    fn writes_json_lines() {
+        let written = trace(
+            &crate::day10::Day10,
+            "noop\naddx 3\n",
+            "aoc_2022=info,aoc_2022::day10=trace",
+            Format::Json,
+        );
+        let events = written
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .collect::<Vec<_>>();
+        let started = events
+            .iter()
+            .find(|event| event["fields"]["message"] == "starting instruction")
+            .unwrap();
+        assert_eq!(started["fields"]["op"], "Noop");
+        assert_eq!(started["span"]["name"], "cycle");
+        assert_eq!(started["spans"][0]["day"], 10);
+    }
+
+    #[test]
+    fn filters_events() {
+        let input = include_str!("../input/day11test");
+        let rounds = trace(
+            &crate::day11::Day11,
+            input,
+            "aoc_2022::day11=debug",
+            Format::Text,
+        );
+        assert!(rounds.contains("inspection count"));
+        assert!(!rounds.contains("threw item"));
+        assert_eq!(
+            trace(
+                &crate::day11::Day11,
+                input,
+                "aoc_2022::day10=trace",
+                Format::Text
+            ),
+            ""
+        );
+    }
+
+    #[test]
+    fn warns_about_skipped_lines() {
+        let solution = crate::day01::Day01
+            .configure(&crate::params::overrides(&["mode=lenient"]))
+            .unwrap();
+        let written = trace(&*solution, "1000\nlots\n", "aoc_2022=warn", Format::Text);
+        assert!(written.contains("skipped a line which isn't a number of calories"));
+        assert!(written.contains("line=2 elf=0 text=\"lots\""), "{written}");
+    }
+
+    #[test]
+    fn rejects_bad_filters() {
+        let error = subscriber(
+            "day10=loud",
+            Format::Text,
+            BoxMakeWriter::new(io::sink),
+            false,
+        )
+        .err()
+        .unwrap();
+        assert!(error.to_string().contains("invalid trace filter"));
+    }
+}
@@ -0,0 +1,177 @@
+//! Re-solving a day whenever its input file changes, for working on hand-crafted inputs.
+//!
+//! A [`Watcher`] polls the file rather than relying on filesystem notifications, so that it works
+//! the same everywhere, including for editors which save by replacing the file. Each change is
+//! solved into a [`Frame`] showing the answers or the error, and the day's drawing as text.
+
+use std::{
+    fmt, fs,
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+
+use crate::{
+    bench::Short,
+    params::{self, Overrides},
+    parsing,
+    render::Backend,
+    Answers, Runner,
+};
+
+/// What was read from the input file: its contents, or why it couldn't be read.
+pub type Snapshot = Result<String, String>;
+
+/// Watches a file for changes to its contents.
+#[derive(Clone, Debug)]
+pub struct Watcher {
+    path: PathBuf,
+    /// The modification time and length of the file when last read.
+    stamp: Option<(SystemTime, u64)>,
+    last: Option<Snapshot>,
+}
+
+impl Watcher {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            stamp: None,
+            last: None,
+        }
+    }
+
+    /// Checks the file, returning what it holds if that has changed since the last check.
+    ///
+    /// The first check always returns. The file is only re-read when its modification time or
+    /// length changes, and saving it unchanged doesn't count as a change.
+    pub fn poll(&mut self) -> Option<Snapshot> {
+        let stamp = fs::metadata(&self.path)
+            .and_then(|m| Ok((m.modified()?, m.len())))
+            .ok();
+        if self.last.is_some() && stamp.is_some() && stamp == self.stamp {
+            return None;
+        }
+        self.stamp = stamp;
+        let snapshot = fs::read_to_string(&self.path)
+            .map_err(|e| format!("reading {}: {e}", self.path.display()));
+        if self.last.as_ref() == Some(&snapshot) {
+            return None;
+        }
+        self.last = Some(snapshot.clone());
+        Some(snapshot)
+    }
+}
+
+/// The outcome of solving one version of the input, as shown after each change.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Frame {
+    pub day: u8,
+    /// The parameters overridden for the run.
+    pub params: Overrides,
+    pub source_name: String,
+    /// The answers and the time taken to solve both parts, or why the input couldn't be solved.
+    pub solved: Result<(Answers, Duration), String>,
+    /// The solved state drawn as text, if the day has anything to draw.
+    pub drawing: Option<String>,
+}
+
+impl Frame {
+    /// Solves the input, then draws the solved state.
+    pub fn solve(solution: &dyn Runner, source_name: &str, snapshot: &Snapshot) -> Self {
+        let solved = snapshot.clone().and_then(|input| {
+            solution
+                .run_timed(&input)
+                .map(|(answers, timings)| (answers, timings.total()))
+                .map_err(|e| format!("{:#}", parsing::name_source(e, source_name)))
+        });
+        let drawing = match (&solved, snapshot) {
+            (Ok(_), Ok(input)) => solution
+                .visualise(input)
+                .and_then(|scene| scene.map(|s| s.render(Backend::Text)).transpose())
+                .unwrap_or_else(|e| Some(format!("could not draw the puzzle: {e:#}"))),
+            _ => None,
+        };
+        Self {
+            day: solution.day(),
+            params: solution.overrides(),
+            source_name: source_name.to_owned(),
+            solved,
+            drawing,
+        }
+    }
+}
+
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Day {} — {}", self.day, self.source_name)?;
+        if !self.params.is_empty() {
+            write!(f, " ({})", params::describe(&self.params))?;
+        }
+        writeln!(f)?;
+        match &self.solved {
+            Ok((answers, time)) => {
+                writeln!(f, "Solved in {}\n", Short(*time))?;
+                writeln!(f, "{answers}")?;
+            }
+            Err(e) => writeln!(f, "\nerror: {}", e.trim_end())?,
+        }
+        if let Some(drawing) = &self.drawing {
+            writeln!(f, "\n{}", drawing.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use super::*;
+
+    /// A fresh path for a watched file, which doesn't exist yet.
+    fn path(name: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("aoc-watch-{}-{name}", process::id()));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn reports_each_change_once() {
+        let path = path("changes");
+        let mut watcher = Watcher::new(&path);
+        assert!(matches!(watcher.poll(), Some(Err(e)) if e.starts_with("reading")));
+        assert_eq!(watcher.poll(), None);
+
+        fs::write(&path, "1000\n").unwrap();
+        assert_eq!(watcher.poll(), Some(Ok("1000\n".to_owned())));
+        assert_eq!(watcher.poll(), None);
+        fs::write(&path, "1000\n").unwrap();
+        assert_eq!(watcher.poll(), None);
+
+        fs::write(&path, "1000\n2000\n").unwrap();
+        assert_eq!(watcher.poll(), Some(Ok("1000\n2000\n".to_owned())));
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(watcher.poll(), Some(Err(_))));
+    }
+
+    #[test]
+    fn shows_answers_and_drawing() {
+        let input = include_str!("../input/day14test");
+        let frame = Frame::solve(&crate::day14::Day14, "pit", &Ok(input.to_owned()));
+        assert_eq!(frame.solved.as_ref().unwrap().0.part1, "24");
+        let shown = frame.to_string();
+        assert!(shown.starts_with("Day 14 — pit\nSolved in "));
+        assert!(shown.contains("Part 1: \n24"));
+        assert!(shown.contains('#'));
+    }
+
+    #[test]
+    fn shows_errors_with_their_location() {
+        let frame = Frame::solve(
+            &crate::day01::Day01,
+            "elves",
+            &Ok("1000\nlots\n".to_owned()),
+        );
+        assert_eq!(frame.drawing, None);
+        assert!(frame.to_string().contains("--> elves:2:1"));
+    }
+}